@@ -0,0 +1,1064 @@
+use crate::audit;
+use crate::cache;
+use crate::hooks;
+use crate::policy;
+use crate::tz;
+#[cfg(feature = "gui")]
+use crate::versions;
+use crate::webhook;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::Certificate;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{CertificateError, DigitallySignedStruct, OtherError, SignatureScheme};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::error::Error as _;
+use std::collections::VecDeque;
+use std::fmt;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::Instant;
+
+const API_BASE: &str = "https://api.short.io";
+const STATS_BASE: &str = "https://statistics.short.io";
+const DOMAINS_TTL: Duration = Duration::hours(1);
+const LINKS_TTL: Duration = Duration::minutes(2);
+const FOLDERS_TTL: Duration = Duration::minutes(30);
+const STATS_TTL: Duration = Duration::minutes(1);
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CreateLinkRequest {
+    #[serde(rename = "originalURL")]
+    pub original_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cloaking: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "passwordContact")]
+    pub password_contact: Option<bool>,
+    #[serde(rename = "allowDuplicates")]
+    pub allow_duplicates: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "clicksLimit")]
+    pub clicks_limit: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "redirectType")]
+    pub redirect_type: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "expiresAt", default, with = "chrono::serde::ts_milliseconds_option")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl CreateLinkRequest {
+    /// A request with the app's defaults, used by the bulk tools.
+    pub fn simple(original_url: String, domain: Option<String>) -> Self {
+        Self {
+            original_url,
+            path: None,
+            domain,
+            cloaking: None,
+            password: None,
+            password_contact: None,
+            allow_duplicates: false,
+            clicks_limit: None,
+            redirect_type: Some(301),
+            tags: Some(vec!["shortyio".to_string()]),
+            title: None,
+            expires_at: None,
+        }
+    }
+}
+
+/// Partial update for an existing link; unset fields are left untouched.
+#[cfg(feature = "gui")]
+#[derive(Serialize, Default)]
+pub struct UpdateLinkRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "originalURL")]
+    pub original_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "clicksLimit")]
+    pub clicks_limit: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "expiresAt", with = "chrono::serde::ts_milliseconds_option")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Preview image for share cards, overriding the destination's.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Default)]
+pub struct LinkResponse {
+    #[serde(rename = "idString", default)]
+    pub id: String,
+    #[serde(rename = "shortURL")]
+    pub short_url: String,
+    #[serde(rename = "originalURL")]
+    pub original_url: String,
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    #[serde(default)]
+    pub path: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(rename = "createdAt", default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    #[serde(rename = "expiresAt", default, deserialize_with = "flexible_timestamp")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(rename = "FolderId", default)]
+    pub folder_id: Option<String>,
+}
+
+/// short.io sends some timestamps as milliseconds and others as ISO strings.
+fn flexible_timestamp<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Millis(i64),
+        Text(String),
+    }
+    Ok(match Option::<Raw>::deserialize(deserializer)? {
+        Some(Raw::Millis(millis)) => DateTime::from_timestamp_millis(millis),
+        Some(Raw::Text(text)) => DateTime::parse_from_rfc3339(&text).ok().map(|at| at.with_timezone(&Utc)),
+        None => None,
+    })
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Folder {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+struct FolderList {
+    #[serde(rename = "linkFolders", default)]
+    folders: Vec<Folder>,
+}
+
+/// Where a domain sends visitors of unknown and expired links. `None`
+/// falls back to short.io's own pages.
+#[cfg(feature = "gui")]
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct DomainFallbacks {
+    #[serde(rename = "notFoundRedirect", default)]
+    pub not_found_url: Option<String>,
+    #[serde(rename = "expiredRedirect", default)]
+    pub expired_url: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Domain {
+    #[serde(default)]
+    pub id: u64,
+    pub hostname: String,
+}
+
+/// One page of a domain's links; `count` is the total across all pages.
+#[derive(Deserialize, Clone)]
+pub struct LinkPage {
+    pub links: Vec<LinkResponse>,
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    #[serde(default)]
+    pub count: Option<u64>,
+    #[serde(rename = "nextPageToken", default)]
+    pub next_page_token: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Default)]
+pub struct LinkStats {
+    #[serde(rename = "totalClicks", default)]
+    pub total_clicks: u64,
+    #[serde(rename = "humanClicks", default)]
+    pub human_clicks: u64,
+    #[serde(rename = "clickStatistics", default)]
+    pub click_statistics: ClickStatistics,
+}
+
+/// Chart data for the requested period; the first dataset holds clicks.
+#[derive(Deserialize, Clone, Default)]
+pub struct ClickStatistics {
+    #[serde(default)]
+    pub datasets: Vec<ClickDataset>,
+}
+
+#[derive(Deserialize, Clone, Default)]
+pub struct ClickDataset {
+    #[serde(default)]
+    pub data: Vec<ClickPoint>,
+}
+
+/// `x` is an ISO timestamp; `y` comes back as either a number or a string.
+#[derive(Deserialize, Clone)]
+pub struct ClickPoint {
+    pub x: String,
+    pub y: serde_json::Value,
+}
+
+impl LinkStats {
+    /// Clicks per day, oldest first.
+    pub fn daily(&self) -> Vec<(NaiveDate, u64)> {
+        let Some(dataset) = self.click_statistics.datasets.first() else {
+            return Vec::new();
+        };
+        dataset
+            .data
+            .iter()
+            .filter_map(|point| {
+                let date = NaiveDate::parse_from_str(point.x.get(..10)?, "%Y-%m-%d").ok()?;
+                let clicks = match &point.y {
+                    serde_json::Value::Number(n) => n.as_u64()?,
+                    serde_json::Value::String(s) => s.parse().ok()?,
+                    _ => return None,
+                };
+                Some((date, clicks))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub enum ApiError {
+    Request(reqwest::Error),
+    Status { status: reqwest::StatusCode, body: String },
+    Parse(reqwest::Error),
+    Json(serde_json::Error),
+    /// The client could not be set up, e.g. an unreadable CA file.
+    Tls(String),
+    /// The destination is on a deny list; nothing was sent.
+    Blocked(String),
+    /// The pre-submit script failed or refused the link; nothing was sent.
+    Hook(String),
+    /// short.io presented a certificate other than the pinned one, with this
+    /// SHA-256 fingerprint; nothing was sent.
+    PinMismatch(String),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Request(e) if is_certificate_error(e) => write!(
+                f,
+                "TLS certificate validation failed: {}. Behind an inspecting proxy, set a CA certificate.",
+                e
+            ),
+            ApiError::Request(e) => write!(f, "Request failed: {}", e),
+            ApiError::Status { status, body } => write!(f, "API error {}: {}", status, body),
+            ApiError::Parse(e) => write!(f, "Failed to parse response: {}", e),
+            ApiError::Json(e) => write!(f, "Failed to parse response: {}", e),
+            ApiError::Tls(message) => write!(f, "TLS setup failed: {}", message),
+            ApiError::Blocked(message) => write!(f, "{}", message),
+            ApiError::Hook(message) => write!(f, "Hook script: {}", message),
+            ApiError::PinMismatch(presented) => write!(
+                f,
+                "short.io presented certificate {}, which doesn't match the pinned one. Nothing was sent.",
+                presented
+            ),
+        }
+    }
+}
+
+/// reqwest only says "error sending request", so look for the TLS cause.
+fn is_certificate_error(error: &reqwest::Error) -> bool {
+    let mut source = error.source();
+    while let Some(cause) = source {
+        if cause.to_string().to_lowercase().contains("certificate") {
+            return true;
+        }
+        source = cause.source();
+    }
+    false
+}
+
+impl ApiError {
+    /// TLS failed on the certificate, typically an inspecting proxy.
+    #[cfg(feature = "gui")]
+    pub fn is_certificate(&self) -> bool {
+        matches!(self, ApiError::Request(e) if is_certificate_error(e))
+    }
+
+    /// short.io asked us to slow down.
+    #[cfg(feature = "gui")]
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self, ApiError::Status { status, .. } if *status == reqwest::StatusCode::TOO_MANY_REQUESTS)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// User agent and extra headers some corporate gateways insist on. Set from
+/// the active profile and picked up by every client created afterwards.
+#[derive(Clone)]
+pub struct RequestOptions {
+    pub user_agent: String,
+    pub headers: Vec<(String, String)>,
+    /// Extra trusted CA, PEM or DER, for proxies that re-sign traffic.
+    pub ca_cert: String,
+    /// SHA-256 fingerprint, or a PEM or DER file, of the only certificate
+    /// accepted for short.io. Replaces every root, `ca_cert` included.
+    pub pinned_cert: String,
+}
+
+static REQUEST_OPTIONS: RwLock<RequestOptions> = RwLock::new(RequestOptions {
+    user_agent: String::new(),
+    headers: Vec::new(),
+    ca_cert: String::new(),
+    pinned_cert: String::new(),
+});
+
+pub fn configure(options: RequestOptions) {
+    *REQUEST_OPTIONS.write().unwrap() = options;
+    *HTTP.write().unwrap() = None;
+}
+
+/// One client for every request until the options change, so links reuse a
+/// pooled connection to short.io instead of paying for DNS, TCP and TLS each
+/// time. Its requests all run on `RUNTIME`.
+static HTTP: RwLock<Option<Result<reqwest::Client, String>>> = RwLock::new(None);
+/// How long idle connections and looked-up addresses are kept.
+const KEEP_ALIVE: std::time::Duration = std::time::Duration::from_secs(90);
+const DNS_TTL: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+/// Longest a request may take, set on the client so a timed-out request is
+/// over, not still running on `RUNTIME`, before a create is retried.
+const TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+const CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+static DNS: Mutex<Vec<(String, Instant, Vec<SocketAddr>)>> = Mutex::new(Vec::new());
+
+/// Drives every connection in the shared pool. A connection belongs to the
+/// runtime that opened it, and the short-lived runtimes callers block on
+/// would take their connections down with them.
+static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+
+fn runtime() -> &'static tokio::runtime::Runtime {
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("shortyio-http")
+            .enable_all()
+            .build()
+            .expect("Failed to start the HTTP runtime")
+    })
+}
+
+/// Looks up api.short.io and opens a connection to it in the background, so
+/// the first link doesn't wait on DNS, TCP and TLS.
+#[cfg(feature = "gui")]
+pub fn warm_up() {
+    runtime().spawn(async {
+        let host = API_BASE.trim_start_matches("https://").to_string();
+        if let Err(e) = lookup(host).await {
+            elog!("Failed to look up api.short.io: {}", e);
+            return;
+        }
+        // Any answer leaves the connection in the pool; a failure shows in
+        // the connectivity indicator
+        let _ = ApiClient::new(String::new()).probe().await;
+    });
+}
+
+/// The addresses of `host`, from `DNS` while they're fresh.
+async fn lookup(host: String) -> Result<Vec<SocketAddr>, Box<dyn std::error::Error + Send + Sync>> {
+    let cached = DNS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(name, at, _)| *name == host && at.elapsed() < DNS_TTL)
+        .map(|(_, _, addrs)| addrs.clone());
+    if let Some(addrs) = cached {
+        return Ok(addrs);
+    }
+    let name = host.clone();
+    // The connector fills in the port
+    let addrs: Vec<SocketAddr> = tokio::task::spawn_blocking(move || (name.as_str(), 0).to_socket_addrs().map(Iterator::collect)).await??;
+    let mut cache = DNS.lock().unwrap();
+    cache.retain(|(name, _, _)| *name != host);
+    cache.push((host, Instant::now(), addrs.clone()));
+    Ok(addrs)
+}
+
+/// The system resolver with its answers remembered for `DNS_TTL`, so a new
+/// connection doesn't wait on a lookup either.
+struct CachedDns;
+
+impl Resolve for CachedDns {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs = lookup(host).await?;
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+fn shared_http() -> Result<reqwest::Client, String> {
+    if let Some(http) = HTTP.read().unwrap().as_ref() {
+        return http.clone();
+    }
+    HTTP.write().unwrap().get_or_insert_with(build_http).clone()
+}
+
+/// Requests left in short.io's rate-limit window, as of the latest response
+/// that said.
+#[cfg(feature = "gui")]
+#[derive(Clone, Copy)]
+pub struct Quota {
+    pub remaining: u64,
+    pub limit: Option<u64>,
+}
+
+/// The latest quota seen, with the `audit::key_id` of the account it's for.
+#[cfg(feature = "gui")]
+static QUOTA: Mutex<Option<(String, Quota)>> = Mutex::new(None);
+
+/// The account's remaining quota, once a response has reported it.
+#[cfg(feature = "gui")]
+pub fn quota(api_key: &str) -> Option<Quota> {
+    let key = audit::key_id(api_key);
+    QUOTA.lock().unwrap().as_ref().filter(|(owner, _)| *owner == key).map(|(_, quota)| *quota)
+}
+
+/// How long short.io took to answer recent requests, so slowness can be
+/// put down to the network or to the app.
+#[cfg(feature = "gui")]
+#[derive(Clone, Copy)]
+pub struct Latency {
+    pub last: std::time::Duration,
+    pub median: std::time::Duration,
+    pub samples: usize,
+}
+
+const LATENCY_SAMPLES: usize = 20;
+/// Requests slower than this are logged.
+pub const SLOW_REQUEST: std::time::Duration = std::time::Duration::from_secs(2);
+
+static LATENCIES: Mutex<VecDeque<std::time::Duration>> = Mutex::new(VecDeque::new());
+
+/// Latency of the most recent requests, once there has been one.
+#[cfg(feature = "gui")]
+pub fn latency() -> Option<Latency> {
+    let latencies = LATENCIES.lock().unwrap();
+    let last = *latencies.back()?;
+    let mut sorted: Vec<_> = latencies.iter().copied().collect();
+    sorted.sort();
+    Some(Latency {
+        last,
+        median: sorted[sorted.len() / 2],
+        samples: sorted.len(),
+    })
+}
+
+trait SendTimed {
+    /// `send` on the shared runtime, noting how long the response took to
+    /// arrive.
+    async fn send_timed(self) -> Result<reqwest::Response, ApiError>;
+}
+
+impl SendTimed for reqwest::RequestBuilder {
+    async fn send_timed(self) -> Result<reqwest::Response, ApiError> {
+        let (client, request) = self.build_split();
+        let request = request.map_err(ApiError::Request)?;
+        let (method, path) = (request.method().clone(), request.url().path().to_string());
+        let started = Instant::now();
+        let response = runtime()
+            .spawn(async move { client.execute(request).await })
+            .await
+            .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+            .map_err(request_error)?;
+        let elapsed = started.elapsed();
+        if elapsed >= SLOW_REQUEST {
+            elog!("{} {} took {} ms", method, path, elapsed.as_millis());
+        }
+        let mut latencies = LATENCIES.lock().unwrap();
+        if latencies.len() == LATENCY_SAMPLES {
+            latencies.pop_front();
+        }
+        latencies.push_back(elapsed);
+        Ok(response)
+    }
+}
+
+fn load_certificates(path: &str) -> Result<Vec<Certificate>, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+    let certificates = if bytes.starts_with(b"-----BEGIN") {
+        Certificate::from_pem_bundle(&bytes)
+    } else {
+        Certificate::from_der(&bytes).map(|certificate| vec![certificate])
+    };
+    certificates.map_err(|e| format!("invalid certificate in {}: {}", path, e))
+}
+
+fn build_http() -> Result<reqwest::Client, String> {
+    let options = REQUEST_OPTIONS.read().unwrap().clone();
+    let mut headers = HeaderMap::new();
+    for (name, value) in &options.headers {
+        match (HeaderName::from_bytes(name.trim().as_bytes()), HeaderValue::from_str(value.trim())) {
+            (Ok(name), Ok(value)) => {
+                headers.insert(name, value);
+            }
+            _ => elog!("Ignoring invalid request header {:?}", name),
+        }
+    }
+    let user_agent = if options.user_agent.trim().is_empty() {
+        concat!("shortyio/", env!("CARGO_PKG_VERSION")).to_string()
+    } else {
+        options.user_agent.trim().to_string()
+    };
+    let mut builder = reqwest::Client::builder()
+        .user_agent(user_agent)
+        .default_headers(headers)
+        .dns_resolver(Arc::new(CachedDns))
+        .timeout(TIMEOUT)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .pool_idle_timeout(KEEP_ALIVE)
+        .tcp_keepalive(KEEP_ALIVE)
+        .http2_keep_alive_interval(KEEP_ALIVE)
+        .http2_keep_alive_while_idle(true);
+    let pin = options.pinned_cert.trim();
+    if !pin.is_empty() {
+        builder = builder.use_preconfigured_tls(pinned_tls(pin)?);
+    } else if !options.ca_cert.trim().is_empty() {
+        for certificate in load_certificates(options.ca_cert.trim())? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// TLS that accepts nothing but the pinned certificate.
+fn pinned_tls(pin: &str) -> Result<rustls::ClientConfig, String> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let verifier = PinnedCert {
+        fingerprint: pin_fingerprint(pin)?,
+        provider: provider.clone(),
+    };
+    let mut config = rustls::ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(verifier))
+        .with_no_client_auth();
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+/// The pin as a SHA-256 fingerprint, given as hex (colons optional) or
+/// taken from the first certificate in a PEM or DER file.
+fn pin_fingerprint(pin: &str) -> Result<[u8; 32], String> {
+    let hex: String = pin.chars().filter(|c| *c != ':').collect();
+    if hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        let mut fingerprint = [0; 32];
+        for (i, byte) in fingerprint.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|e| e.to_string())?;
+        }
+        return Ok(fingerprint);
+    }
+    let bytes = std::fs::read(pin).map_err(|e| format!("cannot read {}: {}", pin, e))?;
+    let certificate = if bytes.starts_with(b"-----BEGIN") {
+        CertificateDer::from_pem_slice(&bytes).map_err(|e| format!("invalid certificate in {}: {}", pin, e))?
+    } else {
+        CertificateDer::from(bytes)
+    };
+    Ok(Sha256::digest(&certificate).into())
+}
+
+/// Colon-separated uppercase hex, as browsers and `openssl x509 -fingerprint`
+/// show it.
+fn fingerprint_text(fingerprint: &[u8]) -> String {
+    fingerprint.iter().map(|byte| format!("{:02X}", byte)).collect::<Vec<_>>().join(":")
+}
+
+/// Accepts exactly the pinned leaf certificate, whoever issued it, so no CA
+/// can stand in for short.io. The handshake signature is still checked, so
+/// only the holder of the certificate's key gets through.
+#[derive(Debug)]
+struct PinnedCert {
+    fingerprint: [u8; 32],
+    provider: Arc<rustls::crypto::CryptoProvider>,
+}
+
+#[derive(Debug)]
+struct PinMismatch(String);
+
+impl fmt::Display for PinMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "certificate {} is not the pinned one", self.0)
+    }
+}
+
+impl std::error::Error for PinMismatch {}
+
+impl ServerCertVerifier for PinnedCert {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let presented: [u8; 32] = Sha256::digest(end_entity).into();
+        if presented == self.fingerprint {
+            return Ok(ServerCertVerified::assertion());
+        }
+        let mismatch = PinMismatch(fingerprint_text(&presented));
+        Err(rustls::Error::InvalidCertificate(CertificateError::Other(OtherError(Arc::new(mismatch)))))
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.provider.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.provider.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// A failed request as an `ApiError`, telling a pin mismatch apart from
+/// other TLS failures.
+fn request_error(error: reqwest::Error) -> ApiError {
+    match pin_mismatch(&error) {
+        Some(presented) => ApiError::PinMismatch(presented),
+        None => ApiError::Request(error),
+    }
+}
+
+fn pin_mismatch(error: &reqwest::Error) -> Option<String> {
+    let mut source = error.source();
+    while let Some(cause) = source {
+        let mismatch = cause.downcast_ref::<rustls::Error>().and_then(|e| match e {
+            rustls::Error::InvalidCertificate(CertificateError::Other(other)) => other.0.downcast_ref::<PinMismatch>(),
+            _ => None,
+        });
+        if let Some(mismatch) = mismatch {
+            return Some(mismatch.0.clone());
+        }
+        // io::Error skips the error it wraps when asked for its source
+        source = match cause.downcast_ref::<std::io::Error>().and_then(|e| e.get_ref()) {
+            Some(wrapped) => Some(wrapped),
+            None => cause.source(),
+        };
+    }
+    None
+}
+
+#[derive(Clone)]
+pub struct ApiClient {
+    /// Fails closed: a broken TLS setup must not fall back to the defaults.
+    http: Result<reqwest::Client, String>,
+    api_key: String,
+}
+
+impl ApiClient {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            http: shared_http(),
+            api_key: api_key.into(),
+        }
+    }
+
+    /// Tells apart the accounts stored data belongs to, see `audit::key_id`.
+    pub fn key_id(&self) -> String {
+        audit::key_id(&self.api_key)
+    }
+
+    /// Whether api.short.io answers at all, with the same TLS and proxy
+    /// settings as every other request. Any status counts.
+    #[cfg(feature = "gui")]
+    pub async fn probe(&self) -> Result<(), ApiError> {
+        self.http()?.head(API_BASE).send_timed().await?;
+        Ok(())
+    }
+
+    #[cfg(feature = "gui")]
+    fn note_quota(&self, headers: &HeaderMap) {
+        let number = |name: &str| headers.get(name)?.to_str().ok()?.trim().parse::<u64>().ok();
+        if let Some(remaining) = number("x-ratelimit-remaining") {
+            let quota = Quota {
+                remaining,
+                limit: number("x-ratelimit-limit"),
+            };
+            *QUOTA.lock().unwrap() = Some((self.key_id(), quota));
+        }
+    }
+
+    /// Drops cached link lists so the next listing comes from the server.
+    #[cfg(feature = "gui")]
+    pub fn forget_cached_links(&self) {
+        cache::forget(&self.api_key, "/api/links");
+    }
+
+    /// Drops cached click counts so the next ones come from the server.
+    #[cfg(feature = "gui")]
+    pub fn forget_cached_stats(&self) {
+        cache::forget(&self.api_key, "/statistics/link/");
+    }
+
+    fn http(&self) -> Result<&reqwest::Client, ApiError> {
+        self.http.as_ref().map_err(|message| ApiError::Tls(message.clone()))
+    }
+
+    pub async fn create_link(&self, request: &CreateLinkRequest) -> Result<LinkResponse, ApiError> {
+        // Scripts may call `http_post`, which blocks on a runtime of its own
+        let pending = request.clone();
+        let hooked = tokio::task::spawn_blocking(move || hooks::pre_submit(&pending))
+            .await
+            .map_err(|e| ApiError::Hook(e.to_string()))?
+            .map_err(|e| ApiError::Hook(format!("{:#}", e)))?;
+        let request = hooked.as_ref().unwrap_or(request);
+        if let Some(rule) = policy::check(&request.original_url) {
+            return Err(ApiError::Blocked(rule.message()));
+        }
+        let started = Utc::now();
+        let link = match self.post_link(request).await {
+            // The link may exist even though the response never arrived, so
+            // look before retrying rather than risk a duplicate or a taken path
+            Err(ApiError::Request(e)) if e.is_timeout() && request.domain.is_some() => match self.find_created(request, started).await {
+                Ok(Some(link)) => link,
+                Ok(None) => self.post_link(request).await?,
+                Err(_) => return Err(ApiError::Request(e)),
+            },
+            result => result?,
+        };
+        audit::record(&self.api_key, "create", &link.id, &link.short_url, &link.original_url);
+        webhook::notify(&link, request.tags.as_deref().unwrap_or_default()).await;
+        let (created, tags) = (link.clone(), request.tags.clone().unwrap_or_default());
+        let _ = tokio::task::spawn_blocking(move || hooks::post_create(&created, &tags)).await;
+        Ok(link)
+    }
+
+    async fn post_link(&self, request: &CreateLinkRequest) -> Result<LinkResponse, ApiError> {
+        let response = self
+            .http()?
+            .post(format!("{}/links", API_BASE))
+            .header("authorization", &self.api_key)
+            .header("accept", "application/json")
+            .json(request)
+            .send_timed()
+            .await?;
+        cache::forget(&self.api_key, "/api/links");
+        self.parse(response).await
+    }
+
+    /// The link a timed-out create made, if it made one: the link at the
+    /// custom path, or one for the same URL created since `started`.
+    async fn find_created(&self, request: &CreateLinkRequest, started: DateTime<Utc>) -> Result<Option<LinkResponse>, ApiError> {
+        let domain = request.domain.as_deref().unwrap_or_default();
+        let found = match &request.path {
+            Some(path) => self.expand_link(domain, path).await,
+            None => self.find_by_original_url(domain, &request.original_url).await,
+        };
+        let link = match found {
+            Ok(link) => link,
+            Err(ApiError::Status { status, .. }) if status == reqwest::StatusCode::NOT_FOUND => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        // Clock skew with short.io is forgiven by a minute
+        let recent = link.created_at.is_none_or(|at| at >= started - Duration::minutes(1));
+        let ours = link.original_url == request.original_url && (request.path.is_some() || recent);
+        Ok(ours.then_some(link))
+    }
+
+    /// The link on `domain` that points at `original_url`.
+    pub async fn find_by_original_url(&self, domain: &str, original_url: &str) -> Result<LinkResponse, ApiError> {
+        let response = self
+            .http()?
+            .get(format!("{}/links/by-original-url", API_BASE))
+            .query(&[("domain", domain), ("originalURL", original_url)])
+            .header("authorization", &self.api_key)
+            .header("accept", "application/json")
+            .send_timed()
+            .await?;
+        self.parse(response).await
+    }
+
+    #[cfg(feature = "gui")]
+    pub async fn update_link(&self, link_id: &str, request: &UpdateLinkRequest) -> Result<LinkResponse, ApiError> {
+        if let Some(rule) = request.original_url.as_deref().and_then(policy::check) {
+            return Err(ApiError::Blocked(rule.message()));
+        }
+        // Looked up first so the change can be reverted later
+        let previous = match request.original_url {
+            Some(_) => match self.get_link(link_id).await {
+                Ok(link) => Some(link.original_url),
+                Err(_) => versions::latest(link_id),
+            },
+            None => None,
+        };
+        let response = self
+            .http()?
+            .post(format!("{}/links/{}", API_BASE, link_id))
+            .header("authorization", &self.api_key)
+            .header("accept", "application/json")
+            .json(request)
+            .send_timed()
+            .await?;
+        cache::forget(&self.api_key, "/api/links");
+        let link: LinkResponse = self.parse(response).await?;
+        audit::record(&self.api_key, "update", link_id, &link.short_url, &link.original_url);
+        if let (Some(from), Some(to)) = (previous, &request.original_url) {
+            versions::record(link_id, &from, to);
+        }
+        Ok(link)
+    }
+
+    pub async fn get_link(&self, link_id: &str) -> Result<LinkResponse, ApiError> {
+        let response = self
+            .http()?
+            .get(format!("{}/links/{}", API_BASE, link_id))
+            .header("authorization", &self.api_key)
+            .header("accept", "application/json")
+            .send_timed()
+            .await?;
+        self.parse(response).await
+    }
+
+    /// Looks up an existing link by its domain and path.
+    pub async fn expand_link(&self, domain: &str, path: &str) -> Result<LinkResponse, ApiError> {
+        let response = self
+            .http()?
+            .get(format!("{}/links/expand", API_BASE))
+            .query(&[("domain", domain), ("path", path)])
+            .header("authorization", &self.api_key)
+            .header("accept", "application/json")
+            .send_timed()
+            .await?;
+        self.parse(response).await
+    }
+
+    pub async fn list_domains(&self) -> Result<Vec<Domain>, ApiError> {
+        self.get_cached(format!("{}/api/domains?limit=100&offset=0", API_BASE), DOMAINS_TTL)
+            .await
+    }
+
+    pub async fn find_domain(&self, hostname: &str) -> Result<Option<Domain>, ApiError> {
+        let domains = self.list_domains().await?;
+        Ok(domains.into_iter().find(|d| d.hostname.eq_ignore_ascii_case(hostname)))
+    }
+
+    #[cfg(feature = "gui")]
+    pub async fn domain_fallbacks(&self, domain_id: u64) -> Result<DomainFallbacks, ApiError> {
+        let response = self
+            .http()?
+            .get(format!("{}/domains/{}", API_BASE, domain_id))
+            .header("authorization", &self.api_key)
+            .header("accept", "application/json")
+            .send_timed()
+            .await?;
+        self.parse(response).await
+    }
+
+    #[cfg(feature = "gui")]
+    pub async fn set_domain_fallbacks(&self, domain_id: u64, fallbacks: &DomainFallbacks) -> Result<(), ApiError> {
+        let response = self
+            .http()?
+            .post(format!("{}/domains/settings/{}", API_BASE, domain_id))
+            .header("authorization", &self.api_key)
+            .header("accept", "application/json")
+            .json(fallbacks)
+            .send_timed()
+            .await?;
+        cache::forget(&self.api_key, "/api/domains");
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ApiError::Status { status, body });
+        }
+        Ok(())
+    }
+
+    pub async fn list_folders(&self, domain_id: u64) -> Result<Vec<Folder>, ApiError> {
+        let list: FolderList = self
+            .get_cached(format!("{}/links/folders/{}", API_BASE, domain_id), FOLDERS_TTL)
+            .await?;
+        Ok(list.folders)
+    }
+
+    pub async fn delete_link(&self, link_id: &str) -> Result<(), ApiError> {
+        let response = self
+            .http()?
+            .delete(format!("{}/links/{}", API_BASE, link_id))
+            .header("authorization", &self.api_key)
+            .header("accept", "application/json")
+            .send_timed()
+            .await?;
+        cache::forget(&self.api_key, "/api/links");
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ApiError::Status { status, body });
+        }
+        audit::record(&self.api_key, "delete", link_id, "", "");
+        Ok(())
+    }
+
+    /// Hides a link from listings without deleting it; `unarchive` undoes it.
+    pub async fn archive_link(&self, link_id: &str, short_url: &str) -> Result<(), ApiError> {
+        self.set_archived(link_id, short_url, true).await
+    }
+
+    #[cfg(feature = "gui")]
+    pub async fn unarchive_link(&self, link_id: &str, short_url: &str) -> Result<(), ApiError> {
+        self.set_archived(link_id, short_url, false).await
+    }
+
+    async fn set_archived(&self, link_id: &str, short_url: &str, archived: bool) -> Result<(), ApiError> {
+        let action = if archived { "archive" } else { "unarchive" };
+        let response = self
+            .http()?
+            .post(format!("{}/links/{}", API_BASE, action))
+            .header("authorization", &self.api_key)
+            .header("accept", "application/json")
+            .json(&serde_json::json!({ "link_id": link_id }))
+            .send_timed()
+            .await?;
+        cache::forget(&self.api_key, "/api/links");
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ApiError::Status { status, body });
+        }
+        audit::record(&self.api_key, action, link_id, short_url, "");
+        Ok(())
+    }
+
+    /// One page of a domain's links, newest first. Pass the previous page's
+    /// `next_page_token` to continue.
+    pub async fn list_links_page(&self, domain_id: u64, page_token: Option<&str>) -> Result<LinkPage, ApiError> {
+        let mut query = vec![("domain_id", domain_id.to_string()), ("limit", "150".to_string())];
+        if let Some(token) = page_token {
+            query.push(("pageToken", token.to_string()));
+        }
+        let url = reqwest::Url::parse_with_params(&format!("{}/api/links", API_BASE), &query)
+            .expect("API base URL is valid");
+        self.get_cached(url.to_string(), LINKS_TTL).await
+    }
+
+    /// Every link on a domain, following pagination to the end.
+    pub async fn list_links(&self, domain_id: u64) -> Result<Vec<LinkResponse>, ApiError> {
+        let mut links = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let page = self.list_links_page(domain_id, page_token.as_deref()).await?;
+            links.extend(page.links);
+            match page.next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => return Ok(links),
+            }
+        }
+    }
+
+    /// All-time click counts for one link.
+    #[cfg(feature = "gui")]
+    pub async fn link_stats(&self, link_id: &str) -> Result<LinkStats, ApiError> {
+        self.link_stats_for(link_id, "total").await
+    }
+
+    /// Click counts for a period such as `today`, `last7`, `last30` or `total`.
+    pub async fn link_stats_for(&self, link_id: &str, period: &str) -> Result<LinkStats, ApiError> {
+        // Days are bucketed in the display zone so charts match the calendar
+        let url = format!("{}/statistics/link/{}?period={}&tz={}", STATS_BASE, link_id, period, tz::zone().name());
+        self.get_cached(url, STATS_TTL).await
+    }
+
+    /// GET for listing and stats endpoints. Fresh cache entries are returned
+    /// without a request; stale ones are revalidated with their ETag when
+    /// there is one. Identical requests already in flight are waited for
+    /// rather than repeated.
+    async fn get_cached<T: DeserializeOwned>(&self, url: String, ttl: Duration) -> Result<T, ApiError> {
+        let key = cache::key(&self.api_key, &url);
+        let (cached, _flight) = loop {
+            let cached = cache::get(&key);
+            if let Some(value) = cached
+                .as_ref()
+                .filter(|entry| entry.is_fresh(ttl))
+                .and_then(|entry| serde_json::from_str(&entry.body).ok())
+            {
+                return Ok(value);
+            }
+            match cache::join(&key) {
+                // The other request's response is in the cache now, unless it failed
+                cache::Flight::Wait(done) => done.await,
+                cache::Flight::Lead(guard) => break (cached, guard),
+            }
+        };
+
+        let mut request = self
+            .http()?
+            .get(&url)
+            .header("authorization", &self.api_key)
+            .header("accept", "application/json");
+        if let Some(etag) = cached.as_ref().and_then(|entry| entry.etag.as_deref()) {
+            request = request.header("if-none-match", etag);
+        }
+        let response = request.send_timed().await?;
+        #[cfg(feature = "gui")]
+        self.note_quota(response.headers());
+
+        let status = response.status();
+        let unchanged = cached
+            .filter(|_| status == reqwest::StatusCode::NOT_MODIFIED)
+            .and_then(|entry| serde_json::from_str(&entry.body).ok());
+        if let Some(value) = unchanged {
+            cache::touch(&key);
+            return Ok(value);
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ApiError::Status { status, body });
+        }
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = response.text().await.map_err(ApiError::Parse)?;
+        let value = serde_json::from_str(&body).map_err(ApiError::Json)?;
+        cache::put(key, body, etag);
+        Ok(value)
+    }
+
+    async fn parse<T: DeserializeOwned>(&self, response: reqwest::Response) -> Result<T, ApiError> {
+        #[cfg(feature = "gui")]
+        self.note_quota(response.headers());
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ApiError::Status { status, body });
+        }
+        response.json::<T>().await.map_err(ApiError::Parse)
+    }
+}
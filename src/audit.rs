@@ -0,0 +1,127 @@
+use crate::storage;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+#[cfg(feature = "gui")]
+use std::path::Path;
+use std::sync::{Mutex, RwLock};
+
+const AUDIT_FILE: &str = "audit.jsonl";
+
+/// One change made through the app. Never rewritten once appended.
+#[derive(Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub at: DateTime<Utc>,
+    pub user: String,
+    pub profile: String,
+    /// First 16 hex digits of the API key's SHA-256.
+    pub key: String,
+    pub action: String,
+    pub link_id: String,
+    pub short_url: String,
+    pub original_url: String,
+    /// Signature of the previous line, chaining the log together.
+    pub prev: String,
+}
+
+/// A line of the log: the entry plus an HMAC-SHA256 over its compact JSON,
+/// keyed with the API key that made the change.
+#[derive(Serialize, Deserialize)]
+struct SignedLine {
+    entry: serde_json::Value,
+    signature: String,
+}
+
+static PROFILE: RwLock<String> = RwLock::new(String::new());
+/// Held while appending; caches the last signature once read.
+static LAST_SIGNATURE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Name recorded with every following entry.
+pub fn set_profile(name: &str) {
+    *PROFILE.write().unwrap() = name.to_string();
+}
+
+/// The profile set with `set_profile`.
+pub fn profile() -> String {
+    PROFILE.read().unwrap().clone()
+}
+
+/// First 16 hex digits of the API key's SHA-256, which tells keys apart
+/// without storing them.
+pub fn key_id(api_key: &str) -> String {
+    hex(&Sha256::digest(api_key.as_bytes())[..8])
+}
+
+pub fn record(api_key: &str, action: &str, link_id: &str, short_url: &str, original_url: &str) {
+    let mut last = LAST_SIGNATURE.lock().unwrap();
+    let prev = last.get_or_insert_with(read_last_signature).clone();
+    let entry = AuditEntry {
+        at: Utc::now(),
+        user: current_user(),
+        profile: PROFILE.read().unwrap().clone(),
+        key: key_id(api_key),
+        action: action.to_string(),
+        link_id: link_id.to_string(),
+        short_url: short_url.to_string(),
+        original_url: original_url.to_string(),
+        prev,
+    };
+    match append(api_key, &entry) {
+        Ok(signature) => *last = Some(signature),
+        Err(e) => elog!("Failed to write audit log: {}", e),
+    }
+}
+
+/// The OS account name.
+pub fn current_user() -> String {
+    std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_default()
+}
+
+fn append(api_key: &str, entry: &AuditEntry) -> anyhow::Result<String> {
+    let path = storage::data_path(AUDIT_FILE).ok_or_else(|| anyhow::anyhow!("Cannot determine data path"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let entry = serde_json::to_value(entry)?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(api_key.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(serde_json::to_string(&entry)?.as_bytes());
+    let signature = hex(&mac.finalize().into_bytes());
+    let line = serde_json::to_string(&SignedLine {
+        entry,
+        signature: signature.clone(),
+    })?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)?;
+    Ok(signature)
+}
+
+fn read_last_signature() -> String {
+    storage::data_path(AUDIT_FILE)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| {
+            let line = content.lines().rev().find(|line| !line.trim().is_empty())?;
+            serde_json::from_str::<SignedLine>(line).ok()
+        })
+        .map(|line| line.signature)
+        .unwrap_or_default()
+}
+
+/// Copies the log as-is, so every signature stays verifiable.
+#[cfg(feature = "gui")]
+pub fn export(dest: &Path) -> anyhow::Result<()> {
+    let path = storage::data_path(AUDIT_FILE).ok_or_else(|| anyhow::anyhow!("Cannot determine data path"))?;
+    let _guard = LAST_SIGNATURE.lock().unwrap();
+    if path.exists() {
+        fs::copy(path, dest)?;
+    } else {
+        fs::write(dest, "")?;
+    }
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
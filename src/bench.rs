@@ -0,0 +1,80 @@
+use crate::history::{HISTORY_FILE, HistoryEntry};
+use crate::storage;
+use chrono::{Duration, Utc};
+
+/// Number of generated links to open the history window with, logging how
+/// long frames take.
+pub const ENV_BENCH_HISTORY: &str = "SHORTYIO_BENCH_HISTORY";
+/// Frames summarized per log line.
+const FRAMES: usize = 300;
+
+/// How many links the history benchmark wants, when it's been asked for.
+/// It only runs against a scratch `SHORTYIO_CONFIG_DIR`, so generated links
+/// never end up in real history.
+pub fn history_size() -> Option<usize> {
+    let count = std::env::var(ENV_BENCH_HISTORY).ok()?.trim().parse().ok()?;
+    if std::env::var_os(storage::ENV_CONFIG_DIR).is_none() {
+        elog!("{} needs {} set to a scratch folder", ENV_BENCH_HISTORY, storage::ENV_CONFIG_DIR);
+        return None;
+    }
+    Some(count)
+}
+
+/// Writes `count` generated links as the history file, unless there is one.
+pub fn seed_history(count: usize) {
+    if storage::data_path(HISTORY_FILE).is_some_and(|path| path.exists()) {
+        return;
+    }
+    let now = Utc::now();
+    let entries: Vec<HistoryEntry> = (0..count)
+        .map(|i| HistoryEntry {
+            link_id: format!("bench{}", i),
+            short_url: format!("https://short.gy/bench-{}", i),
+            original_url: format!("https://example.com/articles/{}?utm_source=bench&utm_campaign=history", i),
+            title: Some(format!("Generated article number {}", i)),
+            tags: vec!["shortyio".to_string(), format!("batch-{}", i % 20)],
+            created_at: now - Duration::minutes(i as i64),
+            clicks_limit: None,
+            renewed: false,
+            deleted_remotely: false,
+        })
+        .collect();
+    if let Err(e) = storage::save_json(HISTORY_FILE, &entries) {
+        elog!("Failed to write benchmark history: {}", e);
+    }
+}
+
+/// Collects per-frame CPU time and summarizes it every `FRAMES` frames.
+pub struct FrameTimes {
+    entries: usize,
+    samples: Vec<f32>,
+}
+
+impl FrameTimes {
+    pub fn new(entries: usize) -> Self {
+        Self {
+            entries,
+            samples: Vec::with_capacity(FRAMES),
+        }
+    }
+
+    /// Adds one frame's time in seconds, returning a summary line once
+    /// enough frames have been seen.
+    pub fn record(&mut self, seconds: f32) -> Option<String> {
+        self.samples.push(seconds * 1000.0);
+        if self.samples.len() < FRAMES {
+            return None;
+        }
+        let mut samples = std::mem::take(&mut self.samples);
+        samples.sort_by(f32::total_cmp);
+        let percentile = |p: usize| samples[(samples.len() - 1) * p / 100];
+        Some(format!(
+            "History window, {} links: median {:.2} ms, p95 {:.2} ms, max {:.2} ms over {} frames",
+            self.entries,
+            percentile(50),
+            percentile(95),
+            samples[samples.len() - 1],
+            FRAMES
+        ))
+    }
+}
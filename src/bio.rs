@@ -0,0 +1,91 @@
+use crate::pins::PinnedLink;
+use crate::publish::Target;
+use crate::storage;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+const BIO_FILE: &str = "bio.json";
+
+/// A "link in bio" page made from the pinned links, and where it's published.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct BioPage {
+    pub title: String,
+    pub about: String,
+    pub target: Target,
+    /// Where the published page can be reached, which the short link points at.
+    pub public_url: String,
+    /// Custom path for the short link.
+    pub path: String,
+    pub short_url: String,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+impl BioPage {
+    pub fn load() -> Self {
+        storage::load_json(BIO_FILE).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        if let Err(e) = storage::save_json(BIO_FILE, self) {
+            elog!("Failed to save link-in-bio page: {}", e);
+        }
+    }
+
+    /// A standalone page with one button per pinned link, readable on phones
+    /// where most bio links are opened.
+    pub fn html(&self, pins: &[PinnedLink]) -> String {
+        let title = if self.title.trim().is_empty() { "Links" } else { self.title.trim() };
+        let links: String = pins
+            .iter()
+            .map(|pin| {
+                let label = if pin.label.trim().is_empty() { &pin.short_url } else { &pin.label };
+                format!(
+                    "    <a href=\"{}\">{}</a>\n",
+                    escape(&pin.utm.apply(&pin.short_url)),
+                    escape(label.trim())
+                )
+            })
+            .collect();
+        let about = if self.about.trim().is_empty() {
+            String::new()
+        } else {
+            format!("    <p>{}</p>\n", escape(self.about.trim()))
+        };
+        format!(
+            "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+  <meta charset=\"utf-8\">
+  <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">
+  <title>{title}</title>
+  <style>
+    body {{ margin: 0; font-family: system-ui, sans-serif; background: #f4f5f7; color: #222; }}
+    main {{ max-width: 480px; margin: 0 auto; padding: 48px 20px; text-align: center; }}
+    h1 {{ font-size: 1.5em; margin: 0 0 8px; }}
+    p {{ color: #555; margin: 0 0 24px; }}
+    a {{ display: block; margin: 12px 0; padding: 14px; border-radius: 10px; background: #fff;
+         color: #1a73e8; text-decoration: none; font-weight: 600; box-shadow: 0 1px 3px rgba(0,0,0,.12); }}
+    a:hover {{ background: #eef3fd; }}
+  </style>
+</head>
+<body>
+  <main>
+    <h1>{title}</h1>
+{about}{links}  </main>
+</body>
+</html>
+",
+            title = escape(title),
+            about = about,
+            links = links,
+        )
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,210 @@
+use anyhow::Result;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+#[derive(Clone)]
+pub struct Bookmark {
+    pub title: String,
+    pub url: String,
+    pub tags: Vec<String>,
+}
+
+/// A folder from the export together with every bookmark beneath it,
+/// including those in subfolders.
+pub struct BookmarkFolder {
+    pub label: String,
+    pub bookmarks: Vec<Bookmark>,
+}
+
+#[derive(Default)]
+struct Node {
+    name: String,
+    links: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+/// Reads a Chrome/Firefox JSON backup or a Netscape-style HTML export and
+/// returns its folders flattened in document order.
+pub fn load(path: &Path) -> Result<Vec<BookmarkFolder>> {
+    let content = fs::read_to_string(path)?;
+    let root = if content.trim_start().starts_with('{') {
+        let value: Value = serde_json::from_str(&content)?;
+        parse_json(&value)
+    } else {
+        parse_html(&content)
+    };
+
+    let mut folders = Vec::new();
+    flatten(&root, &[], &mut folders);
+    folders.retain(|f| !f.bookmarks.is_empty());
+    if folders.is_empty() {
+        anyhow::bail!("No bookmarks found in {}", path.display());
+    }
+    Ok(folders)
+}
+
+fn parse_json(value: &Value) -> Node {
+    // Chrome keeps its top-level folders under "roots"
+    if let Some(roots) = value.get("roots").and_then(Value::as_object) {
+        return Node {
+            name: "Bookmarks".to_string(),
+            links: Vec::new(),
+            children: roots.values().filter(|v| v.is_object()).map(json_node).collect(),
+        };
+    }
+    let mut node = json_node(value);
+    if node.name.is_empty() {
+        node.name = "Bookmarks".to_string();
+    }
+    node
+}
+
+fn json_node(value: &Value) -> Node {
+    let name = value
+        .get("name")
+        .or_else(|| value.get("title"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let mut node = Node {
+        name,
+        ..Node::default()
+    };
+    for child in value.get("children").and_then(Value::as_array).into_iter().flatten() {
+        // Chrome uses "url", Firefox uses "uri"
+        let url = child.get("url").or_else(|| child.get("uri")).and_then(Value::as_str);
+        match url {
+            Some(url) => {
+                let title = child
+                    .get("name")
+                    .or_else(|| child.get("title"))
+                    .and_then(Value::as_str)
+                    .unwrap_or(url);
+                node.links.push((title.to_string(), url.to_string()));
+            }
+            None if child.get("children").is_some() => node.children.push(json_node(child)),
+            None => {}
+        }
+    }
+    node
+}
+
+fn parse_html(content: &str) -> Node {
+    let mut stack = vec![Node {
+        name: "Bookmarks".to_string(),
+        ..Node::default()
+    }];
+    // Whether each open <DL> started a folder (the outermost one does not)
+    let mut lists: Vec<bool> = Vec::new();
+    let mut pending_folder: Option<String> = None;
+    let mut rest = content;
+
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('>') else { break };
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+        let name = tag
+            .split(|c: char| c.is_whitespace())
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        match name.as_str() {
+            "h3" => {
+                let text_end = rest.find('<').unwrap_or(rest.len());
+                pending_folder = Some(unescape(rest[..text_end].trim()));
+            }
+            "dl" => match pending_folder.take() {
+                Some(folder) => {
+                    stack.push(Node {
+                        name: folder,
+                        ..Node::default()
+                    });
+                    lists.push(true);
+                }
+                None => lists.push(false),
+            },
+            "/dl" => {
+                if lists.pop() == Some(true) && stack.len() > 1 {
+                    let folder = stack.pop().unwrap();
+                    stack.last_mut().unwrap().children.push(folder);
+                }
+            }
+            "a" => {
+                if let Some(href) = attribute(tag, "href") {
+                    let text_end = rest.find('<').unwrap_or(rest.len());
+                    let title = unescape(rest[..text_end].trim());
+                    let title = if title.is_empty() { href.clone() } else { title };
+                    stack.last_mut().unwrap().links.push((title, href));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    while stack.len() > 1 {
+        let folder = stack.pop().unwrap();
+        stack.last_mut().unwrap().children.push(folder);
+    }
+    stack.pop().unwrap()
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let start = lower.find(&format!("{}=\"", name))? + name.len() + 2;
+    let end = tag[start..].find('"')? + start;
+    Some(unescape(&tag[start..end]))
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+fn flatten(node: &Node, parents: &[String], out: &mut Vec<BookmarkFolder>) {
+    let mut path = parents.to_vec();
+    path.push(node.name.clone());
+
+    let mut bookmarks = Vec::new();
+    collect(node, &[node.name.clone()], &mut bookmarks);
+    out.push(BookmarkFolder {
+        label: path.join(" / "),
+        bookmarks,
+    });
+
+    for child in &node.children {
+        flatten(child, &path, out);
+    }
+}
+
+fn collect(node: &Node, folders: &[String], out: &mut Vec<Bookmark>) {
+    for (title, url) in &node.links {
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            continue;
+        }
+        out.push(Bookmark {
+            title: title.clone(),
+            url: url.clone(),
+            tags: folders.iter().map(|f| slug(f)).filter(|t| !t.is_empty()).collect(),
+        });
+    }
+    for child in &node.children {
+        let mut nested = folders.to_vec();
+        nested.push(child.name.clone());
+        collect(child, &nested, out);
+    }
+}
+
+/// Turns a folder name into a tag: lowercase words joined by dashes.
+pub fn slug(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
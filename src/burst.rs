@@ -0,0 +1,178 @@
+use crate::api::{ApiClient, CreateLinkRequest};
+use crate::history::{History, HistoryEntry};
+use crate::poster;
+use chrono::Utc;
+use eframe::egui;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Gap between creates, well inside short.io's rate limit so a line of
+/// people checking in never trips it.
+const SPACING: Duration = Duration::from_millis(250);
+/// First wait after short.io rate-limits a create anyway; doubled each time
+/// in a row.
+const BACKOFF: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Clone)]
+pub enum Status {
+    Queued,
+    Created(String),
+    Failed(String),
+}
+
+/// One check-in, in the order they were entered.
+#[derive(Clone)]
+pub struct Entry {
+    pub original_url: String,
+    pub path: String,
+    pub status: Status,
+    /// Why the poster couldn't be saved, when the link itself was created.
+    pub poster_error: Option<String>,
+}
+
+struct Queued {
+    index: usize,
+    api_key: String,
+    request: CreateLinkRequest,
+    poster_dir: Option<PathBuf>,
+}
+
+#[derive(Default)]
+struct State {
+    entries: Vec<Entry>,
+    queue: VecDeque<Queued>,
+    /// Set while waiting out a rate limit.
+    throttled_until: Option<Instant>,
+    started: bool,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    ready: Condvar,
+}
+
+/// Creates burst-mode links one at a time in the background, so entering
+/// the next one never waits on the last.
+#[derive(Clone, Default)]
+pub struct Burst {
+    shared: Arc<Shared>,
+}
+
+impl Burst {
+    /// Queues a link; with `poster_dir`, a QR poster PDF named after the
+    /// path is saved there once it's created.
+    pub fn add(&self, api_key: &str, request: CreateLinkRequest, poster_dir: Option<PathBuf>, history: &History, ctx: &egui::Context) {
+        let mut state = self.shared.state.lock().unwrap();
+        let index = state.entries.len();
+        state.entries.push(Entry {
+            original_url: request.original_url.clone(),
+            path: request.path.clone().unwrap_or_default(),
+            status: Status::Queued,
+            poster_error: None,
+        });
+        state.queue.push_back(Queued {
+            index,
+            api_key: api_key.to_string(),
+            request,
+            poster_dir,
+        });
+        if !state.started {
+            state.started = true;
+            self.spawn_worker(history.clone(), ctx.clone());
+        }
+        self.shared.ready.notify_one();
+    }
+
+    /// Every entry this session, newest first.
+    pub fn entries(&self) -> Vec<Entry> {
+        self.shared.state.lock().unwrap().entries.iter().rev().cloned().collect()
+    }
+
+    pub fn waiting(&self) -> usize {
+        self.shared.state.lock().unwrap().queue.len()
+    }
+
+    pub fn throttled(&self) -> bool {
+        self.shared.state.lock().unwrap().throttled_until.is_some_and(|until| until > Instant::now())
+    }
+
+    fn spawn_worker(&self, history: History, ctx: egui::Context) {
+        let shared = self.shared.clone();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            loop {
+                let queued = {
+                    let mut state = shared.state.lock().unwrap();
+                    loop {
+                        if let Some(next) = state.queue.pop_front() {
+                            break next;
+                        }
+                        state = shared.ready.wait(state).unwrap();
+                    }
+                };
+
+                let client = ApiClient::new(queued.api_key);
+                let mut backoff = BACKOFF;
+                let result = loop {
+                    match runtime.block_on(client.create_link(&queued.request)) {
+                        Err(e) if e.is_rate_limited() => {
+                            shared.state.lock().unwrap().throttled_until = Some(Instant::now() + backoff);
+                            ctx.request_repaint();
+                            std::thread::sleep(backoff);
+                            backoff = (backoff * 2).min(MAX_BACKOFF);
+                        }
+                        result => break result,
+                    }
+                };
+
+                let (status, poster_error) = match result {
+                    Ok(link) => {
+                        let poster_error = queued.poster_dir.and_then(|dir| {
+                            let name = if link.path.is_empty() { link.id.clone() } else { link.path.replace('/', "-") };
+                            poster::write(&dir.join(format!("{}.pdf", name)), &link.short_url, "", None, None)
+                                .err()
+                                .map(|e| e.to_string())
+                        });
+                        history.add(HistoryEntry {
+                            link_id: link.id,
+                            short_url: link.short_url.clone(),
+                            original_url: link.original_url,
+                            title: None,
+                            tags: queued.request.tags.clone().unwrap_or_default(),
+                            created_at: Utc::now(),
+                            clicks_limit: None,
+                            renewed: false,
+                            deleted_remotely: false,
+                        });
+                        (Status::Created(link.short_url), poster_error)
+                    }
+                    Err(e) => (Status::Failed(e.to_string()), None),
+                };
+                {
+                    let mut state = shared.state.lock().unwrap();
+                    state.throttled_until = None;
+                    let entry = &mut state.entries[queued.index];
+                    entry.status = status;
+                    entry.poster_error = poster_error;
+                }
+                ctx.request_repaint();
+                std::thread::sleep(SPACING);
+            }
+        });
+    }
+}
+
+/// The path for check-in `number`: the first run of `#` in `pattern`
+/// becomes the number padded to that many digits, so `booth-###` gives
+/// `booth-007`. Without any `#`, the number goes on the end.
+pub fn path(pattern: &str, number: u32) -> String {
+    let Some(start) = pattern.find('#') else {
+        return format!("{}{}", pattern, number);
+    };
+    let width = pattern[start..].chars().take_while(|c| *c == '#').count();
+    format!("{}{:0width$}{}", &pattern[..start], number, &pattern[start + width..], width = width)
+}
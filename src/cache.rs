@@ -0,0 +1,146 @@
+use crate::storage;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+use tokio::sync::futures::OwnedNotified;
+
+const CACHE_FILE: &str = "api-cache.json";
+
+/// A raw listing response body and the validator needed to revalidate it.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CachedResponse {
+    pub body: String,
+    #[serde(default)]
+    pub etag: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl CachedResponse {
+    pub fn is_fresh(&self, ttl: Duration) -> bool {
+        Utc::now() - self.fetched_at < ttl
+    }
+}
+
+// Shared by every ApiClient, since each background thread builds its own
+static ENTRIES: Mutex<BTreeMap<String, CachedResponse>> = Mutex::new(BTreeMap::new());
+static ON_DISK: AtomicBool = AtomicBool::new(false);
+static IN_FLIGHT: Mutex<BTreeMap<String, Arc<Notify>>> = Mutex::new(BTreeMap::new());
+
+/// Whether this caller should make a request or wait for an identical one
+/// already under way, e.g. the stats prefetcher and a chart asking at once.
+pub enum Flight {
+    Lead(FlightGuard),
+    Wait(OwnedNotified),
+}
+
+/// Wakes waiting callers when the leading request finishes, however it ends.
+pub struct FlightGuard {
+    key: String,
+}
+
+impl Drop for FlightGuard {
+    fn drop(&mut self) {
+        if let Some(notify) = IN_FLIGHT.lock().unwrap().remove(&self.key) {
+            notify.notify_waiters();
+        }
+    }
+}
+
+pub fn join(key: &str) -> Flight {
+    let mut in_flight = IN_FLIGHT.lock().unwrap();
+    match in_flight.get(key) {
+        // Created under the lock so the leader can't finish before we listen
+        Some(notify) => Flight::Wait(notify.clone().notified_owned()),
+        None => {
+            in_flight.insert(key.to_string(), Arc::new(Notify::new()));
+            Flight::Lead(FlightGuard { key: key.to_string() })
+        }
+    }
+}
+
+/// Turns the on-disk copy on or off. Turning it on picks up whatever an
+/// earlier session saved; turning it off removes the file.
+#[cfg(feature = "gui")]
+pub fn set_persistent(enabled: bool) {
+    let was_enabled = ON_DISK.swap(enabled, Ordering::Relaxed);
+    if enabled && !was_enabled {
+        if let Some(saved) = storage::load_json::<BTreeMap<String, CachedResponse>>(CACHE_FILE) {
+            let mut entries = ENTRIES.lock().unwrap();
+            for (key, entry) in saved {
+                entries.entry(key).or_insert(entry);
+            }
+        }
+    } else if !enabled {
+        if let Some(path) = storage::data_path(CACHE_FILE) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Cache key for a request. The API key is hashed so it never ends up in the
+/// cache file, while still keeping profiles apart.
+pub fn key(api_key: &str, url: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    api_key.hash(&mut hasher);
+    format!("{:016x} {}", hasher.finish(), url)
+}
+
+pub fn get(key: &str) -> Option<CachedResponse> {
+    ENTRIES.lock().unwrap().get(key).cloned()
+}
+
+pub fn put(key: String, body: String, etag: Option<String>) {
+    let mut entries = ENTRIES.lock().unwrap();
+    entries.insert(
+        key,
+        CachedResponse {
+            body,
+            etag,
+            fetched_at: Utc::now(),
+        },
+    );
+    persist(&entries);
+}
+
+/// Marks an entry as fresh again after the server confirmed it unchanged.
+pub fn touch(key: &str) {
+    let mut entries = ENTRIES.lock().unwrap();
+    if let Some(entry) = entries.get_mut(key) {
+        entry.fetched_at = Utc::now();
+        persist(&entries);
+    }
+}
+
+/// Drops every entry for `api_key` whose URL contains `fragment`, e.g. link
+/// listings after a link was created.
+pub fn forget(api_key: &str, fragment: &str) {
+    let owner = key(api_key, "");
+    let mut entries = ENTRIES.lock().unwrap();
+    let before = entries.len();
+    entries.retain(|key, _| !(key.starts_with(&owner) && key.contains(fragment)));
+    if entries.len() != before {
+        persist(&entries);
+    }
+}
+
+/// Drops every entry and overwrites the on-disk copy before deleting it,
+/// since cached link listings hold every destination.
+#[cfg(feature = "gui")]
+pub fn wipe() -> anyhow::Result<()> {
+    let mut entries = ENTRIES.lock().unwrap();
+    entries.clear();
+    storage::secure_delete(CACHE_FILE)
+}
+
+fn persist(entries: &BTreeMap<String, CachedResponse>) {
+    if !ON_DISK.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Err(e) = storage::save_json(CACHE_FILE, entries) {
+        elog!("Failed to save API cache: {}", e);
+    }
+}
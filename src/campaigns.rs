@@ -0,0 +1,64 @@
+use crate::storage;
+use crate::tz;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const CAMPAIGNS_FILE: &str = "campaigns.json";
+
+/// A local group of links with a clicks goal to reach between two dates.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Campaign {
+    pub name: String,
+    pub link_ids: Vec<String>,
+    pub goal: u64,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// Set once the user has been told the goal was reached.
+    #[serde(default)]
+    pub reached: bool,
+}
+
+impl Campaign {
+    /// Running total of clicks on the campaign's links for each day from the
+    /// start up to the end or today, whichever is first.
+    pub fn burn_up(&self, daily: impl Fn(&str) -> Option<Vec<(NaiveDate, u64)>>) -> Vec<(NaiveDate, u64)> {
+        let mut per_day: HashMap<NaiveDate, u64> = HashMap::new();
+        for link_id in &self.link_ids {
+            for (day, clicks) in daily(link_id).unwrap_or_default() {
+                if (self.start..=self.end).contains(&day) {
+                    *per_day.entry(day).or_default() += clicks;
+                }
+            }
+        }
+        let last = self.end.min(tz::today());
+        let mut total = 0;
+        self.start
+            .iter_days()
+            .take_while(|day| *day <= last)
+            .map(|day| {
+                total += per_day.get(&day).copied().unwrap_or_default();
+                (day, total)
+            })
+            .collect()
+    }
+}
+
+#[derive(Default)]
+pub struct Campaigns {
+    pub items: Vec<Campaign>,
+}
+
+impl Campaigns {
+    pub fn load() -> Self {
+        Self {
+            items: storage::load_json(CAMPAIGNS_FILE).unwrap_or_default(),
+        }
+    }
+
+    pub fn save(&self) {
+        if let Err(e) = storage::save_json(CAMPAIGNS_FILE, &self.items) {
+            elog!("Failed to save campaigns: {}", e);
+        }
+    }
+}
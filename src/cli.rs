@@ -0,0 +1,610 @@
+use crate::Config;
+use crate::api::{self, ApiClient, ApiError, CreateLinkRequest, LinkResponse};
+use crate::audit;
+use crate::mcp;
+use crate::policy;
+use crate::presets;
+use crate::webhook;
+use crate::history::{History, HistoryEntry};
+use crate::profiles::Profile;
+use crate::slugs;
+use crate::trash;
+use crate::tz;
+use crate::urls;
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+/// Exit codes shown in `--help` and the man page, since scripts rely on them.
+const EXIT_CODES: &str = "\
+Exit codes:
+  0  success
+  1  error       anything else
+  2  config      no config, profile, API key or domain
+  3  auth        API key rejected
+  4  network     short.io could not be reached
+  5  validation  bad input, nothing was sent
+  6  rejected    short.io refused the request";
+
+/// Create short.io links from the terminal. Run without arguments to open
+/// the app.
+#[derive(Parser)]
+#[command(name = "shortyio", version, about, after_help = EXIT_CODES)]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Print a shell completion script and exit
+    #[arg(long, value_name = "SHELL", exclusive = true)]
+    generate_completions: Option<clap_complete::Shell>,
+    /// Print a man page and exit
+    #[arg(long, exclusive = true)]
+    generate_man: bool,
+    /// Stable tab-separated output for launchers and scripts (see README)
+    #[arg(long, global = true)]
+    porcelain: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Shorten a URL and print the short link
+    Shorten {
+        url: String,
+        /// Custom path; the profile's path prefix is added in front
+        #[arg(long)]
+        path: Option<String>,
+        /// Tag the link; repeat for several tags
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+        /// Use this profile instead of the active one
+        #[arg(long)]
+        profile: Option<String>,
+    },
+    /// List links on the profile's domain, newest first
+    List {
+        /// Only links with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Only links in this folder
+        #[arg(long)]
+        folder: Option<String>,
+        /// Only links created on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<NaiveDate>,
+        /// Only links created on or before this date (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<NaiveDate>,
+        /// Print JSON instead of a table
+        #[arg(long)]
+        json: bool,
+        /// Use this profile instead of the active one
+        #[arg(long)]
+        profile: Option<String>,
+    },
+    /// Delete a link by short URL, path on the profile's domain, or link id
+    Delete {
+        link: String,
+        /// Treat LINK as a link id rather than a path
+        #[arg(long)]
+        id: bool,
+        /// Don't ask for confirmation
+        #[arg(long, short)]
+        yes: bool,
+        /// Delete right away instead of moving the link to the trash
+        #[arg(long)]
+        permanent: bool,
+        /// Use this profile instead of the active one
+        #[arg(long)]
+        profile: Option<String>,
+    },
+    /// Print click totals and a per-day table for a short link
+    Stats {
+        short_url: String,
+        /// Period to count clicks over
+        #[arg(long, value_enum, default_value_t = Period::Last30)]
+        period: Period,
+        /// Print JSON instead of a table
+        #[arg(long)]
+        json: bool,
+        /// Use this profile instead of the active one
+        #[arg(long)]
+        profile: Option<String>,
+    },
+    /// Serve shorten, list and stats to AI assistants as an MCP server on
+    /// stdin/stdout
+    Mcp {
+        /// Use this profile instead of the active one
+        #[arg(long)]
+        profile: Option<String>,
+    },
+}
+
+/// Exit codes by failure class, the same with or without `--porcelain`.
+/// Scripts and launcher integrations branch on these, so existing numbers
+/// must never change; see `EXIT_CODES`.
+#[derive(Clone, Copy)]
+enum Failure {
+    Other = 1,
+    Config = 2,
+    Auth = 3,
+    Network = 4,
+    Validation = 5,
+    Rejected = 6,
+}
+
+impl Failure {
+    fn of(error: &anyhow::Error) -> Self {
+        for cause in error.chain() {
+            if cause.is::<ConfigError>() {
+                return Failure::Config;
+            }
+            if cause.is::<ValidationError>() {
+                return Failure::Validation;
+            }
+            if let Some(api_error) = cause.downcast_ref::<ApiError>() {
+                return match api_error {
+                    ApiError::Request(_) | ApiError::PinMismatch(_) => Failure::Network,
+                    ApiError::Tls(_) => Failure::Config,
+                    ApiError::Blocked(_) | ApiError::Hook(_) => Failure::Validation,
+                    ApiError::Status { status, .. } if status.as_u16() == 401 || status.as_u16() == 403 => Failure::Auth,
+                    ApiError::Status { .. } | ApiError::Parse(_) | ApiError::Json(_) => Failure::Rejected,
+                };
+            }
+        }
+        Failure::Other
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Failure::Other => "error",
+            Failure::Config => "config",
+            Failure::Auth => "auth",
+            Failure::Network => "network",
+            Failure::Validation => "validation",
+            Failure::Rejected => "rejected",
+        }
+    }
+}
+
+/// Missing configuration, profile, API key or domain.
+#[derive(Debug)]
+struct ConfigError(String);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Input rejected before anything was sent to the API.
+#[derive(Debug)]
+struct ValidationError(String);
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+#[derive(Serialize)]
+pub(crate) struct ListedLink {
+    id: String,
+    short_url: String,
+    original_url: String,
+    tags: Vec<String>,
+    created_at: Option<DateTime<Utc>>,
+}
+
+/// Periods short.io counts clicks over.
+#[derive(Clone, Copy, ValueEnum)]
+pub(crate) enum Period {
+    Today,
+    Yesterday,
+    Last7,
+    Last30,
+    Week,
+    Month,
+    Total,
+}
+
+impl Period {
+    /// The name the API and the command line use.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Period::Today => "today",
+            Period::Yesterday => "yesterday",
+            Period::Last7 => "last7",
+            Period::Last30 => "last30",
+            Period::Week => "week",
+            Period::Month => "month",
+            Period::Total => "total",
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct StatsReport {
+    short_url: String,
+    period: String,
+    total_clicks: u64,
+    human_clicks: u64,
+    days: Vec<DayClicks>,
+}
+
+#[derive(Serialize)]
+struct DayClicks {
+    date: NaiveDate,
+    clicks: u64,
+}
+
+impl Cli {
+    /// Whether to run in the terminal instead of opening the window.
+    pub fn is_command(&self) -> bool {
+        self.command.is_some() || self.generate_completions.is_some() || self.generate_man
+    }
+}
+
+/// Parses the command line. Bad arguments exit as `validation` rather than
+/// with clap's 2, which `EXIT_CODES` gives to config problems.
+pub fn parse() -> Cli {
+    Cli::try_parse().unwrap_or_else(|e| {
+        let _ = e.print();
+        std::process::exit(if e.exit_code() == 0 { 0 } else { Failure::Validation as i32 })
+    })
+}
+
+/// Runs a terminal command and returns the process exit code.
+pub fn run(cli: Cli) -> i32 {
+    attach_console();
+
+    let result = if let Some(shell) = cli.generate_completions {
+        clap_complete::generate(shell, &mut Cli::command(), "shortyio", &mut io::stdout());
+        Ok(())
+    } else if cli.generate_man {
+        clap_mangen::Man::new(Cli::command())
+            .render(&mut io::stdout())
+            .context("Failed to write man page")
+    } else {
+        let porcelain = cli.porcelain;
+        match cli.command {
+            Some(Command::Shorten {
+                url,
+                path,
+                tags,
+                profile,
+            }) => shorten(url, path, tags, profile.as_deref(), porcelain),
+            Some(Command::List {
+                tag,
+                folder,
+                since,
+                until,
+                json,
+                profile,
+            }) => list(tag, folder, since, until, json, profile.as_deref(), porcelain),
+            Some(Command::Delete {
+                link,
+                id,
+                yes,
+                permanent,
+                profile,
+            }) => delete(link, id, yes, permanent, profile.as_deref(), porcelain),
+            Some(Command::Stats {
+                short_url,
+                period,
+                json,
+                profile,
+            }) => stats(short_url, period, json, profile.as_deref(), porcelain),
+            Some(Command::Mcp { profile }) => mcp::serve(profile),
+            None => Ok(()),
+        }
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            let failure = Failure::of(&e);
+            if cli.porcelain {
+                let message = format!("{:#}", e).replace(['\t', '\n', '\r'], " ");
+                eprintln!("{}\t{}", failure.name(), message);
+            } else {
+                eprintln!("Error: {:#}", e);
+            }
+            failure as i32
+        }
+    }
+}
+
+fn shorten(url: String, path: Option<String>, tags: Vec<String>, profile: Option<&str>, porcelain: bool) -> Result<()> {
+    let link = create(url, path, tags, profile)?;
+    if porcelain {
+        println!("{}\t{}", link.short_url, link.original_url);
+    } else {
+        println!("{}", link.short_url);
+    }
+    Ok(())
+}
+
+/// Creates a link with the profile's tags, naming rules and block lists
+/// applied, and records it in history.
+pub(crate) fn create(url: String, path: Option<String>, tags: Vec<String>, profile: Option<&str>) -> Result<LinkResponse> {
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        bail!(ValidationError(format!("{} is not an http(s) URL", url)));
+    }
+    let config = load_config()?;
+    let profile = select_profile(&config, profile)?;
+
+    let team = presets::cached(&profile.presets_url);
+    let mut all_tags = vec!["shortyio".to_string()];
+    for tag in team.tags.iter().chain(&tags) {
+        if !all_tags.contains(tag) {
+            all_tags.push(tag.clone());
+        }
+    }
+    if let Some(error) = slugs::check(path.as_deref().unwrap_or_default()) {
+        bail!(ValidationError(error));
+    }
+    for rules in [&config.naming, &team.naming] {
+        if let Some(error) = rules
+            .check_path(path.as_deref().unwrap_or_default())
+            .or_else(|| rules.check_tags(&all_tags))
+        {
+            bail!(ValidationError(error));
+        }
+    }
+
+    let domain = (!profile.domain.is_empty()).then(|| profile.domain.clone());
+    let mut request = CreateLinkRequest::simple(url, domain);
+    request.path = path.map(|path| profile.prefixed_path(&path));
+    request.tags = Some(all_tags);
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    let link = runtime.block_on(ApiClient::new(profile.api_key.clone()).create_link(&request))?;
+
+    History::load(config.retention()).add(HistoryEntry {
+        link_id: link.id.clone(),
+        short_url: link.short_url.clone(),
+        original_url: link.original_url.clone(),
+        title: None,
+        tags: request.tags.unwrap_or_default(),
+        created_at: Utc::now(),
+        clicks_limit: None,
+        renewed: false,
+        deleted_remotely: false,
+    });
+    Ok(link)
+}
+
+fn list(
+    tag: Option<String>,
+    folder: Option<String>,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    json: bool,
+    profile: Option<&str>,
+    porcelain: bool,
+) -> Result<()> {
+    let rows = find_links(tag, folder, since, until, profile)?;
+    if porcelain {
+        for row in &rows {
+            println!("{}\t{}", row.short_url, row.original_url);
+        }
+    } else if json {
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+    } else {
+        let width = rows.iter().map(|row| row.short_url.len()).max().unwrap_or(0);
+        for row in &rows {
+            let created = row.created_at.map(|at| at.format("%Y-%m-%d").to_string()).unwrap_or_default();
+            println!("{:<10}  {:<width$}  {}", created, row.short_url, row.original_url, width = width);
+        }
+    }
+    Ok(())
+}
+
+/// Links on the profile's domain, newest first, narrowed by the filters.
+pub(crate) fn find_links(
+    tag: Option<String>,
+    folder: Option<String>,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    profile: Option<&str>,
+) -> Result<Vec<ListedLink>> {
+    let config = load_config()?;
+    let profile = select_profile(&config, profile)?;
+    if profile.domain.is_empty() {
+        bail!(ConfigError(format!("Profile {} has no domain set", profile.name)));
+    }
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    let client = ApiClient::new(profile.api_key.clone());
+    let (links, folder_id) = runtime.block_on(async {
+        let domain = client
+            .find_domain(&profile.domain)
+            .await?
+            .ok_or_else(|| anyhow!(ConfigError(format!("Domain {} not found on this account", profile.domain))))?;
+        let folder_id = match &folder {
+            Some(name) => Some(
+                client
+                    .list_folders(domain.id)
+                    .await?
+                    .into_iter()
+                    .find(|f| f.name.eq_ignore_ascii_case(name))
+                    .map(|f| f.id)
+                    .ok_or_else(|| anyhow!(ValidationError(format!("No folder named {}", name))))?,
+            ),
+            None => None,
+        };
+        anyhow::Ok((client.list_links(domain.id).await?, folder_id))
+    })?;
+
+    let rows: Vec<ListedLink> = links
+        .into_iter()
+        .filter(|link| tag.as_ref().is_none_or(|tag| link.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))))
+        .filter(|link| folder_id.is_none() || link.folder_id == folder_id)
+        .filter(|link| {
+            let created = link.created_at.map(|at| at.date_naive());
+            since.is_none_or(|since| created.is_some_and(|c| c >= since))
+                && until.is_none_or(|until| created.is_some_and(|c| c <= until))
+        })
+        .map(|link| ListedLink {
+            id: link.id,
+            short_url: link.short_url,
+            original_url: link.original_url,
+            tags: link.tags,
+            created_at: link.created_at,
+        })
+        .collect();
+    Ok(rows)
+}
+
+fn delete(link: String, by_id: bool, yes: bool, permanent: bool, profile: Option<&str>, porcelain: bool) -> Result<()> {
+    let config = load_config()?;
+    let profile = select_profile(&config, profile)?;
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    let client = ApiClient::new(profile.api_key.clone());
+    let found = if by_id {
+        runtime.block_on(client.get_link(&link))?
+    } else {
+        let (domain, path) = if link.contains("://") {
+            (urls::host(&link), urls::path(&link).to_string())
+        } else if profile.domain.is_empty() {
+            bail!(ConfigError(format!("Profile {} has no domain set; pass a full short URL", profile.name)));
+        } else {
+            (profile.domain.clone(), link.trim_start_matches('/').to_string())
+        };
+        runtime.block_on(client.expand_link(&domain, &path))?
+    };
+    let label = &found.short_url;
+
+    // Launchers can't answer a prompt, so porcelain mode requires --yes
+    if !yes && (porcelain || !confirm(&format!("Delete {}?", label))?) {
+        bail!(ValidationError("Not confirmed; pass --yes to delete".to_string()));
+    }
+    if permanent {
+        runtime.block_on(client.delete_link(&found.id))?;
+    } else {
+        runtime.block_on(trash::trash(&client, &found.id, &found.short_url, &found.original_url))?;
+        if let Err(e) = runtime.block_on(trash::purge(&client, config.trash_days)) {
+            elog!("Failed to empty old links from the trash: {}", e);
+        }
+    }
+    if porcelain {
+        println!("{}", label);
+    } else if permanent {
+        println!("Deleted {}", label);
+    } else {
+        println!(
+            "Moved {} to the trash; it's deleted for good after {} days unless restored in Shortyio",
+            label, config.trash_days
+        );
+    }
+    Ok(())
+}
+
+fn confirm(question: &str) -> Result<bool> {
+    print!("{} [y/N] ", question);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+fn stats(short_url: String, period: Period, json: bool, profile: Option<&str>, porcelain: bool) -> Result<()> {
+    let report = stats_report(short_url, period, profile)?;
+    if porcelain {
+        println!("{}\t{}", report.total_clicks, report.human_clicks);
+    } else if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{} ({})", report.short_url, report.period);
+        println!("Clicks: {} (human: {})", report.total_clicks, report.human_clicks);
+        if !report.days.is_empty() {
+            println!();
+            println!("{:<12}{:>8}", "Date", "Clicks");
+            for day in &report.days {
+                println!("{:<12}{:>8}", day.date.to_string(), day.clicks);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Click totals and per-day clicks of a short link over a period.
+pub(crate) fn stats_report(short_url: String, period: Period, profile: Option<&str>) -> Result<StatsReport> {
+    let config = load_config()?;
+    let profile = select_profile(&config, profile)?;
+    let domain = urls::host(&short_url);
+    let path = urls::path(&short_url);
+    if domain.is_empty() || path.is_empty() {
+        bail!(ValidationError(format!("{} doesn't look like a short link", short_url)));
+    }
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    let client = ApiClient::new(profile.api_key);
+    let stats = runtime.block_on(async {
+        let link = client.expand_link(&domain, path).await?;
+        client.link_stats_for(&link.id, period.as_str()).await
+    })?;
+
+    Ok(StatsReport {
+        short_url,
+        period: period.as_str().to_string(),
+        total_clicks: stats.total_clicks,
+        human_clicks: stats.human_clicks,
+        days: stats
+            .daily()
+            .into_iter()
+            .map(|(date, clicks)| DayClicks { date, clicks })
+            .collect(),
+    })
+}
+
+fn load_config() -> Result<Config> {
+    Config::load().ok_or_else(|| anyhow!(ConfigError("No configuration found. Open Shortyio once to set your API key.".to_string())))
+}
+
+/// The named profile, or the active one when no name is given.
+fn select_profile(config: &Config, name: Option<&str>) -> Result<Profile> {
+    let profile = match name {
+        Some(name) => config
+            .profiles
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+            .cloned()
+            .map(Profile::with_env_overrides)
+            .ok_or_else(|| anyhow!(ConfigError(format!("No profile named {}", name))))?,
+        None => config.profile(),
+    };
+    if profile.api_key.is_empty() {
+        bail!(ConfigError(format!("Profile {} has no API key", profile.name)));
+    }
+    api::configure(profile.request_options());
+    audit::set_profile(&profile.name);
+    webhook::configure(&profile.webhook_url, &profile.name);
+    policy::set_local(config.blocked_destinations.clone());
+    tz::set(&config.time_zone);
+    policy::set_team(presets::cached(&profile.presets_url).blocked_domains);
+    Ok(profile)
+}
+
+/// Release builds on Windows are GUI apps with no console of their own, so
+/// borrow the one of the shell that started us.
+#[cfg(windows)]
+fn attach_console() {
+    unsafe extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
@@ -0,0 +1,196 @@
+use crate::tz;
+use arboard::{Clipboard, ImageData};
+use chrono::{DateTime, Utc};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// How long a closed app keeps serving its last copy on Linux.
+#[cfg(target_os = "linux")]
+const HOLD_AFTER_EXIT: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+/// Copies remembered for re-copying.
+const HISTORY_LEN: usize = 20;
+
+static PRIMARY_SELECTION: AtomicBool = AtomicBool::new(false);
+
+/// Something the app put on the clipboard this session.
+#[derive(Clone)]
+pub struct Copied {
+    pub content: Content,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+pub enum Content {
+    Text(String),
+    /// RGBA pixels, e.g. a QR code.
+    Image {
+        label: String,
+        width: usize,
+        height: usize,
+        rgba: Arc<Vec<u8>>,
+    },
+}
+
+impl Copied {
+    /// One line describing what was copied.
+    pub fn label(&self) -> String {
+        let label = match &self.content {
+            Content::Text(text) => text.lines().next().unwrap_or_default().to_string(),
+            Content::Image { label, .. } => format!("🖼 {}", label),
+        };
+        format!("{} · {}", tz::local(self.at).format("%H:%M"), label)
+    }
+
+    /// Puts it back on the clipboard, moving it to the top of the history.
+    pub fn recopy(&self) {
+        match &self.content {
+            Content::Text(text) => copy(text),
+            Content::Image {
+                label,
+                width,
+                height,
+                rgba,
+            } => copy_image(label, *width, *height, rgba.clone()),
+        }
+    }
+
+    fn same_as(&self, other: &Content) -> bool {
+        match (&self.content, other) {
+            (Content::Text(a), Content::Text(b)) => a == b,
+            (Content::Image { rgba: a, .. }, Content::Image { rgba: b, .. }) => a == b,
+            _ => false,
+        }
+    }
+}
+
+thread_local! {
+    // Kept alive for the whole session: on X11 and Wayland the copied text
+    // disappears as soon as its owning clipboard handle is dropped.
+    static CLIPBOARD: RefCell<Option<Clipboard>> = const { RefCell::new(None) };
+    static LAST_COPIED: RefCell<Option<String>> = const { RefCell::new(None) };
+    static HISTORY: RefCell<Vec<Copied>> = const { RefCell::new(Vec::new()) };
+}
+
+fn remember(content: Content) {
+    HISTORY.with_borrow_mut(|history| {
+        history.retain(|copied| !copied.same_as(&content));
+        history.insert(0, Copied { content, at: Utc::now() });
+        history.truncate(HISTORY_LEN);
+    });
+}
+
+/// What the app copied this session, newest first.
+pub fn history() -> Vec<Copied> {
+    HISTORY.with_borrow(|history| history.clone())
+}
+
+fn with_clipboard<R>(f: impl FnOnce(&mut Clipboard) -> Result<R, arboard::Error>) -> Option<Result<R, arboard::Error>> {
+    CLIPBOARD.with_borrow_mut(|clipboard| {
+        if clipboard.is_none() {
+            match Clipboard::new() {
+                Ok(opened) => *clipboard = Some(opened),
+                Err(e) => {
+                    elog!("Failed to open clipboard: {}", e);
+                    return None;
+                }
+            }
+        }
+        clipboard.as_mut().map(f)
+    })
+}
+
+/// Copies through arboard instead of egui's output, which some Wayland
+/// compositors ignore.
+pub fn copy(text: &str) {
+    match with_clipboard(|clipboard| clipboard.set_text(text)) {
+        Some(Ok(())) => {
+            LAST_COPIED.set(Some(text.to_string()));
+            remember(Content::Text(text.to_string()));
+        }
+        Some(Err(e)) => elog!("Failed to copy to clipboard: {}", e),
+        None => {}
+    }
+    if PRIMARY_SELECTION.load(Ordering::Relaxed) {
+        copy_primary(text);
+    }
+}
+
+/// Copies an RGBA image. `label` names it in the copy history.
+pub fn copy_image(label: &str, width: usize, height: usize, rgba: Arc<Vec<u8>>) {
+    let image = ImageData {
+        width,
+        height,
+        bytes: Cow::Borrowed(rgba.as_slice()),
+    };
+    match with_clipboard(|clipboard| clipboard.set_image(image)) {
+        Some(Ok(())) => {
+            // Nothing to hold after exit, which only serves text
+            LAST_COPIED.set(None);
+            remember(Content::Image {
+                label: label.to_string(),
+                width,
+                height,
+                rgba,
+            });
+        }
+        Some(Err(e)) => elog!("Failed to copy image to clipboard: {}", e),
+        None => {}
+    }
+}
+
+/// Also put copies into the primary selection, for middle-click paste.
+pub fn set_primary_selection(enabled: bool) {
+    PRIMARY_SELECTION.store(enabled, Ordering::Relaxed);
+}
+
+#[cfg(target_os = "linux")]
+fn copy_primary(text: &str) {
+    use arboard::{LinuxClipboardKind, SetExtLinux};
+
+    if let Some(Err(e)) = with_clipboard(|clipboard| clipboard.set().clipboard(LinuxClipboardKind::Primary).text(text)) {
+        elog!("Failed to set primary selection: {}", e);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn copy_primary(_text: &str) {}
+
+pub fn text() -> Option<String> {
+    with_clipboard(|clipboard| clipboard.get_text())?.ok()
+}
+
+/// Whether the clipboard can be opened and read; an empty one is fine.
+pub fn check() -> Result<(), String> {
+    match with_clipboard(|clipboard| clipboard.get_text()) {
+        None => Err("Couldn't open the clipboard".to_string()),
+        Some(Ok(_)) | Some(Err(arboard::Error::ContentNotAvailable)) => Ok(()),
+        Some(Err(e)) => Err(e.to_string()),
+    }
+}
+
+/// On Linux the clipboard empties when the app that owns it exits. When the
+/// last thing copied is still on the clipboard, keep serving it in the
+/// background until something else is copied or `HOLD_AFTER_EXIT` passes.
+#[cfg(target_os = "linux")]
+pub fn hold_after_exit() {
+    use arboard::SetExtLinux;
+
+    let Some(last) = LAST_COPIED.take() else {
+        return;
+    };
+    if text().as_deref() != Some(last.as_str()) {
+        return;
+    }
+    CLIPBOARD.set(None);
+    let deadline = std::time::Instant::now() + HOLD_AFTER_EXIT;
+    let result = Clipboard::new().and_then(|mut clipboard| clipboard.set().wait_until(deadline).text(last));
+    if let Err(e) = result {
+        elog!("Failed to keep clipboard after exit: {}", e);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn hold_after_exit() {}
@@ -0,0 +1,38 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+const POLL: Duration = Duration::from_secs(2);
+
+/// Modification time of the config file as of the last load or save by this
+/// app, so only outside edits count as changes.
+static SEEN: Mutex<Option<SystemTime>> = Mutex::new(None);
+
+/// Records the file's current state as this app's own.
+pub fn mark_seen(path: &Path) {
+    *SEEN.lock().unwrap() = modified(path);
+}
+
+/// Polls the config file and raises `config_changed` in the context's data
+/// whenever something else edits it, e.g. a script or a synced team copy.
+pub fn start(path: PathBuf, ctx: eframe::egui::Context) {
+    mark_seen(&path);
+    std::thread::spawn(move || {
+        loop {
+            std::thread::sleep(POLL);
+            let current = modified(&path);
+            let mut seen = SEEN.lock().unwrap();
+            if current.is_none() || current == *seen {
+                continue;
+            }
+            *seen = current;
+            drop(seen);
+            ctx.data_mut(|data| data.insert_temp(eframe::egui::Id::new("config_changed"), true));
+            ctx.request_repaint();
+        }
+    });
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
@@ -0,0 +1,91 @@
+use crate::api::ApiClient;
+use chrono::{DateTime, Utc};
+use eframe::egui;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+/// How often to check while online; offline, it's checked sooner so the
+/// status clears soon after the network comes back.
+const ONLINE_INTERVAL: Duration = Duration::from_secs(60);
+const OFFLINE_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum Connection {
+    #[default]
+    Unknown,
+    Online,
+    Offline,
+}
+
+#[derive(Default)]
+struct State {
+    connection: Connection,
+    /// When the connection last changed.
+    since: Option<DateTime<Utc>>,
+    error: Option<String>,
+    started: bool,
+    requested: bool,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+/// Keeps track of whether short.io can be reached, with a cheap request
+/// every minute, which also keeps the pooled connection from going idle.
+#[derive(Clone, Default)]
+pub struct Connectivity {
+    shared: Arc<Shared>,
+}
+
+impl Connectivity {
+    pub fn start(&self, ctx: &egui::Context) {
+        let mut state = self.shared.state.lock().unwrap();
+        if !state.started {
+            state.started = true;
+            self.spawn_worker(ctx.clone());
+        }
+    }
+
+    /// Checks again right away, e.g. after a request failed.
+    pub fn probe_now(&self) {
+        self.shared.state.lock().unwrap().requested = true;
+        self.shared.changed.notify_all();
+    }
+
+    pub fn connection(&self) -> Connection {
+        self.shared.state.lock().unwrap().connection
+    }
+
+    pub fn since(&self) -> Option<DateTime<Utc>> {
+        self.shared.state.lock().unwrap().since
+    }
+
+    /// Why the last check failed, while offline.
+    pub fn error(&self) -> Option<String> {
+        self.shared.state.lock().unwrap().error.clone()
+    }
+
+    fn spawn_worker(&self, ctx: egui::Context) {
+        let shared = self.shared.clone();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            loop {
+                let result = runtime.block_on(ApiClient::new(String::new()).probe());
+                let mut state = shared.state.lock().unwrap();
+                let connection = if result.is_ok() { Connection::Online } else { Connection::Offline };
+                if state.connection != connection {
+                    state.connection = connection;
+                    state.since = Some(Utc::now());
+                    ctx.request_repaint();
+                }
+                state.error = result.err().map(|e| e.to_string());
+                let interval = if connection == Connection::Online { ONLINE_INTERVAL } else { OFFLINE_INTERVAL };
+                let (mut state, _) = shared.changed.wait_timeout_while(state, interval, |state| !state.requested).unwrap();
+                state.requested = false;
+            }
+        });
+    }
+}
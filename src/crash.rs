@@ -0,0 +1,88 @@
+use crate::storage;
+use chrono::Local;
+use std::collections::VecDeque;
+use std::fs;
+#[cfg(feature = "gui")]
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const MAX_LOG_LINES: usize = 50;
+const PENDING_FILE: &str = "crash-pending";
+
+static RECENT: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Prints a log line to stderr and keeps it for the next crash report.
+pub fn record(line: String) {
+    eprintln!("{}", line);
+    if let Ok(mut recent) = RECENT.lock() {
+        if recent.len() == MAX_LOG_LINES {
+            recent.pop_front();
+        }
+        recent.push_back(format!("{} {}", Local::now().format("%H:%M:%S"), line));
+    }
+}
+
+/// Writes a report to the data directory whenever the app panics. The
+/// Windows build has no console, so without this panics vanish silently.
+pub fn install() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let backtrace = std::backtrace::Backtrace::force_capture();
+        let recent = RECENT
+            .lock()
+            .map(|recent| recent.iter().cloned().collect::<Vec<_>>().join("\n"))
+            .unwrap_or_default();
+        let report = format!(
+            "Shortyio {} crash report\nTime: {}\nOS: {} ({})\n\n{}\n\nBacktrace:\n{}\n\nRecent log:\n{}\n",
+            env!("CARGO_PKG_VERSION"),
+            Local::now().to_rfc3339(),
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            info,
+            backtrace,
+            recent
+        );
+
+        let name = format!("crash-{}.txt", Local::now().format("%Y%m%d-%H%M%S"));
+        if let (Some(path), Some(marker)) = (storage::data_path(&name), storage::data_path(PENDING_FILE)) {
+            if let Some(parent) = path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            if fs::write(&path, report).is_ok() {
+                let _ = fs::write(marker, path.to_string_lossy().as_bytes());
+            }
+        }
+
+        default_hook(info);
+    }));
+}
+
+/// The report left behind by a crash in a previous session, if any.
+#[cfg(feature = "gui")]
+pub fn pending_report() -> Option<PathBuf> {
+    let marker = storage::data_path(PENDING_FILE)?;
+    let path = PathBuf::from(fs::read_to_string(marker).ok()?.trim());
+    path.exists().then_some(path)
+}
+
+#[cfg(feature = "gui")]
+pub fn dismiss() {
+    if let Some(marker) = storage::data_path(PENDING_FILE) {
+        let _ = fs::remove_file(marker);
+    }
+}
+
+/// Opens a file with the platform's default application.
+#[cfg(feature = "gui")]
+pub fn open_path(path: &Path) {
+    let result = if cfg!(target_os = "windows") {
+        std::process::Command::new("cmd").arg("/C").arg("start").arg("").arg(path).spawn()
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("open").arg(path).spawn()
+    } else {
+        std::process::Command::new("xdg-open").arg(path).spawn()
+    };
+    if let Err(e) = result {
+        record(format!("Failed to open {}: {}", path.display(), e));
+    }
+}
@@ -0,0 +1,158 @@
+use crate::api::{ApiClient, ApiError};
+use crate::cache;
+use crate::clipboard;
+use crate::storage;
+use std::fs;
+use std::net::ToSocketAddrs;
+use std::path::Path;
+
+const API_HOST: &str = "api.short.io";
+
+/// One step of the diagnostic and how it went.
+#[derive(Clone)]
+pub struct Check {
+    pub name: &'static str,
+    /// What was found, or what went wrong.
+    pub outcome: Result<String, String>,
+    /// What to try when it failed.
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn passed(name: &'static str, found: impl Into<String>) -> Self {
+        Self {
+            name,
+            outcome: Ok(found.into()),
+            hint: None,
+        }
+    }
+
+    fn failed(name: &'static str, error: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            outcome: Err(error.into()),
+            hint: Some(hint.into()),
+        }
+    }
+
+    /// Not run because an earlier step it depends on failed.
+    fn skipped(name: &'static str, after: &str) -> Self {
+        Self {
+            name,
+            outcome: Err(format!("Skipped until {} works", after)),
+            hint: None,
+        }
+    }
+}
+
+/// The checks that touch the network or disk, in the order a request needs
+/// them. Blocks, so it belongs on a worker thread.
+pub async fn run(api_key: &str) -> Vec<Check> {
+    let mut checks = vec![dns()];
+    if checks[0].outcome.is_ok() {
+        checks.push(tls().await);
+    } else {
+        checks.push(Check::skipped("Secure connection", "name lookup"));
+    }
+    if checks[1].outcome.is_ok() {
+        checks.push(auth(api_key).await);
+    } else {
+        checks.push(Check::skipped("API key", "the secure connection"));
+    }
+    checks.push(writable());
+    checks
+}
+
+fn dns() -> Check {
+    const NAME: &str = "Name lookup";
+    match (API_HOST, 443).to_socket_addrs().map(|mut addresses| addresses.next()) {
+        Ok(Some(address)) => Check::passed(NAME, format!("{} is at {}", API_HOST, address.ip())),
+        Ok(None) => Check::failed(NAME, format!("{} has no addresses", API_HOST), dns_hint()),
+        Err(e) => Check::failed(NAME, e.to_string(), dns_hint()),
+    }
+}
+
+fn dns_hint() -> String {
+    "Check that you're online. A VPN, a captive Wi-Fi login page or a DNS filter can also block the lookup".to_string()
+}
+
+async fn tls() -> Check {
+    const NAME: &str = "Secure connection";
+    let error = match ApiClient::new(String::new()).probe().await {
+        Ok(()) => return Check::passed(NAME, format!("Connected to {} over TLS", API_HOST)),
+        Err(e) => e,
+    };
+    let hint = match &error {
+        ApiError::Tls(_) => {
+            "A CA or pinned certificate set in the profile's network settings couldn't be loaded; check the file path"
+        }
+        e if e.is_certificate() => {
+            "Something between you and short.io is inspecting TLS traffic. Set your company's CA certificate in the \
+             profile's network settings"
+        }
+        ApiError::PinMismatch(_) => {
+            "short.io didn't present the pinned certificate. If short.io renewed it, pin the new fingerprint; \
+             otherwise something is intercepting the connection"
+        }
+        ApiError::Request(e) if e.is_timeout() => "The connection timed out; a firewall or proxy may be blocking api.short.io",
+        _ => "A firewall or proxy may be blocking api.short.io. Proxies set in HTTPS_PROXY are used",
+    };
+    Check::failed(NAME, error.to_string(), hint)
+}
+
+async fn auth(api_key: &str) -> Check {
+    const NAME: &str = "API key";
+    if api_key.is_empty() {
+        return Check::failed(NAME, "No API key set", "Add your short.io secret API key in settings (⚙)");
+    }
+    // A cached answer would hide a key revoked since
+    cache::forget(api_key, "/api/domains");
+    match ApiClient::new(api_key).list_domains().await {
+        Ok(domains) => Check::passed(NAME, format!("Accepted, with {} domains", domains.len())),
+        Err(ApiError::Status { status, body }) if status.as_u16() == 401 || status.as_u16() == 403 => Check::failed(
+            NAME,
+            format!("Rejected ({}): {}", status, body),
+            "Copy the secret key again from short.io's Integrations & API page; public keys can't create links",
+        ),
+        Err(e) => Check::failed(NAME, e.to_string(), "short.io answered with an error; try again in a few minutes"),
+    }
+}
+
+fn writable() -> Check {
+    const NAME: &str = "Settings folder";
+    let hint = format!(
+        "Shortyio can't save settings here. Fix the folder's permissions, or set {} to a folder you can write to",
+        storage::ENV_CONFIG_DIR
+    );
+    let Some(config_dir) = storage::config_dir() else {
+        return Check::failed(NAME, "No usable settings folder", hint);
+    };
+    let data_dir = storage::data_path("").unwrap_or_else(|| config_dir.clone());
+    for dir in [&config_dir, &data_dir] {
+        if let Err(e) = try_write(dir) {
+            return Check::failed(NAME, format!("{}: {}", dir.display(), e), hint);
+        }
+    }
+    Check::passed(NAME, format!("{} is writable", config_dir.display()))
+}
+
+fn try_write(dir: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let probe = dir.join(".write-test");
+    fs::write(&probe, b"")?;
+    fs::remove_file(probe)
+}
+
+/// The clipboard belongs to the UI thread, so it's checked there.
+pub fn clipboard() -> Check {
+    const NAME: &str = "Clipboard";
+    match clipboard::check() {
+        Ok(()) => Check::passed(NAME, "Readable"),
+        Err(e) => Check::failed(
+            NAME,
+            e,
+            "Paste & Shorten and copying need clipboard access. On Linux, check that a clipboard manager or the \
+             Wayland data-control protocol is available; elsewhere, check privacy settings for clipboard access",
+        ),
+    }
+}
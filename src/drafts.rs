@@ -0,0 +1,95 @@
+use crate::audit;
+use crate::storage;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+const DRAFTS_FILE: &str = "drafts.json";
+
+/// A link composed without creating it, waiting for someone whose profile
+/// has an API key to approve it.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Draft {
+    pub id: String,
+    pub original_url: String,
+    /// Custom path without any profile prefix; the approver's prefix applies.
+    #[serde(default)]
+    pub path: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub author: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Draft {
+    pub fn new(original_url: String, path: String, tags: Vec<String>) -> Self {
+        let created_at = Utc::now();
+        let author = audit::current_user();
+        Self {
+            id: format!("{}-{}", author, created_at.format("%Y%m%d%H%M%S%f")),
+            original_url,
+            path,
+            tags,
+            author,
+            created_at,
+        }
+    }
+}
+
+/// Drafts on this machine, whether composed here or imported for review.
+/// Exported files can be passed around or kept in a shared folder.
+#[derive(Default)]
+pub struct Drafts {
+    items: Vec<Draft>,
+}
+
+impl Drafts {
+    pub fn load() -> Self {
+        Self {
+            items: storage::load_json(DRAFTS_FILE).unwrap_or_default(),
+        }
+    }
+
+    pub fn items(&self) -> &[Draft] {
+        &self.items
+    }
+
+    pub fn add(&mut self, draft: Draft) {
+        self.items.push(draft);
+        self.persist();
+    }
+
+    /// Drops a draft once it has been created or rejected.
+    pub fn remove(&mut self, id: &str) {
+        self.items.retain(|draft| draft.id != id);
+        self.persist();
+    }
+
+    pub fn export(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(&self.items)?)?;
+        Ok(())
+    }
+
+    /// Adds drafts from an exported file, skipping ones already here.
+    /// Returns how many were new.
+    pub fn import(&mut self, path: &Path) -> Result<usize> {
+        let imported: Vec<Draft> = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let before = self.items.len();
+        for draft in imported {
+            if !self.items.iter().any(|existing| existing.id == draft.id) {
+                self.items.push(draft);
+            }
+        }
+        self.persist();
+        Ok(self.items.len() - before)
+    }
+
+    fn persist(&self) {
+        if let Err(e) = storage::save_json(DRAFTS_FILE, &self.items) {
+            elog!("Failed to save drafts: {}", e);
+        }
+    }
+}
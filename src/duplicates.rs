@@ -0,0 +1,101 @@
+use crate::api::{ApiClient, LinkResponse, UpdateLinkRequest};
+use crate::trash;
+use anyhow::{Result, anyhow};
+use std::collections::{BTreeMap, HashMap};
+use tokio::task::JoinSet;
+
+/// Click counts fetched at a time while scanning.
+const CONCURRENT_STATS: usize = 8;
+
+/// Short links on one domain that all point at the same destination, most
+/// clicked first.
+#[derive(Clone)]
+pub struct DuplicateGroup {
+    pub original_url: String,
+    pub links: Vec<(LinkResponse, u64)>,
+}
+
+/// Destinations compared loosely: scheme and host are case-insensitive and a
+/// trailing slash doesn't matter.
+fn normalize(url: &str) -> String {
+    let url = url.trim().trim_end_matches('/');
+    match url.split_once("://") {
+        Some((scheme, rest)) => {
+            let (host, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+            format!("{}://{}{}", scheme.to_lowercase(), host.to_lowercase(), path)
+        }
+        None => url.to_string(),
+    }
+}
+
+pub async fn find(client: &ApiClient, hostname: &str) -> Result<Vec<DuplicateGroup>> {
+    let domain = client
+        .find_domain(hostname)
+        .await?
+        .ok_or_else(|| anyhow!("Domain {} not found on this account", hostname))?;
+
+    let mut by_destination: BTreeMap<String, Vec<LinkResponse>> = BTreeMap::new();
+    for link in client.list_links(domain.id).await? {
+        by_destination.entry(normalize(&link.original_url)).or_default().push(link);
+    }
+
+    let duplicates: Vec<Vec<LinkResponse>> = by_destination.into_values().filter(|links| links.len() > 1).collect();
+    let ids: Vec<String> = duplicates.iter().flatten().map(|link| link.id.clone()).collect();
+    let mut clicks = HashMap::new();
+    for batch in ids.chunks(CONCURRENT_STATS) {
+        let mut requests = JoinSet::new();
+        for id in batch {
+            let (client, id) = (client.clone(), id.clone());
+            requests.spawn(async move {
+                let stats = client.link_stats(&id).await;
+                (id, stats)
+            });
+        }
+        while let Some(joined) = requests.join_next().await {
+            let (id, stats) = joined?;
+            clicks.insert(id, stats?.total_clicks);
+        }
+    }
+
+    let mut groups = Vec::new();
+    for links in duplicates {
+        let mut counted: Vec<(LinkResponse, u64)> = links
+            .into_iter()
+            .map(|link| {
+                let count = clicks.get(&link.id).copied().unwrap_or(0);
+                (link, count)
+            })
+            .collect();
+        counted.sort_by(|a, b| b.1.cmp(&a.1));
+        groups.push(DuplicateGroup {
+            original_url: counted[0].0.original_url.clone(),
+            links: counted,
+        });
+    }
+    Ok(groups)
+}
+
+/// Tidies a group down to `survivor`, moving the other links to the trash.
+/// With `redirect` they are first pointed at the survivor's short URL, so
+/// restoring one from the trash sends its visitors to the kept link.
+pub async fn merge(client: &ApiClient, group: &DuplicateGroup, survivor: &str, redirect: bool) -> Result<usize> {
+    let target = group
+        .links
+        .iter()
+        .find(|(link, _)| link.id == survivor)
+        .map(|(link, _)| link.short_url.clone())
+        .ok_or_else(|| anyhow!("The link to keep is not in this group"))?;
+    let mut merged = 0;
+    for (link, _) in group.links.iter().filter(|(link, _)| link.id != survivor) {
+        if redirect {
+            let request = UpdateLinkRequest {
+                original_url: Some(target.clone()),
+                ..Default::default()
+            };
+            client.update_link(&link.id, &request).await?;
+        }
+        trash::trash(client, &link.id, &link.short_url, &link.original_url).await?;
+        merged += 1;
+    }
+    Ok(merged)
+}
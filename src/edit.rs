@@ -0,0 +1,137 @@
+use crate::api::{LinkResponse, UpdateLinkRequest};
+use crate::tz;
+use crate::versions::{self, Version};
+use crate::when;
+use chrono::{DateTime, Utc};
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// An existing link open for editing, with the form's current values.
+pub struct LinkEdit {
+    pub link: LinkResponse,
+    pub original_url: String,
+    pub tags: String,
+    /// Typed like the new-link expiration, e.g. "in 2 weeks".
+    pub expires: String,
+    /// Showing the changes for confirmation instead of the form.
+    pub reviewing: bool,
+    pub saving: bool,
+    pub error: Option<String>,
+    /// Earlier destination changes, newest first.
+    pub versions: Vec<Version>,
+}
+
+/// One field whose value the edit would change.
+pub struct Change {
+    pub field: &'static str,
+    pub old: String,
+    pub new: String,
+}
+
+impl LinkEdit {
+    pub fn new(link: LinkResponse) -> Self {
+        Self {
+            original_url: link.original_url.clone(),
+            tags: link.tags.join(", "),
+            expires: link
+                .expires_at
+                .map(|at| tz::local(at).format(TIME_FORMAT).to_string())
+                .unwrap_or_default(),
+            versions: versions::of(&link.id),
+            link,
+            reviewing: false,
+            saving: false,
+            error: None,
+        }
+    }
+
+    /// The update to send, or why the form can't be saved.
+    pub fn request(&self) -> Result<UpdateLinkRequest, String> {
+        let original_url = self.original_url.trim();
+        if !original_url.starts_with("http://") && !original_url.starts_with("https://") {
+            return Err("Destination must start with http:// or https://".to_string());
+        }
+        let tags = self.tag_list();
+        let unchanged = self.link.expires_at.map(|at| tz::local(at).format(TIME_FORMAT).to_string());
+        let expires_at = match self.expires.trim() {
+            text if Some(text) == unchanged.as_deref() => None,
+            "" if self.link.expires_at.is_some() => {
+                return Err("short.io can't remove an expiration once set; pick a later time instead".to_string());
+            }
+            "" => None,
+            text => match when::parse(text) {
+                Some(at) if at <= Utc::now() => return Err("Expiration time must be in the future".to_string()),
+                Some(at) => Some(at),
+                None => return Err(format!("Couldn't read the expiration time \"{}\"", text)),
+            },
+        };
+        Ok(UpdateLinkRequest {
+            original_url: (original_url != self.link.original_url).then(|| original_url.to_string()),
+            tags: (tags != self.link.tags).then_some(tags),
+            expires_at,
+            ..Default::default()
+        })
+    }
+
+    /// What saving would change, field by field.
+    pub fn changes(&self) -> Result<Vec<Change>, String> {
+        let request = self.request()?;
+        let mut changes = Vec::new();
+        if let Some(original_url) = request.original_url {
+            changes.push(Change {
+                field: "Destination",
+                old: self.link.original_url.clone(),
+                new: original_url,
+            });
+        }
+        if let Some(tags) = request.tags {
+            changes.push(Change {
+                field: "Tags",
+                old: self.link.tags.join(", "),
+                new: tags.join(", "),
+            });
+        }
+        if let Some(expires_at) = request.expires_at {
+            let format = |at: DateTime<Utc>| tz::local(at).format(TIME_FORMAT).to_string();
+            changes.push(Change {
+                field: "Expires",
+                old: self.link.expires_at.map(format).unwrap_or_else(|| "Never".to_string()),
+                new: format(expires_at),
+            });
+        }
+        Ok(changes)
+    }
+
+    fn tag_list(&self) -> Vec<String> {
+        self.tags
+            .split(',')
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect()
+    }
+}
+
+/// Splits two versions of a value into the part they share at the start,
+/// the differing middles and the shared end, so a one-letter typo in a long
+/// URL stands out.
+pub fn split_difference<'a>(old: &'a str, new: &'a str) -> (&'a str, &'a str, &'a str, &'a str) {
+    let prefix = old
+        .char_indices()
+        .zip(new.chars())
+        .find(|((_, a), b)| a != b)
+        .map(|((i, _), _)| i)
+        .unwrap_or(old.len().min(new.len()));
+    let suffix = old[prefix..]
+        .chars()
+        .rev()
+        .zip(new[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum::<usize>();
+    (
+        &old[..prefix],
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+        &old[old.len() - suffix..],
+    )
+}
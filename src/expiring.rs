@@ -0,0 +1,37 @@
+use crate::api::{ApiClient, LinkResponse, UpdateLinkRequest};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, Utc};
+
+/// How far "extend" pushes an expiration.
+pub const EXTENSION: Duration = Duration::days(30);
+
+/// Links on the domain that expire within `days`, soonest first.
+pub async fn fetch(client: &ApiClient, hostname: &str, days: i64) -> Result<Vec<LinkResponse>> {
+    let domain = client
+        .find_domain(hostname)
+        .await?
+        .ok_or_else(|| anyhow!("Domain {} not found on this account", hostname))?;
+
+    let now = Utc::now();
+    let until = now + Duration::days(days);
+    let mut links: Vec<LinkResponse> = client
+        .list_links(domain.id)
+        .await?
+        .into_iter()
+        .filter(|link| link.expires_at.is_some_and(|at| at >= now && at <= until))
+        .collect();
+    links.sort_by_key(|link| link.expires_at);
+    Ok(links)
+}
+
+/// Moves the link's expiration `EXTENSION` past the later of now and its
+/// current expiration. Returns the new expiration.
+pub async fn extend(client: &ApiClient, link: &LinkResponse) -> Result<DateTime<Utc>> {
+    let expires_at = link.expires_at.unwrap_or_default().max(Utc::now()) + EXTENSION;
+    let request = UpdateLinkRequest {
+        expires_at: Some(expires_at),
+        ..Default::default()
+    };
+    client.update_link(&link.id, &request).await?;
+    Ok(expires_at)
+}
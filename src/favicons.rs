@@ -0,0 +1,110 @@
+use crate::storage;
+use eframe::egui;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const SIZE: u32 = 32;
+
+enum Entry {
+    Loading,
+    Missing,
+    Loaded(egui::ColorImage),
+    Texture(egui::TextureHandle),
+}
+
+/// Favicons of short domains, fetched once and kept in the data directory
+/// so switching profiles shows the right brand straight away.
+#[derive(Clone, Default)]
+pub struct Favicons {
+    entries: Arc<Mutex<HashMap<String, Entry>>>,
+}
+
+impl Favicons {
+    /// The favicon of `hostname`, starting a fetch the first time it's asked for.
+    pub fn get(&self, hostname: &str, ctx: &egui::Context) -> Option<egui::TextureHandle> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.entry(hostname.to_string()).or_insert_with(|| {
+            match cache_path(hostname).and_then(|path| image::open(path).ok()) {
+                Some(image) => Entry::Loaded(color_image(image)),
+                None => {
+                    self.spawn_fetch(hostname.to_string(), ctx.clone());
+                    Entry::Loading
+                }
+            }
+        });
+        if let Entry::Loaded(image) = entry {
+            let texture = ctx.load_texture(format!("favicon-{}", hostname), image.clone(), Default::default());
+            *entry = Entry::Texture(texture);
+        }
+        match entry {
+            Entry::Texture(texture) => Some(texture.clone()),
+            _ => None,
+        }
+    }
+
+    fn spawn_fetch(&self, hostname: String, ctx: egui::Context) {
+        let entries = self.entries.clone();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let entry = match runtime.block_on(fetch(&hostname)) {
+                Ok(image) => {
+                    let image = image.resize(SIZE, SIZE, image::imageops::FilterType::Lanczos3);
+                    if let Some(path) = cache_path(&hostname) {
+                        if let Some(parent) = path.parent() {
+                            let _ = std::fs::create_dir_all(parent);
+                        }
+                        if let Err(e) = image.save_with_format(&path, image::ImageFormat::Png) {
+                            elog!("Failed to cache favicon of {}: {}", hostname, e);
+                        }
+                    }
+                    Entry::Loaded(color_image(image))
+                }
+                Err(e) => {
+                    elog!("No favicon for {}: {}", hostname, e);
+                    Entry::Missing
+                }
+            };
+            entries.lock().unwrap().insert(hostname, entry);
+            ctx.request_repaint();
+        });
+    }
+}
+
+/// Tries the short domain itself, then the site its root redirects to, since
+/// short domains rarely serve a favicon of their own.
+async fn fetch(hostname: &str) -> anyhow::Result<image::DynamicImage> {
+    let http = reqwest::Client::builder()
+        .timeout(TIMEOUT)
+        .user_agent(concat!("shortyio/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    if let Ok(image) = fetch_image(&http, &format!("https://{}/favicon.ico", hostname)).await {
+        return Ok(image);
+    }
+    let root = http.get(format!("https://{}/", hostname)).send().await?;
+    let mut url = root.url().clone();
+    url.set_path("/favicon.ico");
+    url.set_query(None);
+    fetch_image(&http, url.as_str()).await
+}
+
+async fn fetch_image(http: &reqwest::Client, url: &str) -> anyhow::Result<image::DynamicImage> {
+    let bytes = http.get(url).send().await?.error_for_status()?.bytes().await?;
+    Ok(image::load_from_memory(&bytes)?)
+}
+
+fn color_image(image: image::DynamicImage) -> egui::ColorImage {
+    let rgba = image.to_rgba8();
+    let size = [rgba.width() as usize, rgba.height() as usize];
+    egui::ColorImage::from_rgba_unmultiplied(size, rgba.as_raw())
+}
+
+/// Where the favicon for `hostname` is cached, whether or not it exists yet.
+pub fn cache_path(hostname: &str) -> Option<std::path::PathBuf> {
+    let name: String = hostname
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .collect();
+    storage::data_path(&format!("favicons/{}.png", name))
+}
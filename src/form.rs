@@ -0,0 +1,47 @@
+use crate::urls;
+use eframe::egui;
+
+const ERROR_COLOR: egui::Color32 = egui::Color32::from_rgb(220, 60, 60);
+
+/// Problems with individual fields of the link form, each shown under its
+/// field while the user types.
+#[derive(Default)]
+pub struct FieldErrors {
+    pub url: Option<String>,
+    pub path: Option<String>,
+    pub tags: Option<String>,
+}
+
+impl FieldErrors {
+    pub fn any(&self) -> bool {
+        self.url.is_some() || self.path.is_some() || self.tags.is_some()
+    }
+}
+
+/// Why `url` can't be shortened. An empty URL is only reported once the
+/// user has tried to submit, so a fresh form isn't covered in red.
+pub fn check_url(url: &str, required: bool) -> Option<String> {
+    let url = url.trim();
+    if url.is_empty() {
+        return required.then(|| "Original URL is required".to_string());
+    }
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Some("URL must start with http:// or https://".to_string());
+    }
+    if url.contains(char::is_whitespace) {
+        return Some("URL can't contain spaces".to_string());
+    }
+    if urls::host(url).is_empty() {
+        return Some("URL is missing a host name".to_string());
+    }
+    None
+}
+
+/// Outlines a field in red and shows its problem underneath.
+pub fn show_error(ui: &mut egui::Ui, field: &egui::Response, error: Option<&String>) {
+    if let Some(error) = error {
+        ui.painter()
+            .rect_stroke(field.rect.expand(1.0), 2.0, egui::Stroke::new(1.5, ERROR_COLOR));
+        ui.colored_label(ERROR_COLOR, error);
+    }
+}
@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(8);
+
+/// Whether a destination lets a cloaked short link show it inside an iframe.
+#[derive(Clone)]
+pub enum Framing {
+    Allowed,
+    Refused(String),
+}
+
+/// Checks the destination's `X-Frame-Options` and CSP `frame-ancestors`
+/// headers as seen from `short_domain`.
+pub async fn check(url: &str, short_domain: &str) -> anyhow::Result<Framing> {
+    let response = reqwest::Client::new()
+        .get(url)
+        .timeout(TIMEOUT)
+        .header("user-agent", concat!("shortyio/", env!("CARGO_PKG_VERSION")))
+        .send()
+        .await?;
+    let headers = response.headers();
+
+    if let Some(value) = headers.get("x-frame-options").and_then(|v| v.to_str().ok()) {
+        let value = value.trim().to_ascii_uppercase();
+        if value == "DENY" || value == "SAMEORIGIN" {
+            return Ok(Framing::Refused(format!("sends X-Frame-Options: {}", value)));
+        }
+    }
+    for csp in headers.get_all("content-security-policy") {
+        let Ok(csp) = csp.to_str() else { continue };
+        let Some(ancestors) = csp
+            .split(';')
+            .map(str::trim)
+            .find_map(|directive| directive.strip_prefix("frame-ancestors"))
+        else {
+            continue;
+        };
+        if !ancestors.split_whitespace().any(|source| allows(source, short_domain)) {
+            return Ok(Framing::Refused(format!("only allows frame-ancestors{}", ancestors)));
+        }
+    }
+    Ok(Framing::Allowed)
+}
+
+fn allows(source: &str, short_domain: &str) -> bool {
+    if source == "*" {
+        return true;
+    }
+    let host = source.split("://").last().unwrap_or(source).trim_end_matches('/');
+    let host = host.split(':').next().unwrap_or(host).to_ascii_lowercase();
+    match host.strip_prefix("*.") {
+        Some(suffix) => short_domain.ends_with(&format!(".{}", suffix)),
+        None => host == short_domain,
+    }
+}
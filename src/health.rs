@@ -0,0 +1,141 @@
+use crate::pins::PinnedLink;
+use chrono::{DateTime, Utc};
+use eframe::egui;
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+const INTERVAL: Duration = Duration::from_secs(30 * 60);
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone)]
+pub struct Health {
+    pub ok: bool,
+    pub message: String,
+    pub checked_at: DateTime<Utc>,
+}
+
+#[derive(Default)]
+struct State {
+    /// Short URL and the destination it should redirect to.
+    targets: Vec<(String, String)>,
+    results: HashMap<String, Health>,
+    started: bool,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+/// Requests each pinned short link every half hour, without following the
+/// redirect, to catch links short.io has disabled before visitors do.
+#[derive(Clone, Default)]
+pub struct HealthMonitor {
+    shared: Arc<Shared>,
+}
+
+impl HealthMonitor {
+    /// Updates the links to check; a changed set is checked right away.
+    pub fn watch(&self, pins: &[PinnedLink], ctx: &egui::Context) {
+        let targets: Vec<(String, String)> = pins
+            .iter()
+            .map(|pin| (pin.short_url.clone(), pin.original_url.clone()))
+            .collect();
+        let mut state = self.shared.state.lock().unwrap();
+        if state.targets == targets {
+            return;
+        }
+        state.targets = targets;
+        if !state.started {
+            state.started = true;
+            self.spawn_worker(ctx.clone());
+        }
+        self.shared.changed.notify_all();
+    }
+
+    pub fn get(&self, short_url: &str) -> Option<Health> {
+        self.shared.state.lock().unwrap().results.get(short_url).cloned()
+    }
+
+    /// Pinned links whose last check failed.
+    pub fn broken(&self) -> usize {
+        let state = self.shared.state.lock().unwrap();
+        state
+            .targets
+            .iter()
+            .filter(|(short_url, _)| state.results.get(short_url).is_some_and(|health| !health.ok))
+            .count()
+    }
+
+    fn spawn_worker(&self, ctx: egui::Context) {
+        let shared = self.shared.clone();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let http = client();
+            loop {
+                let targets = shared.state.lock().unwrap().targets.clone();
+                for (short_url, original_url) in &targets {
+                    let health = runtime.block_on(check(&http, reqwest::Method::GET, short_url, original_url));
+                    shared.state.lock().unwrap().results.insert(short_url.clone(), health);
+                    ctx.request_repaint();
+                }
+                let state = shared.state.lock().unwrap();
+                let _ = shared
+                    .changed
+                    .wait_timeout_while(state, INTERVAL, |state| state.targets == targets)
+                    .unwrap();
+            }
+        });
+    }
+}
+
+/// Checks a link just created with a HEAD request, to catch a domain that
+/// isn't set up or hasn't propagated before the link is shared.
+pub async fn test(short_url: &str, original_url: &str) -> Health {
+    check(&client(), reqwest::Method::HEAD, short_url, original_url).await
+}
+
+fn client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(TIMEOUT)
+        .user_agent(concat!("shortyio/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .unwrap_or_default()
+}
+
+async fn check(http: &reqwest::Client, method: reqwest::Method, short_url: &str, original_url: &str) -> Health {
+    let (ok, message) = match http.request(method, short_url).send().await {
+        Err(e) => (false, format!("Unreachable: {}", e)),
+        Ok(response) => {
+            let status = response.status();
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string();
+            if status.is_redirection() {
+                // short.io passes query parameters on, so only the start must match
+                if location.trim_end_matches('/').starts_with(original_url.trim_end_matches('/')) {
+                    (true, format!("{} → {}", status.as_u16(), location))
+                } else {
+                    (false, format!("Redirects to {} instead", location))
+                }
+            } else if status == reqwest::StatusCode::NOT_FOUND {
+                (false, "Not found: deleted on short.io?".to_string())
+            } else if status.is_success() {
+                (false, "No redirect: expired or clicks limit reached?".to_string())
+            } else {
+                (false, format!("Unexpected status {}", status))
+            }
+        }
+    };
+    Health {
+        ok,
+        message,
+        checked_at: Utc::now(),
+    }
+}
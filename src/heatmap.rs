@@ -0,0 +1,65 @@
+use crate::tz;
+use chrono::{Datelike, Duration, NaiveDate};
+use eframe::egui;
+use std::collections::HashMap;
+
+const WEEKS: i64 = 53;
+const CELL: f32 = 11.0;
+const GAP: f32 = 2.0;
+
+/// A GitHub-style calendar of the last year, one column per week with
+/// Monday at the top, shaded by each day's count.
+pub fn show(ui: &mut egui::Ui, counts: &HashMap<NaiveDate, u64>, color: egui::Color32, unit: &str) {
+    let today = tz::today();
+    let last_monday = today - Duration::days(i64::from(today.weekday().num_days_from_monday()));
+    let first = last_monday - Duration::weeks(WEEKS - 1);
+    let most = (0..WEEKS * 7)
+        .filter_map(|offset| counts.get(&(first + Duration::days(offset))))
+        .copied()
+        .max()
+        .unwrap_or(0);
+
+    let label_height = 14.0;
+    let size = egui::vec2(WEEKS as f32 * (CELL + GAP), label_height + 7.0 * (CELL + GAP));
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let empty = ui.visuals().faint_bg_color;
+    let mut hovered = None;
+
+    for week in 0..WEEKS {
+        let x = rect.left() + week as f32 * (CELL + GAP);
+        let monday = first + Duration::weeks(week);
+        if monday.day() <= 7 {
+            painter.text(
+                egui::pos2(x, rect.top()),
+                egui::Align2::LEFT_TOP,
+                monday.format("%b").to_string(),
+                egui::FontId::proportional(10.0),
+                ui.visuals().weak_text_color(),
+            );
+        }
+        for weekday in 0..7 {
+            let day = monday + Duration::days(weekday);
+            if day > today {
+                break;
+            }
+            let count = counts.get(&day).copied().unwrap_or(0);
+            let min = egui::pos2(x, rect.top() + label_height + weekday as f32 * (CELL + GAP));
+            let cell = egui::Rect::from_min_size(min, egui::vec2(CELL, CELL));
+            let fill = if count == 0 {
+                empty
+            } else {
+                // Four steps like GitHub's, so one huge day doesn't wash out the rest
+                let level = (4 * count).div_ceil(most.max(1)).clamp(1, 4);
+                color.gamma_multiply(level as f32 / 4.0)
+            };
+            painter.rect_filled(cell, 2.0, fill);
+            if response.hover_pos().is_some_and(|pos| cell.contains(pos)) {
+                hovered = Some((day, count));
+            }
+        }
+    }
+    if let Some((day, count)) = hovered {
+        response.on_hover_text(format!("{} {} on {}", count, unit, day.format("%a %b %-d, %Y")));
+    }
+}
@@ -0,0 +1,133 @@
+use crate::audit;
+use crate::storage;
+use crate::urls::host;
+use crate::usage::Usage;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+pub const HISTORY_FILE: &str = "history.json";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HistoryEntry {
+    pub link_id: String,
+    pub short_url: String,
+    pub original_url: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub clicks_limit: Option<i32>,
+    /// Replaced by a fresh copy after nearing its clicks limit.
+    #[serde(default)]
+    pub renewed: bool,
+    /// Gone from short.io, deleted outside the app; found by a sync.
+    #[serde(default)]
+    pub deleted_remotely: bool,
+}
+
+/// Limits applied whenever history changes; zero means unlimited.
+#[derive(Clone, Copy, Default)]
+pub struct Retention {
+    pub max_entries: usize,
+    pub max_age_days: u32,
+}
+
+/// Every link created through the app, oldest first. Shared between the UI
+/// and background workers.
+#[derive(Clone)]
+pub struct History {
+    entries: Arc<Mutex<Vec<HistoryEntry>>>,
+    retention: Arc<Mutex<Retention>>,
+}
+
+impl History {
+    pub fn load(retention: Retention) -> Self {
+        let mut entries: Vec<HistoryEntry> = storage::load_json(HISTORY_FILE).unwrap_or_default();
+        if prune(&mut entries, retention) {
+            Self::persist(&entries);
+        }
+        Self {
+            entries: Arc::new(Mutex::new(entries)),
+            retention: Arc::new(Mutex::new(retention)),
+        }
+    }
+
+    pub fn add(&self, entry: HistoryEntry) {
+        Usage::record(&audit::profile(), &host(&entry.short_url), &entry.tags);
+        let retention = *self.retention.lock().unwrap();
+        let mut entries = self.entries.lock().unwrap();
+        entries.push(entry);
+        prune(&mut entries, retention);
+        Self::persist(&entries);
+    }
+
+    /// Applies new limits immediately.
+    #[cfg(feature = "gui")]
+    pub fn set_retention(&self, retention: Retention) {
+        *self.retention.lock().unwrap() = retention;
+        let mut entries = self.entries.lock().unwrap();
+        if prune(&mut entries, retention) {
+            Self::persist(&entries);
+        }
+    }
+
+    /// Forgets every entry and overwrites the history file before deleting it.
+    #[cfg(feature = "gui")]
+    pub fn wipe(&self) -> anyhow::Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.clear();
+        storage::secure_delete(HISTORY_FILE)
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn update(&self, link_id: &str, f: impl FnOnce(&mut HistoryEntry)) {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(entry) = entries.iter_mut().find(|entry| entry.link_id == link_id) {
+            f(entry);
+            Self::persist(&entries);
+        }
+    }
+
+    /// The most recent link created for `original_url`, if any. Whitespace
+    /// around either URL is ignored.
+    #[cfg(feature = "gui")]
+    pub fn find_by_original(&self, original_url: &str) -> Option<HistoryEntry> {
+        let original_url = original_url.trim();
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|entry| entry.original_url.trim() == original_url)
+            .cloned()
+    }
+
+    /// Runs `f` against the entries without cloning them.
+    #[cfg(feature = "gui")]
+    pub fn read<R>(&self, f: impl FnOnce(&[HistoryEntry]) -> R) -> R {
+        f(&self.entries.lock().unwrap())
+    }
+
+    fn persist(entries: &[HistoryEntry]) {
+        if let Err(e) = storage::save_json(HISTORY_FILE, &entries) {
+            elog!("Failed to save history: {}", e);
+        }
+    }
+}
+
+/// Drops entries beyond the retention limits. Returns whether anything changed.
+fn prune(entries: &mut Vec<HistoryEntry>, retention: Retention) -> bool {
+    let before = entries.len();
+    if retention.max_age_days > 0 {
+        let cutoff = Utc::now() - chrono::Duration::days(i64::from(retention.max_age_days));
+        entries.retain(|entry| entry.created_at >= cutoff);
+    }
+    if retention.max_entries > 0 && entries.len() > retention.max_entries {
+        let excess = entries.len() - retention.max_entries;
+        entries.drain(..excess);
+    }
+    entries.len() != before
+}
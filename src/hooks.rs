@@ -0,0 +1,220 @@
+use crate::api::{CreateLinkRequest, LinkResponse};
+use crate::storage;
+use anyhow::{Result, anyhow};
+use rhai::{AST, Dynamic, Engine, EvalAltResult, Scope};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gui")]
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const SCRIPT_FILE: &str = "hooks.rhai";
+/// Keeps a runaway loop from freezing link creation.
+const MAX_OPERATIONS: u64 = 1_000_000;
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[cfg(feature = "gui")]
+const TEMPLATE: &str = r#"// Shortyio hooks. Both functions are optional; delete the ones you don't use.
+
+// Runs before a link is created. Change any of url, path, tags or title and
+// return the link, or throw "reason" to stop it being created.
+fn pre_submit(link) {
+    // if !link.url.contains("utm_source") {
+    //     link.url += if link.url.contains("?") { "&" } else { "?" };
+    //     link.url += "utm_source=shortyio";
+    // }
+    link
+}
+
+// Runs after a link is created, with its id, short_url, original_url and tags.
+fn post_create(link) {
+    // http_post("https://example.com/links", link);
+}
+"#;
+
+/// What `pre_submit` sees and may change. Empty strings stand for unset.
+#[derive(Serialize, Deserialize)]
+struct Submission {
+    url: String,
+    path: String,
+    domain: String,
+    tags: Vec<String>,
+    title: String,
+}
+
+#[derive(Serialize)]
+struct Created<'a> {
+    id: &'a str,
+    short_url: &'a str,
+    original_url: &'a str,
+    tags: &'a [String],
+}
+
+pub fn script_path() -> Option<PathBuf> {
+    storage::config_dir().map(|dir| dir.join(SCRIPT_FILE))
+}
+
+/// The script path, writing a commented template there first if there's no
+/// script yet.
+#[cfg(feature = "gui")]
+pub fn ensure_script() -> Result<PathBuf> {
+    let path = script_path().ok_or_else(|| anyhow!("Cannot determine config path"))?;
+    if !path.exists() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, TEMPLATE)?;
+    }
+    Ok(path)
+}
+
+/// Runs the script's `pre_submit`, returning the rewritten request, or
+/// `None` when there's no script or hook. Errors, including a `throw`, mean
+/// the link must not be created.
+pub fn pre_submit(request: &CreateLinkRequest) -> Result<Option<CreateLinkRequest>> {
+    let Some((engine, ast)) = load("pre_submit")? else {
+        return Ok(None);
+    };
+    let submission = Submission {
+        url: request.original_url.clone(),
+        path: request.path.clone().unwrap_or_default(),
+        domain: request.domain.clone().unwrap_or_default(),
+        tags: request.tags.clone().unwrap_or_default(),
+        title: request.title.clone().unwrap_or_default(),
+    };
+    let result: Dynamic = engine
+        .call_fn(&mut Scope::new(), &ast, "pre_submit", (to_dynamic(&submission)?,))
+        .map_err(|e| anyhow!("pre_submit hook: {}", e))?;
+    if result.is_unit() {
+        return Ok(None);
+    }
+    let changed: Submission = rhai::serde::from_dynamic(&result).map_err(|e| anyhow!("pre_submit hook returned a bad link: {}", e))?;
+    let non_empty = |text: String| (!text.is_empty()).then_some(text);
+    let mut request = request.clone();
+    request.original_url = changed.url;
+    request.path = non_empty(changed.path);
+    request.tags = (!changed.tags.is_empty()).then_some(changed.tags);
+    request.title = non_empty(changed.title);
+    Ok(Some(request))
+}
+
+/// Runs the script's `post_create`. The link already exists, so failures are
+/// only logged.
+pub fn post_create(link: &LinkResponse, tags: &[String]) {
+    let result = load("post_create").and_then(|loaded| {
+        let Some((engine, ast)) = loaded else {
+            return Ok(());
+        };
+        let created = Created {
+            id: &link.id,
+            short_url: &link.short_url,
+            original_url: &link.original_url,
+            tags,
+        };
+        // The return value is ignored
+        let _ = engine
+            .call_fn::<Dynamic>(&mut Scope::new(), &ast, "post_create", (to_dynamic(&created)?,))
+            .map_err(|e| anyhow!("{}", e))?;
+        Ok(())
+    });
+    if let Err(e) = result {
+        elog!("post_create hook failed: {}", e);
+    }
+}
+
+/// The engine and compiled script, if the script defines `hook`. The file is
+/// read on every call so edits apply to the next link.
+fn load(hook: &str) -> Result<Option<(Engine, AST)>> {
+    let Some(path) = script_path().filter(|path| path.exists()) else {
+        return Ok(None);
+    };
+    let engine = engine();
+    let ast = engine
+        .compile_file(path.clone())
+        .map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+    if !ast.iter_functions().any(|f| f.name == hook) {
+        return Ok(None);
+    }
+    Ok(Some((engine, ast)))
+}
+
+// Script errors aren't Send, so they're flattened to text for anyhow
+fn to_dynamic(value: &impl Serialize) -> Result<Dynamic> {
+    rhai::serde::to_dynamic(value).map_err(|e| anyhow!("{}", e))
+}
+
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.on_print(|text| elog!("hooks.rhai: {}", text));
+    engine.on_debug(|text, _, _| elog!("hooks.rhai: {}", text));
+    engine.register_fn("http_post", http_post);
+    engine
+}
+
+/// POSTs `body` as JSON, for scripts that hand links on to other tools.
+fn http_post(url: &str, body: Dynamic) -> Result<(), Box<EvalAltResult>> {
+    let body: serde_json::Value = rhai::serde::from_dynamic(&body)?;
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+    runtime
+        .block_on(async {
+            reqwest::Client::new()
+                .post(url)
+                .timeout(HTTP_TIMEOUT)
+                .header("user-agent", concat!("shortyio/", env!("CARGO_PKG_VERSION")))
+                .json(&body)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+        })
+        .map_err(|e| format!("http_post to {} failed: {}", url, e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::{ApiClient, ApiError, CreateLinkRequest};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// `pre_submit` runs on a tokio worker during `create_link`, so a script
+    /// calling `http_post` there must not try to start a nested runtime.
+    #[test]
+    fn http_post_from_pre_submit() {
+        let server = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", server.local_addr().unwrap());
+        let received = std::thread::spawn(move || {
+            let (mut stream, _) = server.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 4096];
+            while !String::from_utf8_lossy(&request).contains("example.com") {
+                let read = stream.read(&mut buffer).unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+            stream.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n").unwrap();
+            String::from_utf8_lossy(&request).into_owned()
+        });
+
+        let dir = std::env::temp_dir().join(format!("shortyio-hooks-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let script = format!("fn pre_submit(link) {{ http_post(\"{}\", link); throw \"posted\"; }}", url);
+        std::fs::write(dir.join(super::SCRIPT_FILE), script).unwrap();
+        // SAFETY: no other test reads the environment
+        unsafe { std::env::set_var(crate::storage::ENV_CONFIG_DIR, &dir) };
+
+        let request = CreateLinkRequest::simple("https://example.com/page".to_string(), Some("short.gy".to_string()));
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(ApiClient::new("test").create_link(&request));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        match result {
+            Err(ApiError::Hook(message)) => assert!(message.contains("posted"), "{}", message),
+            Err(e) => panic!("expected the hook to stop the link, got {}", e),
+            Ok(_) => panic!("expected the hook to stop the link"),
+        }
+        assert!(received.join().unwrap().contains("https://example.com/page"));
+    }
+}
@@ -0,0 +1,361 @@
+use crate::api::{ApiClient, CreateLinkRequest};
+use crate::history::{History, HistoryEntry};
+use crate::storage::{self, csv_field};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const JOBS_FILE: &str = "jobs.json";
+const PAUSE_POLL: Duration = Duration::from_millis(500);
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum JobState {
+    Running,
+    Paused,
+    Cancelled,
+    Finished,
+}
+
+/// What happens with the results once every item has been processed.
+#[derive(Serialize, Deserialize, Clone)]
+pub enum JobOutput {
+    /// Write `title,original_url,short_url,tags` rows to a CSV file.
+    Csv { path: PathBuf },
+    /// Replace the given byte ranges of `source` and write it to `path`.
+    Markdown {
+        path: PathBuf,
+        source: String,
+        spans: Vec<(usize, usize)>,
+    },
+    /// A PDF with one QR code per page, labeled with the item's title.
+    QrSheet { path: PathBuf },
+    /// One `<title>.png` QR code per item in `dir`.
+    QrImages { dir: PathBuf },
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct JobItem {
+    pub url: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub short_url: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl JobItem {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            title: None,
+            tags: Vec::new(),
+            short_url: None,
+            error: None,
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.short_url.is_some() || self.error.is_some()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Job {
+    pub id: u64,
+    pub name: String,
+    /// Profile whose API key the job creates links with.
+    #[serde(default)]
+    pub profile: String,
+    pub domain: Option<String>,
+    pub items: Vec<JobItem>,
+    pub output: JobOutput,
+    pub state: JobState,
+    #[serde(default)]
+    pub output_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Job {
+    pub fn new(name: String, profile: String, domain: Option<String>, items: Vec<JobItem>, output: JobOutput) -> Self {
+        Self {
+            id: 0,
+            name,
+            profile,
+            domain,
+            items,
+            output,
+            state: JobState::Running,
+            output_error: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn done(&self) -> usize {
+        self.items.iter().filter(|item| item.is_done()).count()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = String> + '_ {
+        self.items
+            .iter()
+            .filter_map(|item| item.error.as_ref().map(|e| format!("{}: {}", item.url, e)))
+            .chain(self.output_error.clone())
+    }
+
+    /// Short URL and label of every item that has one.
+    fn labeled_links(&self) -> Vec<(String, String)> {
+        self.items
+            .iter()
+            .filter_map(|item| {
+                let short_url = item.short_url.clone()?;
+                Some((short_url, item.title.clone().unwrap_or_else(|| item.url.clone())))
+            })
+            .collect()
+    }
+
+    fn write_output(&self) -> anyhow::Result<()> {
+        match &self.output {
+            JobOutput::Csv { path } => {
+                let mut rows = vec!["title,original_url,short_url,tags".to_string()];
+                for item in &self.items {
+                    if let Some(short_url) = &item.short_url {
+                        rows.push(format!(
+                            "{},{},{},{}",
+                            csv_field(item.title.as_deref().unwrap_or_default()),
+                            csv_field(&item.url),
+                            csv_field(short_url),
+                            csv_field(&item.tags.join(" "))
+                        ));
+                    }
+                }
+                rows.push(String::new());
+                fs::write(path, rows.join("\n"))?;
+            }
+            JobOutput::Markdown { path, source, spans } => {
+                let shortened: HashMap<String, String> = self
+                    .items
+                    .iter()
+                    .filter_map(|item| item.short_url.clone().map(|short| (item.url.clone(), short)))
+                    .collect();
+                let spans: Vec<_> = spans.iter().map(|&(start, end)| start..end).collect();
+                fs::write(path, crate::markdown::rewrite(source, &spans, &shortened))?;
+            }
+            JobOutput::QrSheet { path } => crate::poster::write_sheets(path, &self.labeled_links())?,
+            JobOutput::QrImages { dir } => crate::poster::write_pngs(dir, &self.labeled_links())?,
+        }
+        Ok(())
+    }
+}
+
+/// Bulk shortening jobs, each processed on its own worker thread. Jobs are
+/// persisted after every item so unfinished ones resume on the next launch.
+#[derive(Clone)]
+pub struct Jobs {
+    jobs: Arc<Mutex<Vec<Job>>>,
+    workers: Arc<Mutex<HashSet<u64>>>,
+    history: History,
+}
+
+impl Jobs {
+    pub fn load(history: History) -> Self {
+        let jobs: Vec<Job> = storage::load_json(JOBS_FILE).unwrap_or_default();
+        let resume: Vec<u64> = jobs
+            .iter()
+            .filter(|job| matches!(job.state, JobState::Running | JobState::Paused))
+            .map(|job| job.id)
+            .collect();
+        let manager = Self {
+            jobs: Arc::new(Mutex::new(jobs)),
+            workers: Arc::new(Mutex::new(HashSet::new())),
+            history,
+        };
+        for id in resume {
+            manager.spawn_worker(id);
+        }
+        manager
+    }
+
+    pub fn start(&self, mut job: Job) {
+        let id = {
+            let mut jobs = self.jobs.lock().unwrap();
+            job.id = jobs.iter().map(|j| j.id).max().unwrap_or(0) + 1;
+            let id = job.id;
+            jobs.push(job);
+            Self::persist(&jobs);
+            id
+        };
+        self.spawn_worker(id);
+    }
+
+    pub fn snapshot(&self) -> Vec<Job> {
+        self.jobs.lock().unwrap().clone()
+    }
+
+    pub fn any_running(&self) -> bool {
+        self.jobs.lock().unwrap().iter().any(|job| job.state == JobState::Running)
+    }
+
+    pub fn set_state(&self, id: u64, state: JobState) {
+        {
+            let mut jobs = self.jobs.lock().unwrap();
+            if let Some(job) = jobs.iter_mut().find(|job| job.id == id) {
+                if job.state != JobState::Finished {
+                    job.state = state;
+                    job.output_error = None;
+                }
+            }
+            Self::persist(&jobs);
+        }
+        if state == JobState::Running {
+            self.spawn_worker(id);
+        }
+    }
+
+    /// Clears the errors of failed items so they are attempted again.
+    pub fn retry_failed(&self, id: u64) {
+        {
+            let mut jobs = self.jobs.lock().unwrap();
+            if let Some(job) = jobs.iter_mut().find(|job| job.id == id) {
+                for item in &mut job.items {
+                    item.error = None;
+                }
+                job.output_error = None;
+                job.state = JobState::Running;
+            }
+            Self::persist(&jobs);
+        }
+        self.spawn_worker(id);
+    }
+
+    pub fn remove(&self, id: u64) {
+        let mut jobs = self.jobs.lock().unwrap();
+        jobs.retain(|job| job.id != id || matches!(job.state, JobState::Running | JobState::Paused));
+        Self::persist(&jobs);
+    }
+
+    /// Starts a worker for `id` unless one is already processing it.
+    fn spawn_worker(&self, id: u64) {
+        if !self.workers.lock().unwrap().insert(id) {
+            return;
+        }
+        let manager = self.clone();
+        std::thread::spawn(move || {
+            manager.run(id);
+            manager.workers.lock().unwrap().remove(&id);
+        });
+    }
+
+    fn run(&self, id: u64) {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        // Jobs whose items already have short URLs, like QR sheets, only
+        // write their output and need no API key.
+        let mut client = None;
+
+        loop {
+            let next = {
+                let jobs = self.jobs.lock().unwrap();
+                let Some(job) = jobs.iter().find(|job| job.id == id) else {
+                    return;
+                };
+                match job.state {
+                    JobState::Cancelled | JobState::Finished => return,
+                    JobState::Paused => None,
+                    JobState::Running => Some(
+                        job.items
+                            .iter()
+                            .position(|item| !item.is_done())
+                            .map(|index| (index, job.items[index].clone(), job.domain.clone(), job.profile.clone())),
+                    ),
+                }
+            };
+
+            let (index, item, domain, profile) = match next {
+                None => {
+                    std::thread::sleep(PAUSE_POLL);
+                    continue;
+                }
+                Some(None) => {
+                    self.finish(id);
+                    return;
+                }
+                Some(Some(next)) => next,
+            };
+            if client.is_none() {
+                let Some(config) = crate::Config::load() else {
+                    self.pause_with_error(id, "API key is not configured".to_string());
+                    return;
+                };
+                // The job's own profile, even if another one is active now
+                let Some(profile) = config.profile_named(&profile) else {
+                    self.pause_with_error(id, format!("Profile \"{}\" no longer exists", profile));
+                    return;
+                };
+                client = Some(ApiClient::new(profile.api_key));
+            }
+            let Some(client) = &client else { return };
+
+            let mut request = CreateLinkRequest::simple(item.url.clone(), domain);
+            if !item.tags.is_empty() {
+                let mut tags = vec!["shortyio".to_string()];
+                tags.extend(item.tags.iter().cloned());
+                request.tags = Some(tags);
+            }
+            request.title = item.title.clone();
+            let result = runtime.block_on(client.create_link(&request));
+
+            let mut jobs = self.jobs.lock().unwrap();
+            if let Some(job) = jobs.iter_mut().find(|job| job.id == id) {
+                match result {
+                    Ok(link) => {
+                        job.items[index].short_url = Some(link.short_url.clone());
+                        self.history.add(HistoryEntry {
+                            link_id: link.id,
+                            short_url: link.short_url,
+                            original_url: link.original_url,
+                            title: item.title,
+                            tags: request.tags.unwrap_or_default(),
+                            created_at: Utc::now(),
+                            clicks_limit: None,
+                            renewed: false,
+                            deleted_remotely: false,
+                        });
+                    }
+                    Err(e) => job.items[index].error = Some(e.to_string()),
+                }
+            }
+            Self::persist(&jobs);
+        }
+    }
+
+    fn finish(&self, id: u64) {
+        let mut jobs = self.jobs.lock().unwrap();
+        if let Some(job) = jobs.iter_mut().find(|job| job.id == id) {
+            if let Err(e) = job.write_output() {
+                job.output_error = Some(format!("Cannot write output: {}", e));
+            }
+            job.state = JobState::Finished;
+        }
+        Self::persist(&jobs);
+    }
+
+    fn pause_with_error(&self, id: u64, error: String) {
+        let mut jobs = self.jobs.lock().unwrap();
+        if let Some(job) = jobs.iter_mut().find(|job| job.id == id) {
+            job.output_error = Some(error);
+            job.state = JobState::Paused;
+        }
+        Self::persist(&jobs);
+    }
+
+    fn persist(jobs: &[Job]) {
+        if let Err(e) = storage::save_json(JOBS_FILE, &jobs) {
+            elog!("Failed to save jobs: {}", e);
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use crate::api::{LinkPage, LinkResponse};
+
+/// The My Links listing for one domain, filled a page at a time as the user
+/// scrolls.
+#[derive(Default)]
+pub struct LinkList {
+    pub domain: String,
+    pub links: Vec<LinkResponse>,
+    pub total: Option<u64>,
+    pub next_page: Option<String>,
+    pub exhausted: bool,
+    pub loading: bool,
+    pub error: Option<String>,
+}
+
+impl LinkList {
+    pub fn reset(&mut self, domain: &str) {
+        *self = Self {
+            domain: domain.to_string(),
+            ..Self::default()
+        };
+    }
+
+    pub fn wants_more(&self) -> bool {
+        !self.loading && !self.exhausted && self.error.is_none()
+    }
+
+    pub fn append(&mut self, page: LinkPage) {
+        self.links.extend(page.links);
+        self.total = page.count.or(self.total);
+        self.next_page = page.next_page_token.filter(|token| !token.is_empty());
+        self.exhausted = self.next_page.is_none();
+        self.loading = false;
+    }
+}
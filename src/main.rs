@@ -1,76 +1,307 @@
 #![windows_subsystem = "windows"]
 
+/// Logs to stderr and keeps the line for crash reports.
+macro_rules! elog {
+    ($($arg:tt)*) => {
+        $crate::crash::record(format!($($arg)*))
+    };
+}
+
+mod api;
+mod audit;
+#[cfg(feature = "gui")]
+mod bench;
+#[cfg(feature = "gui")]
+mod bio;
+#[cfg(feature = "gui")]
+mod bookmarks;
+#[cfg(feature = "gui")]
+mod burst;
+mod cache;
+mod cli;
+#[cfg(feature = "gui")]
+mod campaigns;
+#[cfg(feature = "gui")]
+mod clipboard;
+#[cfg(feature = "gui")]
+mod config_watch;
+#[cfg(feature = "gui")]
+mod connectivity;
+mod crash;
+#[cfg(feature = "gui")]
+mod diagnostics;
+#[cfg(feature = "gui")]
+mod drafts;
+#[cfg(feature = "gui")]
+mod duplicates;
+#[cfg(feature = "gui")]
+mod edit;
+#[cfg(feature = "gui")]
+mod expiring;
+#[cfg(feature = "gui")]
+mod favicons;
+#[cfg(feature = "gui")]
+mod form;
+#[cfg(feature = "gui")]
+mod framing;
+#[cfg(feature = "gui")]
+mod health;
+#[cfg(feature = "gui")]
+mod heatmap;
+mod history;
+mod hooks;
+#[cfg(feature = "gui")]
+mod jobs;
+#[cfg(feature = "gui")]
+mod link_list;
+#[cfg(feature = "gui")]
+mod markdown;
+mod mcp;
+mod naming;
+#[cfg(feature = "gui")]
+mod nfc;
+#[cfg(feature = "gui")]
+mod og;
+#[cfg(feature = "gui")]
+mod nav;
+#[cfg(feature = "gui")]
+mod options;
+#[cfg(feature = "gui")]
+mod pins;
+#[cfg(feature = "gui")]
+mod placeholders;
+#[cfg(feature = "gui")]
+mod plugins;
+mod policy;
+#[cfg(feature = "gui")]
+mod popouts;
+#[cfg(feature = "gui")]
+mod poster;
+#[cfg(feature = "gui")]
+mod prefetch;
+mod presets;
+#[cfg(feature = "gui")]
+mod profanity;
+mod profiles;
+#[cfg(feature = "gui")]
+mod publish;
+#[cfg(feature = "gui")]
+mod qr;
+#[cfg(feature = "gui")]
+mod recent;
+#[cfg(feature = "gui")]
+mod redirects;
+mod renew;
+#[cfg(feature = "gui")]
+mod reports;
+#[cfg(feature = "gui")]
+mod reserved;
+#[cfg(feature = "gui")]
+mod retag;
+#[cfg(feature = "gui")]
+mod rollups;
+#[cfg(feature = "gui")]
+mod schedule;
+#[cfg(feature = "gui")]
+mod segments;
+#[cfg(feature = "gui")]
+mod share_sheet;
+mod slugs;
+#[cfg(feature = "gui")]
+mod social;
+mod storage;
+#[cfg(feature = "gui")]
+mod sync;
+#[cfg(feature = "gui")]
+mod thumbnails;
+#[cfg(feature = "gui")]
+mod titles;
+#[cfg(feature = "gui")]
+mod toasts;
+mod trash;
+mod tz;
+mod update;
+mod urls;
+mod usage;
+#[cfg(feature = "gui")]
+mod vanity;
+#[cfg(feature = "gui")]
+mod versions;
+#[cfg(feature = "gui")]
+mod watch;
+mod webhook;
+#[cfg(feature = "gui")]
+mod when;
+
 use anyhow::Result;
-use arboard::Clipboard;
-use directories::ProjectDirs;
+#[cfg(feature = "gui")]
+use api::{ApiClient, CreateLinkRequest, Domain, DomainFallbacks, LinkPage, LinkResponse, UpdateLinkRequest};
+#[cfg(feature = "gui")]
+use bio::BioPage;
+#[cfg(feature = "gui")]
+use bookmarks::BookmarkFolder;
+#[cfg(feature = "gui")]
+use burst::Burst;
+#[cfg(feature = "gui")]
+use campaigns::{Campaign, Campaigns};
+#[cfg(feature = "gui")]
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, Utc};
+#[cfg(feature = "gui")]
+use connectivity::{Connection, Connectivity};
+#[cfg(feature = "gui")]
+use drafts::{Draft, Drafts};
+#[cfg(feature = "gui")]
+use duplicates::DuplicateGroup;
+#[cfg(feature = "gui")]
 use eframe::egui;
+#[cfg(feature = "gui")]
+use favicons::Favicons;
+#[cfg(feature = "gui")]
+use edit::LinkEdit;
+#[cfg(feature = "gui")]
+use form::FieldErrors;
+#[cfg(feature = "gui")]
+use framing::Framing;
+#[cfg(feature = "gui")]
+use health::{Health, HealthMonitor};
+#[cfg(feature = "gui")]
+use history::{History, HistoryEntry};
+use history::Retention;
+#[cfg(feature = "gui")]
+use jobs::{Job, JobItem, JobOutput, JobState, Jobs};
+#[cfg(feature = "gui")]
+use link_list::LinkList;
+use naming::NamingRules;
+#[cfg(feature = "gui")]
+use nav::{Navigation, Panel};
+#[cfg(feature = "gui")]
+use options::{AdvancedTab, OptionChip};
+#[cfg(feature = "gui")]
+use pins::{PinnedLink, Pins};
+#[cfg(feature = "gui")]
+use placeholders::Counters;
+use policy::BlockRule;
+#[cfg(feature = "gui")]
+use popouts::{Comparison, Popout, PopoutKind};
+#[cfg(feature = "gui")]
+use prefetch::StatsPrefetcher;
+#[cfg(feature = "gui")]
+use presets::{Presets, TeamPresets};
+use profiles::Profile;
+#[cfg(feature = "gui")]
+use recent::RecentResults;
+use renew::RenewMode;
+#[cfg(feature = "gui")]
+use renew::Renewer;
+#[cfg(feature = "gui")]
+use reports::ReportFormat;
+#[cfg(feature = "gui")]
+use schedule::{Rotation, ScheduledActivation, Scheduler};
+#[cfg(feature = "gui")]
+use rollups::RollupStats;
+#[cfg(feature = "gui")]
+use segments::SegmentStats;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "gui")]
+use share_sheet::ShareSheet;
+#[cfg(feature = "gui")]
+use sync::SyncDaemon;
+#[cfg(feature = "gui")]
+use social::{Compose, Network};
+#[cfg(feature = "gui")]
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
+#[cfg(feature = "gui")]
+use thumbnails::Thumbnails;
+#[cfg(feature = "gui")]
+use toasts::{Toasts, Undo};
+#[cfg(feature = "gui")]
+use usage::Usage;
+#[cfg(feature = "gui")]
+use watch::FileWatcher;
 
-#[derive(Serialize, Deserialize)]
-struct CreateLinkRequest {
-    #[serde(rename = "originalURL")]
-    original_url: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    path: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    domain: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    cloaking: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    password: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "passwordContact")]
-    password_contact: Option<bool>,
-    #[serde(rename = "allowDuplicates")]
-    allow_duplicates: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "clicksLimit")]
-    clicks_limit: Option<i32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "redirectType")]
-    redirect_type: Option<i32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    tags: Option<Vec<String>>,
-}
-
-#[derive(Deserialize, Clone)]
-struct LinkResponse {
-    #[serde(rename = "shortURL")]
-    short_url: String,
-    #[serde(rename = "originalURL")]
-    original_url: String,
-}
-
-#[derive(Deserialize, Clone, Debug)]
-struct Domain {
-    hostname: String,
-}
+/// Not Shift+V: egui turns every Cmd/Ctrl+V into a paste event, so that
+/// key never arrives.
+#[cfg(feature = "gui")]
+const PASTE_AND_SHORTEN: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT), egui::Key::L);
 
 struct Config {
-    api_key: String,
-    domain: String,
+    profiles: Vec<Profile>,
+    active_profile: usize,
+    exclude_domains: Vec<String>,
+    check_updates: bool,
+    history_max_entries: usize,
+    history_max_age_days: u32,
+    cache_on_disk: bool,
+    keep_clipboard: bool,
+    primary_selection: bool,
+    blocked_destinations: Vec<BlockRule>,
+    naming: NamingRules,
+    clicks_limit_renew: RenewMode,
+    thumbnails: bool,
+    screenshot_service: String,
+    mastodon_server: String,
+    time_zone: String,
+    trash_days: u32,
+    sync_interval_minutes: u32,
+    startup_check: bool,
 }
 
 impl Config {
+    /// Minutes between background syncs with short.io; zero turns them off.
+    fn default_sync_interval() -> u32 {
+        30
+    }
+
+    /// The profile links are created with.
+    fn profile(&self) -> Profile {
+        self.profiles.get(self.active_profile).cloned().unwrap_or_default().with_env_overrides()
+    }
+
+    /// The profile background work was set up under, so it keeps its own
+    /// API key after a switch. Work saved before profiles were recorded has
+    /// no name and uses the active one.
+    #[cfg(feature = "gui")]
+    fn profile_named(&self, name: &str) -> Option<Profile> {
+        let active = self.profiles.get(self.active_profile).is_none_or(|profile| profile.name == name);
+        if name.is_empty() || active {
+            return Some(self.profile());
+        }
+        self.profiles.iter().find(|profile| profile.name == name).cloned()
+    }
+
+    fn retention(&self) -> Retention {
+        Retention {
+            max_entries: self.history_max_entries,
+            max_age_days: self.history_max_age_days,
+        }
+    }
+
     fn config_path() -> Option<PathBuf> {
-        ProjectDirs::from("systems", "weedmark", "shortyio")
-            .map(|proj_dirs| proj_dirs.config_dir().join("config.json"))
+        storage::config_dir().map(|dir| dir.join("config.json"))
     }
 
+    /// The config file, or defaults when there is none but an API key comes
+    /// from the environment.
     fn load() -> Option<Self> {
-        let path = Self::config_path()?;
-        let content = fs::read_to_string(path).ok()?;
-        serde_json::from_str(&content).ok()
+        let content = Self::config_path().and_then(|path| fs::read_to_string(path).ok());
+        match content {
+            Some(content) => serde_json::from_str(&content).ok(),
+            None if profiles::env_override(profiles::ENV_API_KEY).is_some() => serde_json::from_str("{}").ok(),
+            None => None,
+        }
     }
 
+    #[cfg(feature = "gui")]
     fn save(&self) -> Result<()> {
         let path = Self::config_path().ok_or_else(|| anyhow::anyhow!("Cannot determine config path"))?;
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
         }
-        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        config_watch::mark_seen(&path);
         Ok(())
     }
 }
@@ -81,9 +312,26 @@ impl Serialize for Config {
         S: serde::Serializer,
     {
         use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("Config", 2)?;
-        state.serialize_field("api_key", &self.api_key)?;
-        state.serialize_field("domain", &self.domain)?;
+        let mut state = serializer.serialize_struct("Config", 19)?;
+        state.serialize_field("profiles", &self.profiles)?;
+        state.serialize_field("active_profile", &self.active_profile)?;
+        state.serialize_field("exclude_domains", &self.exclude_domains)?;
+        state.serialize_field("check_updates", &self.check_updates)?;
+        state.serialize_field("history_max_entries", &self.history_max_entries)?;
+        state.serialize_field("history_max_age_days", &self.history_max_age_days)?;
+        state.serialize_field("cache_on_disk", &self.cache_on_disk)?;
+        state.serialize_field("keep_clipboard", &self.keep_clipboard)?;
+        state.serialize_field("primary_selection", &self.primary_selection)?;
+        state.serialize_field("blocked_destinations", &self.blocked_destinations)?;
+        state.serialize_field("naming", &self.naming)?;
+        state.serialize_field("clicks_limit_renew", &self.clicks_limit_renew)?;
+        state.serialize_field("thumbnails", &self.thumbnails)?;
+        state.serialize_field("screenshot_service", &self.screenshot_service)?;
+        state.serialize_field("mastodon_server", &self.mastodon_server)?;
+        state.serialize_field("time_zone", &self.time_zone)?;
+        state.serialize_field("trash_days", &self.trash_days)?;
+        state.serialize_field("sync_interval_minutes", &self.sync_interval_minutes)?;
+        state.serialize_field("startup_check", &self.startup_check)?;
         state.end()
     }
 }
@@ -95,26 +343,109 @@ impl<'de> Deserialize<'de> for Config {
     {
         #[derive(Deserialize)]
         struct ConfigHelper {
+            // Configs from before profiles existed hold a single key/domain
+            #[serde(default)]
             api_key: String,
+            #[serde(default)]
             domain: String,
+            #[serde(default)]
+            profiles: Vec<Profile>,
+            #[serde(default)]
+            active_profile: usize,
+            #[serde(default)]
+            exclude_domains: Vec<String>,
+            #[serde(default = "update::enabled_by_default")]
+            check_updates: bool,
+            #[serde(default)]
+            history_max_entries: usize,
+            #[serde(default)]
+            history_max_age_days: u32,
+            #[serde(default)]
+            cache_on_disk: bool,
+            #[serde(default)]
+            keep_clipboard: bool,
+            #[serde(default)]
+            primary_selection: bool,
+            #[serde(default)]
+            blocked_destinations: Vec<BlockRule>,
+            #[serde(default)]
+            naming: NamingRules,
+            #[serde(default)]
+            clicks_limit_renew: RenewMode,
+            #[serde(default)]
+            thumbnails: bool,
+            #[serde(default)]
+            screenshot_service: String,
+            #[serde(default)]
+            mastodon_server: String,
+            #[serde(default)]
+            time_zone: String,
+            #[serde(default = "trash::default_days")]
+            trash_days: u32,
+            #[serde(default = "Config::default_sync_interval")]
+            sync_interval_minutes: u32,
+            #[serde(default)]
+            startup_check: bool,
         }
         let helper = ConfigHelper::deserialize(deserializer)?;
+        let profiles = if helper.profiles.is_empty() {
+            vec![Profile {
+                api_key: helper.api_key,
+                domain: helper.domain,
+                ..Profile::named("Default")
+            }]
+        } else {
+            helper.profiles
+        };
         Ok(Config {
-            api_key: helper.api_key,
-            domain: helper.domain,
+            active_profile: helper.active_profile.min(profiles.len() - 1),
+            profiles,
+            exclude_domains: helper.exclude_domains,
+            check_updates: helper.check_updates,
+            history_max_entries: helper.history_max_entries,
+            history_max_age_days: helper.history_max_age_days,
+            cache_on_disk: helper.cache_on_disk,
+            keep_clipboard: helper.keep_clipboard,
+            primary_selection: helper.primary_selection,
+            blocked_destinations: helper.blocked_destinations,
+            naming: helper.naming,
+            clicks_limit_renew: helper.clicks_limit_renew,
+            thumbnails: helper.thumbnails,
+            screenshot_service: helper.screenshot_service,
+            mastodon_server: helper.mastodon_server,
+            time_zone: helper.time_zone,
+            trash_days: helper.trash_days,
+            sync_interval_minutes: helper.sync_interval_minutes,
+            startup_check: helper.startup_check,
         })
     }
 }
 
+#[cfg(feature = "gui")]
 struct ShortyApp {
     api_key: String,
     domain: String,
+    profiles: Vec<Profile>,
+    active_profile: usize,
+    new_profile_name: String,
+    kiosk_unlocked: bool,
+    kiosk_pin_entry: String,
+    kiosk_error: Option<String>,
+    show_segment: bool,
+    segment_stats: Option<SegmentStats>,
+    segment_loading: bool,
+    segment_error: Option<String>,
+    share_sheet: ShareSheet,
+    cache_on_disk: bool,
     original_url: String,
     custom_path: String,
     cloaking: bool,
     password: String,
     password_contact: bool,
-    clicks_limit: String,
+    clicks_limit_enabled: bool,
+    clicks_limit: i32,
+    /// Expiration as typed, e.g. "in 2 weeks"; empty for none.
+    expires: String,
     redirect_type: i32,
     result: Option<LinkResponse>,
     error: Option<String>,
@@ -122,324 +453,5515 @@ struct ShortyApp {
     show_settings: bool,
     domains: Vec<Domain>,
     domains_loading: bool,
+    schedule_enabled: bool,
+    go_live_at: String,
+    holding_url: String,
+    scheduler: Scheduler,
+    show_rotations: bool,
+    rotation_path: String,
+    rotation_start: String,
+    rotation_interval_days: u32,
+    rotation_queue: String,
+    rotation_drafts: HashMap<String, String>,
+    rotation_error: Option<String>,
+    show_watch: bool,
+    watcher: Option<FileWatcher>,
+    history: History,
+    show_history: bool,
+    sync_daemon: SyncDaemon,
+    sync_interval_minutes: u32,
+    sync_report: Option<Result<sync::Report, String>>,
+    show_sync_report: bool,
+    show_import: bool,
+    bookmark_folders: Vec<BookmarkFolder>,
+    bookmark_folder: usize,
+    import_error: Option<String>,
+    exclude_domains: String,
+    show_markdown: bool,
+    markdown_error: Option<String>,
+    jobs: Jobs,
+    show_jobs: bool,
+    show_copied: bool,
+    show_trash: bool,
+    trash_items: Vec<trash::Trashed>,
+    trash_busy: bool,
+    trash_error: Option<String>,
+    trash_purged: bool,
+    duplicate: Option<HistoryEntry>,
+    check_updates: bool,
+    update_checked: bool,
+    startup_check: bool,
+    startup_checked: bool,
+    diagnostics: Option<Vec<diagnostics::Check>>,
+    diagnostics_busy: bool,
+    show_diagnostics: bool,
+    update_available: Option<update::Release>,
+    update_status: Option<String>,
+    crash_report: Option<PathBuf>,
+    usage: Option<Usage>,
+    autofilled_url: String,
+    window_focused: bool,
+    /// Whether the clipboard has been checked for a URL since startup.
+    clipboard_read: bool,
+    retention: Retention,
+    confirm_clear_history: bool,
+    show_links: bool,
+    link_list: LinkList,
+    link_edit: Option<LinkEdit>,
+    history_row_height: Option<f32>,
+    /// Set while `SHORTYIO_BENCH_HISTORY` is timing the history window.
+    history_bench: Option<bench::FrameTimes>,
+    stats_prefetch: StatsPrefetcher,
+    keep_clipboard: bool,
+    primary_selection: bool,
+    headers_draft: String,
+    reserved_draft: String,
+    presets: Presets,
+    utm_template: String,
+    counters: Counters,
+    blocked_destinations: String,
+    tags: String,
+    naming: NamingRules,
+    show_drafts: bool,
+    drafts: Drafts,
+    draft_busy: Option<String>,
+    draft_status: Option<String>,
+    page_titles: HashMap<String, Option<String>>,
+    slack_loading: bool,
+    pins: Pins,
+    show_signature: bool,
+    health: HealthMonitor,
+    connectivity: Connectivity,
+    clicks_limit_renew: RenewMode,
+    renewer: Renewer,
+    show_expiring: bool,
+    expiring_days: i64,
+    expiring: Option<Vec<LinkResponse>>,
+    expiring_loading: bool,
+    expiring_error: Option<String>,
+    extending: Option<String>,
+    show_retag: bool,
+    retag_url_contains: String,
+    retag_before: String,
+    retag_add: String,
+    retag_remove: String,
+    retag_matches: Option<Vec<LinkResponse>>,
+    retag_busy: bool,
+    retag_status: Option<String>,
+    show_duplicates: bool,
+    duplicate_groups: Option<Vec<DuplicateGroup>>,
+    duplicate_survivors: HashMap<String, String>,
+    duplicates_redirect: bool,
+    duplicates_busy: bool,
+    duplicates_status: Option<String>,
+    domain_fallbacks: Option<(String, DomainFallbacks)>,
+    domain_fallbacks_busy: bool,
+    domain_fallbacks_status: Option<String>,
+    framing: HashMap<String, Result<Framing, String>>,
+    framing_loading: bool,
+    path_suggestions: Option<(String, Vec<String>)>,
+    suggestions_loading: bool,
+    favicons: Favicons,
+    recent: RecentResults,
+    toasts: Toasts,
+    show_required: bool,
+    advanced_tab: AdvancedTab,
+    focus_url: bool,
+    nav: Navigation,
+    popouts: Vec<Popout>,
+    /// The active profile's logo and the file it was loaded from.
+    profile_logo: Option<(String, Option<egui::TextureHandle>)>,
+    history_selected: HashSet<String>,
+    show_live_qr: bool,
+    show_burst: bool,
+    burst: Burst,
+    burst_url: String,
+    /// Paths like `booth-###`, see `burst::path`.
+    burst_pattern: String,
+    burst_next: u32,
+    burst_poster_dir: Option<PathBuf>,
+    burst_error: Option<String>,
+    nfc_busy: bool,
+    redirect_test_busy: bool,
+    /// Outcome of "Test redirect" on the result card.
+    redirect_test: Option<Health>,
+    nfc_status: Option<Result<String, String>>,
+    /// Short URL of the latest result if its destination has no share image.
+    og_missing: Option<String>,
+    og_card: Option<PathBuf>,
+    og_image_url: String,
+    og_busy: bool,
+    og_status: Option<String>,
+    show_thumbnails: bool,
+    screenshot_service: String,
+    thumbnails: Thumbnails,
+    history_query: String,
+    /// Tags a history entry must all have to be listed.
+    history_tags: HashSet<String>,
+    show_insights: bool,
+    show_rollups: bool,
+    rollup_period: String,
+    rollup_by_folder: bool,
+    rollup_stats: Option<RollupStats>,
+    rollup_loading: bool,
+    rollup_error: Option<String>,
+    report_format: ReportFormat,
+    /// Set when there's nowhere to save settings until the user picks a place.
+    storage_problem: Option<String>,
+    config_watched: bool,
+    plugins_loaded: bool,
+    plugin_actions: Vec<plugins::Action>,
+    plugin_busy: bool,
+    plugin_status: Option<Result<String, String>>,
+    compose: Option<Compose>,
+    mastodon_server: String,
+    bio: BioPage,
+    bio_busy: bool,
+    bio_status: Option<Result<String, String>>,
+    campaigns: Campaigns,
+    campaigns_requested: bool,
+    new_campaign_name: String,
+    new_campaign_goal: u64,
+    new_campaign_start: String,
+    new_campaign_end: String,
+    campaign_error: Option<String>,
+    /// IANA name of the display time zone; empty for the system's.
+    time_zone: String,
+    trash_days: u32,
 }
 
+#[cfg(feature = "gui")]
 impl Default for ShortyApp {
     fn default() -> Self {
         let config = Config::load();
+        let retention = config.as_ref().map(Config::retention).unwrap_or_default();
+        let bench_size = bench::history_size();
+        if let Some(count) = bench_size {
+            bench::seed_history(count);
+        }
+        let history_bench = bench_size.map(bench::FrameTimes::new);
+        let history = History::load(retention);
 
-        let original_url = Clipboard::new()
-            .ok()
-            .and_then(|mut clipboard| clipboard.get_text().ok())
-            .filter(|text| {
-                text.starts_with("http://") || text.starts_with("https://")
-            })
-            .unwrap_or_default();
+        let cache_on_disk = config.as_ref().is_some_and(|c| c.cache_on_disk);
+        cache::set_persistent(cache_on_disk);
+        let primary_selection = config.as_ref().is_some_and(|c| c.primary_selection);
+        clipboard::set_primary_selection(primary_selection);
+        let profile = config.as_ref().map(Config::profile).unwrap_or_else(|| Profile::named("Default"));
+        api::configure(profile.request_options());
+        api::warm_up();
+        audit::set_profile(&profile.name);
+        webhook::configure(&profile.webhook_url, &profile.name);
+        let headers_draft = profile.headers_text();
+        let reserved_draft = profile.reserved_text();
+        let blocked_destinations = config.as_ref().map(|c| c.blocked_destinations.clone()).unwrap_or_default();
+        policy::set_local(blocked_destinations.clone());
+        let presets = Presets::start(&profile.presets_url);
+        let redirect_type = profile.default_redirect();
+        let nav = Navigation::load();
+        let screenshot_service = config.as_ref().map(|c| c.screenshot_service.clone()).unwrap_or_default();
+        let thumbnails = Thumbnails::default();
+        thumbnails.set_service(&screenshot_service);
+        let time_zone = config.as_ref().map(|c| c.time_zone.clone()).unwrap_or_default();
+        tz::set(&time_zone);
 
         Self {
-            api_key: config.as_ref().map(|c| c.api_key.clone()).unwrap_or_default(),
-            domain: config.as_ref().map(|c| c.domain.clone()).unwrap_or_default(),
-            original_url,
+            api_key: profile.api_key.clone(),
+            domain: profile.domain.clone(),
+            profiles: config.as_ref().map(|c| c.profiles.clone()).unwrap_or_else(|| vec![profile]),
+            active_profile: config.as_ref().map(|c| c.active_profile).unwrap_or(0),
+            new_profile_name: String::new(),
+            kiosk_unlocked: false,
+            kiosk_pin_entry: String::new(),
+            kiosk_error: None,
+            show_segment: false,
+            segment_stats: None,
+            segment_loading: false,
+            segment_error: None,
+            share_sheet: ShareSheet::default(),
+            cache_on_disk,
+            exclude_domains: config.as_ref().map(|c| c.exclude_domains.join(", ")).unwrap_or_default(),
+            original_url: String::new(),
             custom_path: String::new(),
             cloaking: false,
             password: String::new(),
             password_contact: false,
-            clicks_limit: String::new(),
-            redirect_type: 301,
+            clicks_limit_enabled: false,
+            clicks_limit: 100,
+            expires: String::new(),
+            redirect_type,
             result: None,
             error: None,
             loading: false,
             show_settings: false,
             domains: Vec::new(),
             domains_loading: false,
+            schedule_enabled: false,
+            go_live_at: String::new(),
+            holding_url: String::new(),
+            scheduler: Scheduler::start(),
+            show_rotations: false,
+            rotation_path: String::new(),
+            rotation_start: String::new(),
+            rotation_interval_days: 7,
+            rotation_queue: String::new(),
+            rotation_drafts: HashMap::new(),
+            rotation_error: None,
+            show_watch: false,
+            watcher: None,
+            history: history.clone(),
+            show_history: nav.was_open(Panel::History) || bench_size.is_some(),
+            sync_daemon: SyncDaemon::default(),
+            sync_interval_minutes: config
+                .as_ref()
+                .map(|c| c.sync_interval_minutes)
+                .unwrap_or_else(Config::default_sync_interval),
+            sync_report: None,
+            show_sync_report: false,
+            show_import: false,
+            bookmark_folders: Vec::new(),
+            bookmark_folder: 0,
+            import_error: None,
+            show_markdown: false,
+            markdown_error: None,
+            jobs: Jobs::load(history.clone()),
+            show_jobs: nav.was_open(Panel::Jobs),
+            show_copied: false,
+            show_trash: false,
+            trash_items: Vec::new(),
+            trash_busy: false,
+            trash_error: None,
+            trash_purged: false,
+            duplicate: None,
+            check_updates: config.as_ref().map(|c| c.check_updates).unwrap_or_else(update::enabled_by_default),
+            update_checked: false,
+            startup_check: config.as_ref().is_some_and(|c| c.startup_check),
+            startup_checked: false,
+            diagnostics: None,
+            diagnostics_busy: false,
+            show_diagnostics: false,
+            update_available: None,
+            update_status: None,
+            crash_report: crash::pending_report(),
+            usage: None,
+            autofilled_url: String::new(),
+            window_focused: true,
+            clipboard_read: false,
+            retention,
+            confirm_clear_history: false,
+            show_links: nav.was_open(Panel::Links),
+            link_list: LinkList::default(),
+            link_edit: None,
+            history_row_height: None,
+            history_bench,
+            stats_prefetch: StatsPrefetcher::default(),
+            keep_clipboard: config.as_ref().is_some_and(|c| c.keep_clipboard),
+            primary_selection,
+            headers_draft,
+            reserved_draft,
+            presets,
+            utm_template: String::new(),
+            counters: Counters::load(),
+            blocked_destinations: policy::rules_text(&blocked_destinations),
+            tags: String::new(),
+            naming: config.as_ref().map(|c| c.naming.clone()).unwrap_or_default(),
+            show_drafts: nav.was_open(Panel::Drafts),
+            drafts: Drafts::load(),
+            draft_busy: None,
+            draft_status: None,
+            page_titles: HashMap::new(),
+            slack_loading: false,
+            pins: Pins::load(),
+            show_signature: false,
+            health: HealthMonitor::default(),
+            connectivity: Connectivity::default(),
+            clicks_limit_renew: config.as_ref().map(|c| c.clicks_limit_renew).unwrap_or_default(),
+            renewer: Renewer::default(),
+            show_expiring: false,
+            expiring_days: 14,
+            expiring: None,
+            expiring_loading: false,
+            expiring_error: None,
+            extending: None,
+            show_retag: false,
+            retag_url_contains: String::new(),
+            retag_before: String::new(),
+            retag_add: String::new(),
+            retag_remove: String::new(),
+            retag_matches: None,
+            retag_busy: false,
+            retag_status: None,
+            show_duplicates: false,
+            duplicate_groups: None,
+            duplicate_survivors: HashMap::new(),
+            duplicates_redirect: true,
+            duplicates_busy: false,
+            duplicates_status: None,
+            domain_fallbacks: None,
+            domain_fallbacks_busy: false,
+            domain_fallbacks_status: None,
+            framing: HashMap::new(),
+            framing_loading: false,
+            path_suggestions: None,
+            suggestions_loading: false,
+            favicons: Favicons::default(),
+            recent: RecentResults::load(),
+            toasts: Toasts::default(),
+            show_required: false,
+            advanced_tab: AdvancedTab::default(),
+            focus_url: false,
+            nav,
+            popouts: Vec::new(),
+            profile_logo: None,
+            history_selected: HashSet::new(),
+            show_live_qr: false,
+            show_burst: false,
+            burst: Burst::default(),
+            burst_url: String::new(),
+            burst_pattern: "booth-###".to_string(),
+            burst_next: 1,
+            burst_poster_dir: None,
+            burst_error: None,
+            nfc_busy: false,
+            redirect_test_busy: false,
+            redirect_test: None,
+            nfc_status: None,
+            og_missing: None,
+            og_card: None,
+            og_image_url: String::new(),
+            og_busy: false,
+            og_status: None,
+            show_thumbnails: config.as_ref().is_some_and(|c| c.thumbnails),
+            screenshot_service,
+            thumbnails,
+            history_query: String::new(),
+            history_tags: HashSet::new(),
+            show_insights: false,
+            show_rollups: false,
+            rollup_period: "last30".to_string(),
+            rollup_by_folder: false,
+            rollup_stats: None,
+            rollup_loading: false,
+            rollup_error: None,
+            report_format: ReportFormat::default(),
+            storage_problem: storage::config_dir().is_none().then(String::new),
+            config_watched: false,
+            plugins_loaded: false,
+            plugin_actions: Vec::new(),
+            plugin_busy: false,
+            plugin_status: None,
+            compose: None,
+            mastodon_server: config.as_ref().map(|c| c.mastodon_server.clone()).unwrap_or_default(),
+            bio: BioPage::load(),
+            bio_busy: false,
+            bio_status: None,
+            campaigns: Campaigns::load(),
+            campaigns_requested: false,
+            new_campaign_name: String::new(),
+            new_campaign_goal: 1000,
+            new_campaign_start: tz::today().to_string(),
+            new_campaign_end: (tz::today() + chrono::Days::new(30)).to_string(),
+            campaign_error: None,
+            time_zone,
+            trash_days: config.as_ref().map(|c| c.trash_days).unwrap_or_else(trash::default_days),
         }
     }
 }
 
+#[cfg(feature = "gui")]
 impl ShortyApp {
-    fn create_short_link(&mut self, ctx: egui::Context) {
-        if self.api_key.is_empty() {
-            self.error = Some("API key is required. Click settings (⚙) to configure.".to_string());
-            return;
+    fn save_config(&self) {
+        let mut profiles = self.profiles.clone();
+        // Overridden values stay out of the file; what it had is kept
+        if profiles::env_override(profiles::ENV_API_KEY).is_none() {
+            profiles[self.active_profile].api_key = self.api_key.clone();
         }
-
-        if self.original_url.is_empty() {
-            self.error = Some("Original URL is required".to_string());
-            return;
+        if profiles::env_override(profiles::ENV_DOMAIN).is_none() {
+            profiles[self.active_profile].domain = self.domain.clone();
         }
-
-        let api_key = self.api_key.clone();
-        let domain = if self.domain.is_empty() {
-            None
-        } else {
-            Some(self.domain.clone())
+        let config = Config {
+            profiles,
+            active_profile: self.active_profile,
+            exclude_domains: split_list(&self.exclude_domains),
+            check_updates: self.check_updates,
+            history_max_entries: self.retention.max_entries,
+            history_max_age_days: self.retention.max_age_days,
+            cache_on_disk: self.cache_on_disk,
+            keep_clipboard: self.keep_clipboard,
+            primary_selection: self.primary_selection,
+            blocked_destinations: policy::parse_rules(&self.blocked_destinations),
+            naming: self.naming.clone(),
+            clicks_limit_renew: self.clicks_limit_renew,
+            thumbnails: self.show_thumbnails,
+            screenshot_service: self.screenshot_service.clone(),
+            mastodon_server: self.mastodon_server.clone(),
+            time_zone: self.time_zone.clone(),
+            trash_days: self.trash_days,
+            sync_interval_minutes: self.sync_interval_minutes,
+            startup_check: self.startup_check,
         };
+        if let Err(e) = config.save() {
+            elog!("Failed to save config: {}", e);
+        }
+    }
 
-        let clicks_limit = if self.clicks_limit.is_empty() {
-            None
-        } else {
-            self.clicks_limit.parse::<i32>().ok()
-        };
+    /// Takes on settings edited outside the app. Unsaved changes in the
+    /// settings window are replaced.
+    fn apply_config(&mut self, config: Config) {
+        let retention = config.retention();
+        self.profiles = config.profiles;
+        self.active_profile = config.active_profile;
+        let profile = &self.profiles[self.active_profile];
+        let overridden = profile.clone().with_env_overrides();
+        self.api_key = overridden.api_key;
+        self.domain = overridden.domain;
+        self.headers_draft = profile.headers_text();
+        self.reserved_draft = profile.reserved_text();
+        api::configure(profile.request_options());
+        api::warm_up();
+        audit::set_profile(&profile.name);
+        webhook::configure(&profile.webhook_url, &profile.name);
+        self.presets.set_url(&profile.presets_url);
 
-        let request = CreateLinkRequest {
-            original_url: self.original_url.clone(),
-            path: if self.custom_path.is_empty() {
-                None
-            } else {
-                Some(self.custom_path.clone())
-            },
-            domain,
-            cloaking: if self.cloaking { Some(true) } else { None },
-            password: if self.password.is_empty() {
-                None
-            } else {
-                Some(self.password.clone())
-            },
-            password_contact: if self.password_contact { Some(true) } else { None },
-            allow_duplicates: false,
-            clicks_limit,
-            redirect_type: Some(self.redirect_type),
-            tags: Some(vec!["shortyio".to_string()]),
-        };
+        self.exclude_domains = config.exclude_domains.join(", ");
+        self.check_updates = config.check_updates;
+        self.retention = retention;
+        self.history.set_retention(retention);
+        self.cache_on_disk = config.cache_on_disk;
+        cache::set_persistent(config.cache_on_disk);
+        self.keep_clipboard = config.keep_clipboard;
+        self.primary_selection = config.primary_selection;
+        clipboard::set_primary_selection(config.primary_selection);
+        self.blocked_destinations = policy::rules_text(&config.blocked_destinations);
+        policy::set_local(config.blocked_destinations);
+        self.naming = config.naming;
+        self.clicks_limit_renew = config.clicks_limit_renew;
+        self.show_thumbnails = config.thumbnails;
+        self.screenshot_service = config.screenshot_service;
+        self.thumbnails.set_service(&self.screenshot_service);
+        self.mastodon_server = config.mastodon_server;
+        self.time_zone = config.time_zone;
+        tz::set(&self.time_zone);
+        self.trash_days = config.trash_days;
+        self.sync_interval_minutes = config.sync_interval_minutes;
+        self.startup_check = config.startup_check;
+        self.domains.clear();
+    }
 
-        self.loading = true;
-        self.error = None;
-        self.result = None;
+    fn switch_profile(&mut self, index: usize) {
+        let current = &mut self.profiles[self.active_profile];
+        if profiles::env_override(profiles::ENV_API_KEY).is_none() {
+            current.api_key = std::mem::take(&mut self.api_key);
+        }
+        if profiles::env_override(profiles::ENV_DOMAIN).is_none() {
+            current.domain = std::mem::take(&mut self.domain);
+        }
 
-        std::thread::spawn(move || {
-            let runtime = tokio::runtime::Runtime::new().unwrap();
-            runtime.block_on(async {
-                let client = reqwest::Client::new();
-                let response = client
-                    .post("https://api.short.io/links")
-                    .header("authorization", api_key)
-                    .json(&request)
-                    .send()
-                    .await;
+        self.active_profile = index;
+        let overridden = self.profiles[index].clone().with_env_overrides();
+        self.api_key = overridden.api_key;
+        self.domain = overridden.domain;
+        self.redirect_type = self.profiles[index].default_redirect();
+        self.headers_draft = self.profiles[index].headers_text();
+        self.reserved_draft = self.profiles[index].reserved_text();
+        api::configure(self.profiles[index].request_options());
+        api::warm_up();
+        audit::set_profile(&self.profiles[index].name);
+        webhook::configure(&self.profiles[index].webhook_url, &self.profiles[index].name);
+        self.presets.set_url(&self.profiles[index].presets_url);
+        self.utm_template.clear();
+        self.domains.clear();
+        self.result = None;
+        self.duplicate = None;
+        self.save_config();
+    }
 
-                ctx.request_repaint();
+    /// Kiosk profiles hide the API key and block settings and edit/delete
+    /// actions until unlocked.
+    fn kiosk_locked(&self) -> bool {
+        self.profiles[self.active_profile].kiosk && !self.kiosk_unlocked
+    }
 
-                match response {
-                    Ok(resp) => {
-                        if resp.status().is_success() {
-                            match resp.json::<LinkResponse>().await {
-                                Ok(link) => {
-                                    ctx.data_mut(|data| {
-                                        data.insert_temp(egui::Id::new("result"), Some(link));
-                                        data.insert_temp(egui::Id::new("error"), None::<String>);
-                                        data.insert_temp(egui::Id::new("loading"), false);
-                                    });
-                                }
-                                Err(e) => {
-                                    ctx.data_mut(|data| {
-                                        data.insert_temp(egui::Id::new("result"), None::<LinkResponse>);
-                                        data.insert_temp(
-                                            egui::Id::new("error"),
-                                            Some(format!("Failed to parse response: {}", e)),
-                                        );
-                                        data.insert_temp(egui::Id::new("loading"), false);
-                                    });
-                                }
-                            }
-                        } else {
-                            let status = resp.status();
-                            let error_text = resp.text().await.unwrap_or_default();
-                            ctx.data_mut(|data| {
-                                data.insert_temp(egui::Id::new("result"), None::<LinkResponse>);
-                                data.insert_temp(
-                                    egui::Id::new("error"),
-                                    Some(format!("API error {}: {}", status, error_text)),
-                                );
-                                data.insert_temp(egui::Id::new("loading"), false);
-                            });
-                        }
-                    }
-                    Err(e) => {
-                        ctx.data_mut(|data| {
-                            data.insert_temp(egui::Id::new("result"), None::<LinkResponse>);
-                            data.insert_temp(egui::Id::new("error"), Some(format!("Request failed: {}", e)));
-                            data.insert_temp(egui::Id::new("loading"), false);
-                        });
-                    }
+    fn show_kiosk_lock(&mut self, ui: &mut egui::Ui) {
+        let profile = &self.profiles[self.active_profile];
+        ui.label(format!("Profile: {}", profile.name));
+        ui.label("This profile is in kiosk mode, so settings are locked.");
+        ui.add_space(8.0);
+        let has_pin = !profile.kiosk_pin.is_empty();
+        if has_pin {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.kiosk_pin_entry)
+                    .password(true)
+                    .hint_text("PIN")
+                    .desired_width(120.0),
+            );
+        }
+        ui.horizontal(|ui| {
+            if ui.button("🔓 Unlock").clicked() {
+                if !has_pin || self.kiosk_pin_entry == self.profiles[self.active_profile].kiosk_pin {
+                    self.kiosk_unlocked = true;
+                    self.kiosk_error = None;
+                } else {
+                    self.kiosk_error = Some("Wrong PIN".to_string());
                 }
-            });
+                self.kiosk_pin_entry.clear();
+            }
+            if ui.button("Close").clicked() {
+                self.kiosk_error = None;
+                self.show_settings = false;
+            }
         });
-    }
-
-    fn fetch_domains(&mut self, ctx: egui::Context) {
-        if self.api_key.is_empty() {
-            return;
+        if let Some(error) = &self.kiosk_error {
+            ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("❌ {}", error));
         }
+    }
 
-        let api_key = self.api_key.clone();
-        self.domains_loading = true;
-
+    /// Looks for a URL on the clipboard off the UI thread, as some clipboard
+    /// managers are slow to answer.
+    fn read_clipboard(&self, ctx: egui::Context) {
         std::thread::spawn(move || {
-            let runtime = tokio::runtime::Runtime::new().unwrap();
-            runtime.block_on(async {
-                let client = reqwest::Client::new();
-                let response = client
-                    .get("https://api.short.io/api/domains?limit=100&offset=0")
-                    .header("Authorization", api_key)
-                    .header("accept", "application/json")
-                    .send()
-                    .await;
-
+            if let Some(url) = clipboard_url() {
+                ctx.data_mut(|data| data.insert_temp(egui::Id::new("clipboard_url"), url));
                 ctx.request_repaint();
-
-                match response {
-                    Ok(resp) => {
-                        if resp.status().is_success() {
-                            match resp.json::<Vec<Domain>>().await {
-                                Ok(domains) => {
-                                    ctx.data_mut(|data| {
-                                        data.insert_temp(egui::Id::new("domains"), domains);
-                                        data.insert_temp(egui::Id::new("domains_loading"), false);
-                                    });
-                                }
-                                Err(e) => {
-                                    eprintln!("Failed to parse domains: {}", e);
-                                    ctx.data_mut(|data| {
-                                        data.insert_temp(egui::Id::new("domains"), Vec::<Domain>::new());
-                                        data.insert_temp(egui::Id::new("domains_loading"), false);
-                                    });
-                                }
-                            }
-                        } else {
-                            eprintln!("API error fetching domains: {}", resp.status());
-                            ctx.data_mut(|data| {
-                                data.insert_temp(egui::Id::new("domains"), Vec::<Domain>::new());
-                                data.insert_temp(egui::Id::new("domains_loading"), false);
-                            });
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to fetch domains: {}", e);
-                        ctx.data_mut(|data| {
-                            data.insert_temp(egui::Id::new("domains"), Vec::<Domain>::new());
-                            data.insert_temp(egui::Id::new("domains_loading"), false);
-                        });
-                    }
-                }
-            });
+            }
         });
     }
-}
 
-impl eframe::App for ShortyApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        ctx.data_mut(|data| {
-            if let Some(result) = data.get_temp::<Option<LinkResponse>>(egui::Id::new("result")) {
-                self.result = result;
-                data.remove::<Option<LinkResponse>>(egui::Id::new("result"));
-            }
-            if let Some(error) = data.get_temp::<Option<String>>(egui::Id::new("error")) {
-                self.error = error;
-                data.remove::<Option<String>>(egui::Id::new("error"));
-            }
-            if let Some(loading) = data.get_temp::<bool>(egui::Id::new("loading")) {
-                self.loading = loading;
-                data.remove::<bool>(egui::Id::new("loading"));
-            }
-            if let Some(domains) = data.get_temp::<Vec<Domain>>(egui::Id::new("domains")) {
-                self.domains = domains;
-                data.remove::<Vec<Domain>>(egui::Id::new("domains"));
+    /// Fills in a URL found on the clipboard at startup or when the window
+    /// regains focus, unless the user has typed their own URL since the last
+    /// autofill.
+    fn autofill(&mut self, url: String) {
+        if !self.original_url.is_empty() && self.original_url != self.autofilled_url {
+            return;
+        }
+        // Don't pick up a short link we just copied ourselves
+        let is_own_link = self.result.as_ref().is_some_and(|r| r.short_url == url)
+            || (!self.domain.is_empty() && urls::host(&url) == self.domain.to_lowercase());
+        if !is_own_link {
+            self.original_url = url.clone();
+            self.autofilled_url = url;
+        }
+    }
+
+    fn paste_and_shorten(&mut self, ctx: egui::Context) {
+        match clipboard_url() {
+            Some(url) => {
+                self.original_url = url.clone();
+                self.autofilled_url = url;
+                self.create_short_link(ctx);
             }
-            if let Some(domains_loading) = data.get_temp::<bool>(egui::Id::new("domains_loading")) {
-                self.domains_loading = domains_loading;
-                data.remove::<bool>(egui::Id::new("domains_loading"));
+            None => {
+                self.result = None;
+                self.error = Some("Clipboard doesn't contain a URL".to_string());
             }
-        });
-
-        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
-            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
         }
+    }
 
-        if self.show_settings {
-            // Fetch domains if we haven't yet and API key is set
-            if self.domains.is_empty() && !self.domains_loading && !self.api_key.is_empty() {
-                self.fetch_domains(ctx.clone());
+    fn create_short_link(&mut self, ctx: egui::Context) {
+        if !self.schedule_enabled {
+            if let Some(existing) = self.history.find_by_original(self.original_url.trim()) {
+                self.duplicate = Some(existing);
+                self.error = None;
+                self.result = None;
+                return;
             }
+        }
+        self.submit_link(ctx, false);
+    }
 
-            egui::Window::new("⚙ Settings")
-                .collapsible(false)
-                .resizable(false)
-                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
-                .show(ctx, |ui| {
-                    ui.set_min_width(400.0);
+    /// Tags sent with a new link: the app's own, the team's and the user's.
+    fn link_tags(&self, team: &TeamPresets) -> Vec<String> {
+        let mut tags = vec!["shortyio".to_string()];
+        for tag in team.tags.iter().cloned().chain(split_list(&self.tags)) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        tags
+    }
 
-                    ui.label("API Key:");
-                    let api_key_changed = ui.add(
-                        egui::TextEdit::singleline(&mut self.api_key)
-                            .password(true)
-                            .hint_text("Enter your short.io API key"),
-                    ).changed();
+    /// Problems with each field of the link form, including blocked
+    /// destinations and the naming policy from the config and team presets.
+    fn field_errors(&self) -> FieldErrors {
+        let team = self.presets.current();
+        let tags = self.link_tags(&team);
+        let rules = [&self.naming, &team.naming];
+        FieldErrors {
+            url: form::check_url(&self.original_url, self.show_required)
+                .or_else(|| policy::check(&self.original_url).map(|rule| rule.message())),
+            path: placeholders::unknown(&self.custom_path)
+                .map(|name| format!("Unknown placeholder {}", name))
+                .or_else(|| {
+                    let path = placeholders::sample(&self.custom_path);
+                    slugs::check(&path).or_else(|| rules.iter().find_map(|rules| rules.check_path(&path)))
+                }),
+            tags: rules.iter().find_map(|rules| rules.check_tags(&tags)),
+        }
+    }
 
-                    // If API key changed, fetch domains
-                    if api_key_changed && !self.api_key.is_empty() {
-                        self.fetch_domains(ctx.clone());
-                    }
+    /// A reason to think twice about the custom path, which unlike the field
+    /// errors doesn't stop the link being created.
+    fn path_warning(&self) -> Option<String> {
+        let folders = self.history.read(|entries| reserved::folders(entries.iter().map(|entry| urls::path(&entry.short_url))));
+        let profile = &self.profiles[self.active_profile];
+        // The profile's own prefix is expected, so only what was typed is
+        // checked against the reserved words
+        let path = placeholders::sample(&self.custom_path);
+        reserved::check(&path, &profile.reserved_paths())
+            .or_else(|| profanity::check(&path))
+            .or_else(|| reserved::check_folder(&profile.prefixed_path(&path), &folders))
+    }
 
-                    ui.add_space(8.0);
+    /// Flags every invalid field. Returns whether the form can be submitted.
+    fn validate_form(&mut self) -> bool {
+        self.show_required = true;
+        !self.field_errors().any()
+    }
 
-                    ui.label("Domain:");
-                    if self.domains_loading {
-                        ui.horizontal(|ui| {
-                            ui.spinner();
-                            ui.label("Loading domains...");
-                        });
-                    } else if !self.domains.is_empty() {
-                        let mut selected_label = if self.domain.is_empty() {
-                            "No custom domain".to_string()
-                        } else {
-                            self.domain.clone()
-                        };
+    /// The Advanced Options panel, split into tabs so options stay easy to
+    /// find as more are added.
+    fn show_advanced_options(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        ui.horizontal(|ui| {
+            for tab in AdvancedTab::ALL {
+                ui.selectable_value(&mut self.advanced_tab, tab, tab.label());
+            }
+        });
+        ui.separator();
 
-                        egui::ComboBox::from_id_salt("domain_selector")
-                            .selected_text(&selected_label)
+        match self.advanced_tab {
+            AdvancedTab::Campaign => {
+                let templates = self.presets.current().utm_templates;
+                if !templates.is_empty() {
+                    ui.horizontal(|ui| {
+                        ui.label("UTM template:");
+                        egui::ComboBox::from_id_salt("utm_template")
+                            .selected_text(if self.utm_template.is_empty() { "None" } else { self.utm_template.as_str() })
                             .show_ui(ui, |ui| {
-                                if ui.selectable_value(&mut selected_label, "No custom domain".to_string(), "No custom domain").clicked() {
-                                    self.domain = String::new();
-                                }
-                                for domain in &self.domains {
-                                    if ui.selectable_value(&mut selected_label, domain.hostname.clone(), &domain.hostname).clicked() {
-                                        self.domain = domain.hostname.clone();
-                                    }
+                                ui.selectable_value(&mut self.utm_template, String::new(), "None");
+                                for template in &templates {
+                                    ui.selectable_value(
+                                        &mut self.utm_template,
+                                        template.name.clone(),
+                                        &template.name,
+                                    );
                                 }
                             });
-                    } else {
-                        ui.add(
-                            egui::TextEdit::singleline(&mut self.domain)
-                                .hint_text("Enter API key to load domains"),
-                        );
+                    });
+                } else {
+                    ui.weak("The team presets have no UTM templates");
+                }
+            }
+            AdvancedTab::Access => {
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.cloaking, "Enable cloaking")
+                        .on_hover_text("Hide the redirect in an iframe");
+                    if self.cloaking && !self.original_url.trim().is_empty() {
+                        if self.framing_loading {
+                            ui.spinner();
+                        } else if ui
+                            .small_button("Preview")
+                            .on_hover_text("Check whether the destination allows being shown in an iframe")
+                            .clicked()
+                        {
+                            self.preview_framing(ctx.clone());
+                        }
                     }
-                    ui.add_space(12.0);
+                });
+                if self.cloaking {
+                    match self.framing.get(self.original_url.trim()) {
+                        Some(Ok(Framing::Allowed)) => {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(60, 160, 60),
+                                "✓ The destination can be shown cloaked",
+                            );
+                        }
+                        Some(Ok(Framing::Refused(reason))) => {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(220, 140, 40),
+                                format!("⚠ The destination {}, so the cloaked link will show a blank page", reason),
+                            );
+                        }
+                        Some(Err(e)) => {
+                            ui.weak(format!("Couldn't check the destination: {}", e));
+                        }
+                        None => {}
+                    }
+                }
 
-                    ui.horizontal(|ui| {
-                        if ui.button("Save").clicked() {
-                            let config = Config {
-                                api_key: self.api_key.clone(),
-                                domain: self.domain.clone(),
-                            };
-                            if let Err(e) = config.save() {
-                                eprintln!("Failed to save config: {}", e);
-                            }
-                            self.show_settings = false;
+                ui.add_space(4.0);
+                ui.label("Password (optional):");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.password)
+                        .password(true)
+                        .hint_text("Protect link with password"),
+                );
+
+                ui.checkbox(&mut self.password_contact, "Show contact for password")
+                    .on_hover_text("Provide email to users to get password");
+            }
+            AdvancedTab::Behavior => {
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.clicks_limit_enabled, "Clicks limit")
+                        .on_hover_text("Disable link after this many clicks");
+                    ui.add_enabled(
+                        self.clicks_limit_enabled,
+                        egui::DragValue::new(&mut self.clicks_limit)
+                            .range(1..=i32::MAX)
+                            .speed(1.0)
+                            .suffix(" clicks"),
+                    );
+                });
+
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.label("Expires:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.expires)
+                            .hint_text("in 2 weeks, friday 5pm, end of month…")
+                            .desired_width(200.0),
+                    );
+                });
+                if !self.expires.trim().is_empty() {
+                    match when::parse(&self.expires) {
+                        Some(at) if at > Utc::now() => {
+                            ui.weak(format!(
+                                "→ {} ({})",
+                                tz::local(at).format("%a %Y-%m-%d %H:%M"),
+                                tz::zone().name()
+                            ));
                         }
-                        if ui.button("Cancel").clicked() {
-                            self.show_settings = false;
+                        Some(_) => {
+                            ui.colored_label(egui::Color32::from_rgb(220, 140, 40), "⚠ That time has already passed");
                         }
-                    });
+                        None => {
+                            ui.colored_label(egui::Color32::from_rgb(220, 140, 40), "⚠ Couldn't read that time");
+                        }
+                    }
+                }
+
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.label("Redirect Type:");
+                    redirects::picker(ui, "redirect_type", &mut self.redirect_type);
                 });
+            }
+            AdvancedTab::Schedule => {
+                ui.checkbox(&mut self.schedule_enabled, "Schedule go-live")
+                    .on_hover_text("Point the link at a holding page until the go-live time");
+                if self.schedule_enabled {
+                    ui.label(format!("Go live at ({}):", tz::zone().name()));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.go_live_at)
+                            .hint_text("YYYY-MM-DD HH:MM")
+                            .desired_width(160.0),
+                    );
+                    ui.label("Holding page:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.holding_url)
+                            .hint_text("https://example.com/coming-soon"),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Opens a QR code or click chart for a link in its own window, or
+    /// brings the existing one to the front.
+    fn open_popout(&mut self, kind: PopoutKind, link_id: &str, short_url: &str, ctx: &egui::Context) {
+        if self.popouts.iter().any(|popout| popout.kind == kind && popout.short_url == short_url) {
+            ctx.send_viewport_cmd_to(popout_viewport(kind, short_url), egui::ViewportCommand::Focus);
+            return;
+        }
+        let profile = &self.profiles[self.active_profile];
+        let mut popout = Popout::new(kind, link_id, short_url);
+        popout.poster_logo = (!profile.logo.trim().is_empty()).then(|| std::path::PathBuf::from(profile.logo.trim()));
+        popout.poster_accent = profile.accent;
+        self.popouts.push(popout);
+        if kind == PopoutKind::Stats {
+            self.fetch_popout_stats(link_id, ctx);
+        }
+    }
+
+    /// Loads daily clicks for a chart window or the link it's compared with.
+    fn fetch_popout_stats(&self, link_id: &str, ctx: &egui::Context) {
+        let api_key = self.api_key.clone();
+        let link_id = link_id.to_string();
+        let ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let daily = runtime
+                .block_on(ApiClient::new(api_key).link_stats(&link_id))
+                .map(|stats| stats.daily())
+                .map_err(|e| e.to_string());
+            ctx.data_mut(|data| data.insert_temp(egui::Id::new("popout_stats"), (link_id, daily)));
+            ctx.request_repaint();
+        });
+    }
+
+    fn show_popouts(&mut self, ctx: &egui::Context) {
+        let mut closed = Vec::new();
+        let mut compare = Vec::new();
+        let links: Vec<(String, String)> = self.history.read(|entries| {
+            entries
+                .iter()
+                .rev()
+                .take(200)
+                .map(|entry| (entry.link_id.clone(), entry.short_url.clone()))
+                .collect()
+        });
+        for (i, popout) in self.popouts.iter_mut().enumerate() {
+            let size = match popout.kind {
+                PopoutKind::Qr => [420.0, 560.0],
+                PopoutKind::Stats => [560.0, 340.0],
+            };
+            let title = popout.title();
+            let builder = egui::ViewportBuilder::default()
+                .with_title(&title)
+                .with_inner_size(size);
+            ctx.show_viewport_immediate(popout_viewport(popout.kind, &popout.short_url), builder, |ctx, class| {
+                let body = |ui: &mut egui::Ui| {
+                    if let Some(link) = show_popout(ui, popout, &links) {
+                        compare.push(link);
+                    }
+                };
+                if class == egui::ViewportClass::Embedded {
+                    // Backends without multiple windows get an ordinary window
+                    let mut open = true;
+                    egui::Window::new(&title).open(&mut open).show(ctx, body);
+                    if !open {
+                        closed.push(i);
+                    }
+                } else {
+                    egui::CentralPanel::default().show(ctx, body);
+                    if ctx.input(|i| i.viewport().close_requested()) {
+                        closed.push(i);
+                    }
+                }
+            });
+        }
+        for i in closed.into_iter().rev() {
+            self.popouts.remove(i);
+        }
+        for link_id in compare {
+            self.fetch_popout_stats(&link_id, ctx);
+        }
+    }
+
+    /// Rapid-fire shortening for event check-ins: each Enter queues a link at
+    /// the next numbered path and the field is ready for the next one at once.
+    fn show_burst_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_burst;
+        egui::Window::new("⚡ Burst Mode")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Paths:");
+                    ui.add(egui::TextEdit::singleline(&mut self.burst_pattern).hint_text("booth-###").desired_width(140.0))
+                        .on_hover_text("# marks where the number goes, padded to as many digits as there are #");
+                    ui.label("next");
+                    ui.add(egui::DragValue::new(&mut self.burst_next).range(0..=u32::MAX));
+                    let profile = &self.profiles[self.active_profile];
+                    let next = profile.prefixed_path(&burst::path(&self.burst_pattern, self.burst_next));
+                    ui.label(egui::RichText::new(format!("→ /{}", next)).weak().monospace());
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Posters:");
+                    match &self.burst_poster_dir {
+                        Some(dir) => {
+                            ui.label(egui::RichText::new(dir.display().to_string()).weak())
+                                .on_hover_text("A QR poster PDF for each link is saved here, e.g. for a printer's hot folder");
+                            if ui.small_button("✖").on_hover_text("Don't save posters").clicked() {
+                                self.burst_poster_dir = None;
+                            }
+                        }
+                        None => {
+                            ui.label(egui::RichText::new("Off").weak());
+                        }
+                    }
+                    if ui.small_button("Choose folder…").clicked() {
+                        if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                            self.burst_poster_dir = Some(dir);
+                        }
+                    }
+                });
+                ui.separator();
+
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.burst_url)
+                        .hint_text("Paste or scan a URL and press Enter")
+                        .desired_width(f32::INFINITY),
+                );
+                let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                // Stays focused so a scanner or keyboard can keep going
+                if entered || ui.memory(|memory| memory.focused().is_none()) {
+                    response.request_focus();
+                }
+                if entered && !self.burst_url.trim().is_empty() {
+                    self.add_burst_link(ctx);
+                }
+                if let Some(error) = &self.burst_error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("❌ {}", error));
+                }
+
+                let entries = self.burst.entries();
+                let waiting = self.burst.waiting();
+                if self.burst.throttled() {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(220, 140, 40),
+                        format!("Slowed down by short.io's rate limit; {} waiting", waiting),
+                    );
+                } else if waiting > 0 {
+                    ui.label(egui::RichText::new(format!("{} waiting", waiting)).weak());
+                }
+                let latest = entries.iter().find_map(|entry| match &entry.status {
+                    burst::Status::Created(short_url) => Some(short_url.clone()),
+                    _ => None,
+                });
+                if let Some(short_url) = latest {
+                    ui.add_space(8.0);
+                    ui.vertical_centered(|ui| {
+                        qr::show(ui, &short_url, 200.0);
+                        ui.label(egui::RichText::new(&short_url).monospace().size(18.0));
+                    });
+                }
+                ui.add_space(8.0);
+                egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                    for entry in &entries {
+                        ui.horizontal(|ui| {
+                            match &entry.status {
+                                burst::Status::Queued => {
+                                    ui.spinner();
+                                    ui.label(format!("/{}", entry.path));
+                                }
+                                burst::Status::Created(short_url) => {
+                                    if ui.small_button("📋").on_hover_text("Copy").clicked() {
+                                        clipboard::copy(short_url);
+                                        self.toasts.info("Copied");
+                                    }
+                                    ui.label(egui::RichText::new(short_url).strong());
+                                }
+                                burst::Status::Failed(error) => {
+                                    ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("❌ /{}", entry.path))
+                                        .on_hover_text(error);
+                                }
+                            }
+                            ui.add(egui::Label::new(egui::RichText::new(&entry.original_url).weak().size(11.0)).truncate());
+                        });
+                        if let Some(error) = &entry.poster_error {
+                            ui.label(egui::RichText::new(format!("Poster not saved: {}", error)).weak().size(11.0));
+                        }
+                    }
+                });
+            });
+        self.show_burst = open;
+    }
+
+    fn add_burst_link(&mut self, ctx: &egui::Context) {
+        let url = self.burst_url.trim().to_string();
+        if self.api_key.is_empty() {
+            self.burst_error = Some("API key is required. Click settings (⚙) to configure.".to_string());
+            return;
+        }
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            self.burst_error = Some("URL must start with http:// or https://".to_string());
+            return;
+        }
+        let profile = &self.profiles[self.active_profile];
+        let path = profile.prefixed_path(&burst::path(&self.burst_pattern, self.burst_next));
+        let domain = (!self.domain.is_empty()).then(|| self.domain.clone());
+        let mut request = CreateLinkRequest::simple(url, domain);
+        request.path = Some(path);
+        request.tags = Some(vec!["shortyio".to_string(), "burst".to_string()]);
+        // Check-ins often share a destination; each still gets its own path
+        request.allow_duplicates = true;
+        self.burst.add(&self.api_key, request, self.burst_poster_dir.clone(), &self.history, ctx);
+        self.burst_next = self.burst_next.saturating_add(1);
+        self.burst_url.clear();
+        self.burst_error = None;
+    }
+
+    /// A full-window QR code of the newest link in history, for leaving on a
+    /// projector. Follows along as links are created from anywhere in the app.
+    fn show_live_qr_viewport(&mut self, ctx: &egui::Context) {
+        let latest = self.history.read(|entries| entries.last().map(|entry| entry.short_url.clone()));
+        let builder = egui::ViewportBuilder::default()
+            .with_title("Live QR")
+            .with_inner_size([640.0, 720.0]);
+        let mut open = true;
+        ctx.show_viewport_immediate(egui::ViewportId::from_hash_of("live_qr"), builder, |ctx, class| {
+            // Links from jobs and watched files arrive without a repaint
+            ctx.request_repaint_after(std::time::Duration::from_secs(1));
+            let fullscreen = ctx.input(|i| i.viewport().fullscreen.unwrap_or(false));
+            if ctx.input(|i| i.key_pressed(egui::Key::F11) || (fullscreen && i.key_pressed(egui::Key::Escape))) {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(!fullscreen));
+            }
+            let body = |ui: &mut egui::Ui| {
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
+                    let label = if fullscreen { "Exit full screen" } else { "⛶ Full screen" };
+                    if ui.small_button(label).on_hover_text("F11").clicked() {
+                        ui.ctx().send_viewport_cmd(egui::ViewportCommand::Fullscreen(!fullscreen));
+                    }
+                });
+                ui.vertical_centered(|ui| match &latest {
+                    Some(short_url) => {
+                        let size = ui.available_width().min(ui.available_height() - 80.0).max(64.0);
+                        qr::show(ui, short_url, size);
+                        ui.add_space(12.0);
+                        ui.label(egui::RichText::new(short_url).monospace().size(32.0));
+                    }
+                    None => {
+                        ui.add_space(ui.available_height() / 2.0 - 20.0);
+                        ui.label(egui::RichText::new("Create a link and its QR code appears here").weak().size(20.0));
+                    }
+                });
+            };
+            if class == egui::ViewportClass::Embedded {
+                egui::Window::new("Live QR").open(&mut open).show(ctx, body);
+            } else {
+                egui::CentralPanel::default().show(ctx, body);
+                if ctx.input(|i| i.viewport().close_requested()) {
+                    open = false;
+                }
+            }
+        });
+        self.show_live_qr = open;
+    }
+
+    /// Asks the plugins for their actions in the background.
+    fn load_plugins(&mut self, ctx: egui::Context) {
+        self.plugins_loaded = true;
+        std::thread::spawn(move || {
+            let actions = plugins::discover();
+            ctx.data_mut(|data| data.insert_temp(egui::Id::new("plugin_actions"), actions));
+            ctx.request_repaint();
+        });
+    }
+
+    fn run_plugin(&mut self, action: plugins::Action, ctx: egui::Context) {
+        let Some(link) = self.result.clone() else {
+            return;
+        };
+        let title = self.page_titles.get(&link.original_url).cloned().flatten();
+        self.plugin_busy = true;
+        self.plugin_status = None;
+        std::thread::spawn(move || {
+            let status = plugins::run(&action, &link, title.as_deref())
+                .map(|message| if message.is_empty() { format!("{} done", action.label) } else { message })
+                .map_err(|e| format!("{}: {:#}", action.label, e));
+            ctx.data_mut(|data| data.insert_temp(egui::Id::new("plugin_status"), status));
+            ctx.request_repaint();
+        });
+    }
+
+    fn test_redirect(&mut self, short_url: String, original_url: String, ctx: egui::Context) {
+        self.redirect_test_busy = true;
+        self.redirect_test = None;
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let health = runtime.block_on(health::test(&short_url, &original_url));
+            ctx.data_mut(|data| data.insert_temp(egui::Id::new("redirect_test"), health));
+            ctx.request_repaint();
+        });
+    }
+
+    fn write_nfc(&mut self, short_url: String, ctx: egui::Context) {
+        self.nfc_busy = true;
+        self.nfc_status = None;
+        std::thread::spawn(move || {
+            let status = nfc::write(&short_url).map_err(|e| e.to_string());
+            ctx.data_mut(|data| data.insert_temp(egui::Id::new("nfc_status"), status));
+            ctx.request_repaint();
+        });
+    }
+
+    /// Renders a share card for a link whose destination lacks one and saves
+    /// it where the user picks, ready to upload.
+    fn make_og_card(&mut self, short_url: &str, original_url: &str, ctx: egui::Context) {
+        let Some(path) = rfd::FileDialog::new()
+            .set_file_name(format!("{}.png", urls::path(short_url).replace('/', "-")))
+            .add_filter("PNG", &["png"])
+            .save_file()
+        else {
+            return;
+        };
+        let known_title = self.page_titles.get(original_url).cloned().flatten();
+        let original_url = original_url.to_string();
+        let domain = urls::host(short_url);
+        self.og_busy = true;
+        self.og_status = None;
+        std::thread::spawn(move || {
+            let title = known_title.or_else(|| {
+                let runtime = tokio::runtime::Runtime::new().unwrap();
+                runtime.block_on(titles::fetch(&original_url)).ok().flatten()
+            });
+            let title = title.unwrap_or_else(|| urls::host(&original_url));
+            let logo = favicons::cache_path(&domain).filter(|path| path.exists());
+            let result = og::render(&title, &domain, logo.as_deref())
+                .and_then(|card| Ok(card.save(&path)?))
+                .map(|()| path)
+                .map_err(|e| e.to_string());
+            ctx.data_mut(|data| data.insert_temp(egui::Id::new("og_card"), result));
+            ctx.request_repaint();
+        });
+    }
+
+    fn attach_og_card(&mut self, link_id: String, ctx: egui::Context) {
+        let request = UpdateLinkRequest {
+            image: Some(self.og_image_url.trim().to_string()),
+            ..Default::default()
+        };
+        let api_key = self.api_key.clone();
+        self.og_busy = true;
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let result = runtime
+                .block_on(ApiClient::new(api_key).update_link(&link_id, &request))
+                .map(|_| ())
+                .map_err(|e| e.to_string());
+            ctx.data_mut(|data| data.insert_temp(egui::Id::new("og_attached"), result));
+            ctx.request_repaint();
+        });
+    }
+
+    /// Clears the link itself for the next one, keeping the advanced options.
+    fn shorten_another(&mut self) {
+        self.original_url.clear();
+        self.custom_path.clear();
+        self.result = None;
+        self.error = None;
+        self.nfc_status = None;
+        self.plugin_status = None;
+        self.redirect_test = None;
+        self.duplicate = None;
+        self.show_required = false;
+        self.focus_url = true;
+    }
+
+    /// Advanced options that differ from their defaults, with a short label.
+    fn option_chips(&self) -> Vec<(OptionChip, String)> {
+        let mut chips = Vec::new();
+        if !self.utm_template.is_empty() {
+            chips.push((OptionChip::Utm, format!("UTM: {}", self.utm_template)));
+        }
+        if self.cloaking {
+            chips.push((OptionChip::Cloaking, "Cloaked".to_string()));
+        }
+        if !self.password.is_empty() {
+            chips.push((OptionChip::Password, "Password".to_string()));
+        }
+        if self.clicks_limit_enabled {
+            chips.push((OptionChip::ClicksLimit, format!("{} clicks max", self.clicks_limit)));
+        }
+        if !self.expires.trim().is_empty() {
+            chips.push((OptionChip::Expires, format!("Expires {}", self.expires.trim())));
+        }
+        if self.redirect_type != self.profiles[self.active_profile].default_redirect() {
+            chips.push((OptionChip::Redirect, redirects::label(self.redirect_type)));
+        }
+        if self.schedule_enabled {
+            chips.push((OptionChip::Schedule, format!("Goes live {}", self.go_live_at.trim())));
+        }
+        chips
+    }
+
+    fn clear_option(&mut self, chip: OptionChip) {
+        match chip {
+            OptionChip::Utm => self.utm_template.clear(),
+            OptionChip::Cloaking => self.cloaking = false,
+            OptionChip::Password => {
+                self.password.clear();
+                self.password_contact = false;
+            }
+            OptionChip::ClicksLimit => self.clicks_limit_enabled = false,
+            OptionChip::Expires => self.expires.clear(),
+            OptionChip::Redirect => self.redirect_type = self.profiles[self.active_profile].default_redirect(),
+            OptionChip::Schedule => self.schedule_enabled = false,
+        }
+    }
+
+    /// The active profile's logo, loaded again only when its file changes.
+    fn profile_logo(&mut self, ctx: &egui::Context) -> Option<egui::TextureHandle> {
+        let path = self.profiles[self.active_profile].logo.trim().to_string();
+        if path.is_empty() {
+            return None;
+        }
+        if self.profile_logo.as_ref().is_none_or(|(loaded, _)| *loaded != path) {
+            let texture = match image::open(&path) {
+                Ok(image) => {
+                    let rgba = image.thumbnail(128, 128).to_rgba8();
+                    let size = [rgba.width() as usize, rgba.height() as usize];
+                    let image = egui::ColorImage::from_rgba_unmultiplied(size, rgba.as_raw());
+                    Some(ctx.load_texture("profile-logo", image, Default::default()))
+                }
+                Err(e) => {
+                    elog!("Failed to load profile logo {}: {}", path, e);
+                    None
+                }
+            };
+            self.profile_logo = Some((path, texture));
+        }
+        self.profile_logo.as_ref().and_then(|(_, texture)| texture.clone())
+    }
+
+    /// Favicon and hostname of the domain new links are created on.
+    fn show_domain_badge(&self, ui: &mut egui::Ui) {
+        if self.domain.is_empty() {
+            return;
+        }
+        let response = ui
+            .horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = 4.0;
+                if let Some(texture) = self.favicons.get(&self.domain, ui.ctx()) {
+                    ui.image((texture.id(), egui::vec2(16.0, 16.0)));
+                }
+                ui.label(egui::RichText::new(&self.domain).size(12.0).weak());
+            })
+            .response;
+        response.on_hover_text(format!(
+            "New links are created on {} ({} profile)",
+            self.domain, self.profiles[self.active_profile].name
+        ));
+    }
+
+    fn show_signature_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        egui::Window::new("✉ Pinned Links")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(460.0)
+            .show(ctx, |ui| {
+                if self.pins.items.is_empty() {
+                    ui.label(egui::RichText::new("Pin links from History (📌) to add them here").weak());
+                    return;
+                }
+                let mut changed = false;
+                let mut unpin = None;
+                egui::ScrollArea::vertical().max_height(260.0).show(ui, |ui| {
+                    for (index, pin) in self.pins.items.iter_mut().enumerate() {
+                        ui.group(|ui| {
+                            ui.set_min_width(ui.available_width());
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new(&pin.short_url).strong());
+                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                    if ui.small_button("✖").on_hover_text("Unpin").clicked() {
+                                        unpin = Some(index);
+                                    }
+                                });
+                            });
+                            match self.health.get(&pin.short_url) {
+                                Some(health) => {
+                                    let color = if health.ok {
+                                        egui::Color32::from_rgb(60, 179, 113)
+                                    } else {
+                                        egui::Color32::from_rgb(220, 60, 60)
+                                    };
+                                    ui.colored_label(color, egui::RichText::new(&health.message).size(11.0)).on_hover_text(
+                                        format!("Checked {}", health.checked_at.with_timezone(&Local).format("%H:%M")),
+                                    );
+                                }
+                                None => {
+                                    ui.label(egui::RichText::new("Checking…").weak().size(11.0));
+                                }
+                            }
+                            ui.horizontal(|ui| {
+                                ui.label("Text:");
+                                changed |= ui
+                                    .add(egui::TextEdit::singleline(&mut pin.label).hint_text(pin.short_url.as_str()))
+                                    .changed();
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("UTM:");
+                                for (value, hint) in [
+                                    (&mut pin.utm.source, "source"),
+                                    (&mut pin.utm.medium, "medium"),
+                                    (&mut pin.utm.campaign, "campaign"),
+                                ] {
+                                    changed |= ui
+                                        .add(egui::TextEdit::singleline(value).hint_text(hint).desired_width(90.0))
+                                        .changed();
+                                }
+                            });
+                        });
+                    }
+                });
+                if let Some(index) = unpin {
+                    let pin = self.pins.items.remove(index);
+                    self.toasts.with_undo("Unpinned", Undo::Pin(index, pin));
+                    changed = true;
+                }
+                if changed {
+                    self.pins.save();
+                }
+
+                ui.add_space(8.0);
+                let html = self.pins.signature_html();
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("HTML").strong());
+                    if ui.button("📋 Copy HTML").clicked() {
+                        clipboard::copy(&html);
+                        self.toasts.info("Signature HTML copied");
+                    }
+                });
+                ui.add(
+                    egui::TextEdit::multiline(&mut html.as_str())
+                        .code_editor()
+                        .desired_rows(4)
+                        .desired_width(f32::INFINITY),
+                );
+                if let Some(path) = pins::signature_path() {
+                    ui.label(
+                        egui::RichText::new(format!("Kept up to date in {}", path.display()))
+                            .weak()
+                            .size(11.0),
+                    );
+                }
+
+                ui.add_space(8.0);
+                ui.collapsing("🌐 Link-in-bio page", |ui| self.show_bio_page(ui, ctx));
+            });
+        self.show_signature &= open;
+    }
+
+    /// Settings for the page of pinned links and where to publish it.
+    fn show_bio_page(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let mut changed = false;
+        let bio = &mut self.bio;
+        egui::Grid::new("bio_page").num_columns(2).show(ui, |ui| {
+            ui.label("Title:");
+            changed |= ui.text_edit_singleline(&mut bio.title).changed();
+            ui.end_row();
+            ui.label("About:");
+            changed |= ui.text_edit_singleline(&mut bio.about).changed();
+            ui.end_row();
+            ui.label("Publish to:");
+            ui.horizontal(|ui| {
+                for kind in publish::Kind::ALL {
+                    changed |= ui.selectable_value(&mut bio.target.kind, kind, kind.label()).changed();
+                }
+            });
+            ui.end_row();
+            let target = &mut bio.target;
+            let fields: Vec<(&str, &mut String, bool)> = match target.kind {
+                publish::Kind::Folder => vec![("Folder:", &mut target.folder, false)],
+                publish::Kind::Ftp => vec![
+                    ("Host:", &mut target.ftp_host, false),
+                    ("User:", &mut target.ftp_user, false),
+                    ("Password:", &mut target.ftp_password, true),
+                    ("File path:", &mut target.ftp_path, false),
+                ],
+                publish::Kind::S3 => vec![
+                    ("Endpoint:", &mut target.s3_endpoint, false),
+                    ("Region:", &mut target.s3_region, false),
+                    ("Bucket:", &mut target.s3_bucket, false),
+                    ("Object key:", &mut target.s3_key, false),
+                    ("Access key:", &mut target.s3_access_key, false),
+                    ("Secret key:", &mut target.s3_secret_key, true),
+                ],
+            };
+            for (label, value, secret) in fields {
+                ui.label(label);
+                changed |= ui.add(egui::TextEdit::singleline(value).password(secret)).changed();
+                ui.end_row();
+            }
+            ui.label("Public URL:");
+            changed |= ui
+                .add(egui::TextEdit::singleline(&mut bio.public_url).hint_text("https://example.com/links/"))
+                .on_hover_text("Where the published page can be opened; the short link points here")
+                .changed();
+            ui.end_row();
+            if bio.short_url.is_empty() {
+                ui.label("Short path:");
+                changed |= ui.add(egui::TextEdit::singleline(&mut bio.path).hint_text("bio")).changed();
+                ui.end_row();
+            }
+        });
+        if bio.target.kind == publish::Kind::Folder && ui.button("Choose folder…").clicked() {
+            if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                bio.target.folder = folder.display().to_string();
+                changed = true;
+            }
+        }
+        if changed {
+            bio.save();
+        }
+
+        ui.horizontal(|ui| {
+            if self.bio_busy {
+                ui.spinner();
+            } else if ui
+                .add_enabled(!self.pins.items.is_empty(), egui::Button::new("Publish"))
+                .on_hover_text("Upload the page, and create its short link the first time")
+                .clicked()
+            {
+                self.publish_bio(ctx.clone());
+            }
+            if ui.button("💾 Save HTML…").clicked() {
+                if let Some(path) = rfd::FileDialog::new().set_file_name("index.html").save_file() {
+                    if let Err(e) = fs::write(&path, self.bio.html(&self.pins.items)) {
+                        elog!("Failed to save link-in-bio page: {}", e);
+                    }
+                }
+            }
+            if !self.bio.short_url.is_empty() && ui.button("📋 Copy short link").clicked() {
+                clipboard::copy(&self.bio.short_url);
+                self.toasts.info("Copied");
+            }
+        });
+        if let Some(at) = self.bio.published_at {
+            ui.label(
+                egui::RichText::new(format!("Published {}", at.with_timezone(&Local).format("%Y-%m-%d %H:%M")))
+                    .weak()
+                    .size(11.0),
+            );
+        }
+        match &self.bio_status {
+            Some(Ok(message)) => {
+                ui.colored_label(egui::Color32::from_rgb(60, 160, 60), format!("✔ {}", message));
+            }
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::from_rgb(220, 60, 60), e);
+            }
+            None => {}
+        }
+    }
+
+    /// Uploads the page, then gives it a short link unless it has one.
+    fn publish_bio(&mut self, ctx: egui::Context) {
+        let html = self.bio.html(&self.pins.items);
+        let bio = self.bio.clone();
+        let needs_link = bio.short_url.is_empty() && !bio.public_url.trim().is_empty();
+        let mut request = CreateLinkRequest::simple(bio.public_url.trim().to_string(), (!self.domain.is_empty()).then(|| self.domain.clone()));
+        if !bio.path.trim().is_empty() {
+            request.path = Some(self.profiles[self.active_profile].prefixed_path(bio.path.trim()));
+        }
+        let api_key = self.api_key.clone();
+        let history = self.history.clone();
+        self.bio_busy = true;
+        self.bio_status = None;
+        std::thread::spawn(move || {
+            let result = bio.target.publish(&html).and_then(|()| {
+                if !needs_link {
+                    return Ok(None);
+                }
+                let runtime = tokio::runtime::Runtime::new()?;
+                let link = runtime.block_on(ApiClient::new(api_key).create_link(&request))?;
+                history.add(HistoryEntry {
+                    link_id: link.id.clone(),
+                    short_url: link.short_url.clone(),
+                    original_url: link.original_url.clone(),
+                    title: Some(bio.title.clone()).filter(|title| !title.is_empty()),
+                    tags: request.tags.clone().unwrap_or_default(),
+                    created_at: Utc::now(),
+                    clicks_limit: None,
+                    renewed: false,
+                    deleted_remotely: false,
+                });
+                Ok(Some(link.short_url))
+            });
+            let result = result.map_err(|e| format!("{:#}", e));
+            ctx.data_mut(|data| data.insert_temp(egui::Id::new("bio_published"), result));
+            ctx.request_repaint();
+        });
+    }
+
+    /// Copies `<short_url|page title>` for pasting into Slack, fetching the
+    /// destination's title first unless it is already known.
+    fn copy_for_slack(&mut self, short_url: &str, original_url: &str, title: Option<&str>, ctx: egui::Context) {
+        let known = title
+            .map(str::to_string)
+            .or_else(|| self.page_titles.get(original_url).cloned().flatten());
+        if known.is_some() || self.page_titles.contains_key(original_url) {
+            clipboard::copy(&titles::slack_link(short_url, known.as_deref()));
+            self.toasts.info("Copied for Slack");
+            return;
+        }
+        self.slack_loading = true;
+        let short_url = short_url.to_string();
+        let original_url = original_url.to_string();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let title = runtime.block_on(titles::fetch(&original_url)).unwrap_or_else(|e| {
+                elog!("Failed to fetch title of {}: {}", original_url, e);
+                None
+            });
+            ctx.data_mut(|data| {
+                data.insert_temp(egui::Id::new("slack_title"), (short_url, original_url, title));
+            });
+            ctx.request_repaint();
+        });
+    }
+
+    /// Opens the post composer for a link, fetching the page title to
+    /// suggest if it isn't known yet.
+    fn start_compose(&mut self, short_url: String, original_url: String, ctx: egui::Context) {
+        let known = self.page_titles.get(&original_url).cloned();
+        self.compose = Some(Compose {
+            text: social::suggested_text(known.clone().flatten().as_deref(), &short_url),
+            short_url,
+            original_url: original_url.clone(),
+            network: self.compose.as_ref().map(|c| c.network).unwrap_or_default(),
+        });
+        if known.is_some() {
+            return;
+        }
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let title = runtime.block_on(titles::fetch(&original_url)).unwrap_or_else(|e| {
+                elog!("Failed to fetch title of {}: {}", original_url, e);
+                None
+            });
+            ctx.data_mut(|data| data.insert_temp(egui::Id::new("compose_title"), (original_url, title)));
+            ctx.request_repaint();
+        });
+    }
+
+    fn show_compose_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut server_changed = false;
+        let mut post = None;
+        egui::Window::new("📣 Post link")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                let Some(compose) = self.compose.as_mut() else {
+                    return;
+                };
+                ui.horizontal(|ui| {
+                    for network in Network::ALL {
+                        ui.selectable_value(&mut compose.network, network, network.label());
+                    }
+                });
+                if compose.network == Network::Mastodon {
+                    ui.horizontal(|ui| {
+                        ui.label("Server:");
+                        server_changed = ui
+                            .add(egui::TextEdit::singleline(&mut self.mastodon_server).hint_text("mastodon.social"))
+                            .lost_focus();
+                    });
+                }
+                ui.add(egui::TextEdit::multiline(&mut compose.text).desired_rows(4).desired_width(f32::INFINITY));
+
+                let count = compose.network.count(&compose.text);
+                let limit = compose.network.limit();
+                let counter = format!("{} / {}", count, limit);
+                ui.horizontal(|ui| {
+                    if count > limit {
+                        ui.colored_label(egui::Color32::from_rgb(220, 60, 60), counter);
+                    } else {
+                        ui.label(egui::RichText::new(counter).weak());
+                    }
+                    ui.label(egui::RichText::new("Links count as 23").weak().size(11.0));
+                });
+                ui.horizontal(|ui| {
+                    let ready = count <= limit
+                        && !compose.text.trim().is_empty()
+                        && (compose.network != Network::Mastodon || !self.mastodon_server.trim().is_empty());
+                    if ui.add_enabled(ready, egui::Button::new("Open compose page")).clicked() {
+                        post = Some(compose.network.compose_url(&compose.text, &self.mastodon_server));
+                    }
+                    if ui.button("📋 Copy text").clicked() {
+                        clipboard::copy(&compose.text);
+                        self.toasts.info("Copied");
+                    }
+                });
+            });
+        if server_changed {
+            self.save_config();
+        }
+        if let Some(url) = post {
+            ctx.open_url(egui::OpenUrl::new_tab(url));
+            open = false;
+        }
+        if !open {
+            self.compose = None;
+        }
+    }
+
+    /// Keeps the form as a draft for someone with an API key to approve.
+    fn save_draft(&mut self) {
+        if !self.validate_form() {
+            return;
+        }
+        self.drafts.add(Draft::new(
+            self.original_url.clone(),
+            self.custom_path.clone(),
+            split_list(&self.tags),
+        ));
+        self.error = None;
+        self.draft_status = Some("Saved as draft".to_string());
+        self.show_drafts = true;
+    }
+
+    fn approve_draft(&mut self, draft: Draft, ctx: egui::Context) {
+        let team = self.presets.current();
+        let domain = [&self.domain, &team.domain].into_iter().find(|domain| !domain.is_empty()).cloned();
+        let mut request = CreateLinkRequest::simple(draft.original_url.clone(), domain);
+        if !draft.path.is_empty() {
+            request.path = Some(self.profiles[self.active_profile].prefixed_path(&draft.path));
+        }
+        let mut tags = request.tags.take().unwrap_or_default();
+        for tag in team.tags.iter().chain(&draft.tags) {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        request.tags = Some(tags);
+
+        let api_key = self.api_key.clone();
+        let history = self.history.clone();
+        self.draft_busy = Some(draft.id.clone());
+        self.draft_status = None;
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let result = runtime.block_on(ApiClient::new(api_key).create_link(&request));
+            if let Ok(link) = &result {
+                history.add(HistoryEntry {
+                    link_id: link.id.clone(),
+                    short_url: link.short_url.clone(),
+                    original_url: link.original_url.clone(),
+                    title: None,
+                    tags: request.tags.clone().unwrap_or_default(),
+                    created_at: Utc::now(),
+                    clicks_limit: None,
+                    renewed: false,
+                    deleted_remotely: false,
+                });
+            }
+            ctx.data_mut(|data| {
+                data.insert_temp(egui::Id::new("draft_result"), (draft.id, result.map_err(|e| e.to_string())));
+            });
+            ctx.request_repaint();
+        });
+    }
+
+    fn show_drafts_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let can_approve = !self.api_key.is_empty() && !self.kiosk_locked();
+        let mut approve = None;
+        let mut reject = None;
+        egui::Window::new("🗂 Drafts")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Export…").on_hover_text("Save the drafts for a reviewer").clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .set_file_name("shortyio-drafts.json")
+                            .add_filter("JSON", &["json"])
+                            .save_file()
+                        {
+                            self.draft_status = Some(match self.drafts.export(&path) {
+                                Ok(()) => format!("Exported {} drafts", self.drafts.items().len()),
+                                Err(e) => format!("Export failed: {}", e),
+                            });
+                        }
+                    }
+                    if ui.button("Import…").on_hover_text("Load drafts to review").clicked() {
+                        if let Some(path) = rfd::FileDialog::new().add_filter("JSON", &["json"]).pick_file() {
+                            self.draft_status = Some(match self.drafts.import(&path) {
+                                Ok(added) => format!("Imported {} new drafts", added),
+                                Err(e) => format!("Import failed: {}", e),
+                            });
+                        }
+                    }
+                });
+                if let Some(status) = &self.draft_status {
+                    ui.label(egui::RichText::new(status).size(11.0));
+                }
+                ui.separator();
+
+                let output = self.nav.scroll_area(Panel::Drafts, egui::ScrollArea::vertical().max_height(300.0)).show(ui, |ui| {
+                    if self.drafts.items().is_empty() {
+                        ui.label(egui::RichText::new("No drafts").weak());
+                    }
+                    for draft in self.drafts.items() {
+                        ui.group(|ui| {
+                            ui.set_min_width(ui.available_width());
+                            ui.add(egui::Label::new(egui::RichText::new(&draft.original_url).strong()).truncate());
+                            let mut detail = format!(
+                                "by {} on {}",
+                                if draft.author.is_empty() { "unknown" } else { draft.author.as_str() },
+                                draft.created_at.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+                            );
+                            if !draft.path.is_empty() {
+                                detail.push_str(&format!("  /{}", draft.path));
+                            }
+                            if !draft.tags.is_empty() {
+                                detail.push_str(&format!("  🏷 {}", draft.tags.join(", ")));
+                            }
+                            ui.label(egui::RichText::new(detail).weak().size(11.0));
+                            ui.horizontal(|ui| {
+                                if self.draft_busy.as_deref() == Some(draft.id.as_str()) {
+                                    ui.spinner();
+                                } else if ui
+                                    .add_enabled(can_approve && self.draft_busy.is_none(), egui::Button::new("✔ Approve & create"))
+                                    .on_disabled_hover_text("Needs an unlocked profile with an API key")
+                                    .clicked()
+                                {
+                                    approve = Some(draft.clone());
+                                }
+                                if ui.button("✖ Reject").clicked() {
+                                    reject = Some(draft.id.clone());
+                                }
+                            });
+                        });
+                    }
+                });
+                self.nav.scrolled(Panel::Drafts, output.state.offset.y);
+            });
+        if let Some(draft) = approve {
+            self.approve_draft(draft, ctx.clone());
+        }
+        if let Some(id) = reject {
+            self.drafts.remove(&id);
+        }
+        self.show_drafts &= open;
+    }
+
+    /// Which template's `{counter}` the link form uses: the selected UTM
+    /// template's if its fields have one, otherwise the custom path's.
+    fn counter_key(&self) -> Option<String> {
+        let team = self.presets.current();
+        let utm = team.utm_templates.iter().find(|t| t.name == self.utm_template);
+        match utm {
+            Some(template) if placeholders::template_uses(template, "counter") => Some(format!("utm:{}", template.name)),
+            _ if placeholders::uses(&self.custom_path, "counter") => Some(format!("path:{}", self.custom_path)),
+            _ => None,
+        }
+    }
+
+    /// The next `{counter}` value of the form's template, with controls to
+    /// change it or start over.
+    fn show_counter(&mut self, ui: &mut egui::Ui, key: &str) {
+        let profile = self.profiles[self.active_profile].name.clone();
+        let mut next = self.counters.peek(&profile, key);
+        ui.horizontal(|ui| {
+            let template = match key.split_once(':') {
+                Some(("utm", name)) => format!("UTM template \"{}\"", name),
+                _ => "this path".to_string(),
+            };
+            ui.weak(format!("Next {{counter}} for {}:", template));
+            if ui.add(egui::DragValue::new(&mut next).range(1..=u64::MAX)).changed() {
+                self.counters.set(&profile, key, next);
+            }
+            if ui
+                .add_enabled(next > 1, egui::Button::new("↺ Reset").small())
+                .on_hover_text("Start this template's counter over from 1")
+                .clicked()
+            {
+                self.counters.reset(&profile, key);
+            }
+        });
+    }
+
+    fn submit_link(&mut self, ctx: egui::Context, allow_duplicates: bool) {
+        self.duplicate = None;
+
+        if self.api_key.is_empty() {
+            self.error = Some("API key is required. Click settings (⚙) to configure.".to_string());
+            return;
+        }
+
+        if !self.validate_form() {
+            self.error = None;
+            return;
+        }
+
+        let team = self.presets.current();
+
+        let api_key = self.api_key.clone();
+        let domain = if !self.domain.is_empty() {
+            Some(self.domain.clone())
+        } else if !team.domain.is_empty() {
+            Some(team.domain.clone())
+        } else {
+            None
+        };
+        // Placeholders are filled in on the worker thread, after the page
+        // title has been fetched if `{title-slug}` needs it
+        let utm = team.utm_templates.iter().find(|t| t.name == self.utm_template).cloned();
+        let destination = self.original_url.trim().to_string();
+        let uses = |name: &str| {
+            placeholders::uses(&self.custom_path, name)
+                || utm.as_ref().is_some_and(|template| placeholders::template_uses(template, name))
+        };
+        let needs_title = uses("title-slug");
+        let counter = match self.counter_key() {
+            Some(key) => self.counters.take(&self.profiles[self.active_profile].name, &key),
+            None => 0,
+        };
+        let known_title = self.page_titles.get(&destination).cloned();
+        let tags = self.link_tags(&team);
+
+        let clicks_limit = self.clicks_limit_enabled.then_some(self.clicks_limit);
+        let expires_at = if self.expires.trim().is_empty() {
+            None
+        } else {
+            match when::parse(&self.expires) {
+                Some(at) if at > Utc::now() => Some(at),
+                Some(_) => {
+                    self.error = Some("Expiration time must be in the future".to_string());
+                    return;
+                }
+                None => {
+                    self.error = Some(format!("Couldn't read the expiration time \"{}\"", self.expires.trim()));
+                    return;
+                }
+            }
+        };
+
+        let mut request = CreateLinkRequest {
+            original_url: String::new(),
+            path: if self.custom_path.is_empty() {
+                None
+            } else {
+                Some(self.profiles[self.active_profile].prefixed_path(&self.custom_path))
+            },
+            domain,
+            cloaking: if self.cloaking { Some(true) } else { None },
+            password: if self.password.is_empty() {
+                None
+            } else {
+                Some(self.password.clone())
+            },
+            password_contact: if self.password_contact { Some(true) } else { None },
+            allow_duplicates,
+            clicks_limit,
+            redirect_type: Some(self.redirect_type),
+            tags: Some(tags),
+            title: None,
+            expires_at,
+        };
+
+        let activation = if self.schedule_enabled {
+            let go_live_at = match NaiveDateTime::parse_from_str(self.go_live_at.trim(), "%Y-%m-%d %H:%M")
+                .ok()
+                .and_then(tz::to_utc)
+            {
+                Some(at) => at,
+                None => {
+                    self.error = Some("Go-live time must be in the format YYYY-MM-DD HH:MM".to_string());
+                    return;
+                }
+            };
+            if go_live_at <= Utc::now() {
+                self.error = Some("Go-live time must be in the future".to_string());
+                return;
+            }
+            if self.holding_url.is_empty() {
+                self.error = Some("A holding page URL is required for scheduled links".to_string());
+                return;
+            }
+            // Several scheduled links may share one holding page
+            request.allow_duplicates = true;
+            Some((self.holding_url.clone(), go_live_at))
+        } else {
+            None
+        };
+        let scheduler = self.scheduler.clone();
+        let history = self.history.clone();
+        let profile_name = self.profiles[self.active_profile].name.clone();
+
+        self.loading = true;
+        self.error = None;
+        self.result = None;
+        self.nfc_status = None;
+        self.plugin_status = None;
+        self.redirect_test = None;
+        self.og_missing = None;
+        self.og_card = None;
+        self.og_status = None;
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            runtime.block_on(async {
+                let title = match known_title {
+                    Some(title) => title,
+                    None if needs_title => titles::fetch(&destination).await.unwrap_or_else(|e| {
+                        elog!("Failed to fetch title of {}: {}", destination, e);
+                        None
+                    }),
+                    None => None,
+                };
+                let values = placeholders::Values::new(&destination, counter, title.as_deref());
+                request.original_url = match &utm {
+                    Some(template) => values.utm(template).apply(&destination),
+                    None => destination,
+                };
+                request.path = request.path.map(|path| values.expand(&path));
+                let activation = activation
+                    .map(|(holding_url, go_live_at)| (std::mem::replace(&mut request.original_url, holding_url), go_live_at));
+
+                let client = ApiClient::new(api_key);
+                let response = client.create_link(&request).await;
+
+                ctx.request_repaint();
+
+                match response {
+                    Ok(link) => {
+                        if let Some((destination, go_live_at)) = activation {
+                            scheduler.add(ScheduledActivation {
+                                link_id: link.id.clone(),
+                                short_url: link.short_url.clone(),
+                                destination,
+                                go_live_at,
+                                profile: profile_name,
+                                last_error: None,
+                            });
+                        }
+                        history.add(HistoryEntry {
+                            link_id: link.id.clone(),
+                            short_url: link.short_url.clone(),
+                            original_url: link.original_url.clone(),
+                            title: None,
+                            tags: request.tags.clone().unwrap_or_default(),
+                            created_at: Utc::now(),
+                            clicks_limit: request.clicks_limit,
+                            renewed: false,
+                            deleted_remotely: false,
+                        });
+                        let (short_url, original_url) = (link.short_url.clone(), link.original_url.clone());
+                        ctx.data_mut(|data| {
+                            data.insert_temp(egui::Id::new("result"), Some(link));
+                            data.insert_temp(egui::Id::new("error"), None::<String>);
+                            data.insert_temp(egui::Id::new("loading"), false);
+                        });
+                        // Checked after the result shows so slow pages don't hold it up
+                        if let Ok(false) = og::has_image(&original_url).await {
+                            ctx.data_mut(|data| data.insert_temp(egui::Id::new("og_missing"), short_url));
+                            ctx.request_repaint();
+                        }
+                    }
+                    Err(e) => {
+                        ctx.data_mut(|data| {
+                            data.insert_temp(egui::Id::new("result"), None::<LinkResponse>);
+                            data.insert_temp(egui::Id::new("error"), Some(e.to_string()));
+                            data.insert_temp(egui::Id::new("loading"), false);
+                        });
+                    }
+                }
+            });
+        });
+    }
+
+    fn add_rotation(&mut self, ctx: egui::Context) {
+        if self.api_key.is_empty() {
+            self.rotation_error = Some("API key is required. Click settings (⚙) to configure.".to_string());
+            return;
+        }
+        if self.domain.is_empty() {
+            self.rotation_error = Some("Select a domain in settings to rotate its links".to_string());
+            return;
+        }
+        let path = self.rotation_path.trim().trim_start_matches('/').to_string();
+        if path.is_empty() {
+            self.rotation_error = Some("Path is required".to_string());
+            return;
+        }
+        let Some(next_run) = NaiveDateTime::parse_from_str(self.rotation_start.trim(), "%Y-%m-%d %H:%M")
+            .ok()
+            .and_then(tz::to_utc)
+        else {
+            self.rotation_error = Some("First rotation must be in the format YYYY-MM-DD HH:MM".to_string());
+            return;
+        };
+        let queue = parse_url_lines(&self.rotation_queue);
+
+        let api_key = self.api_key.clone();
+        let domain = self.domain.clone();
+        let interval_days = self.rotation_interval_days;
+        let scheduler = self.scheduler.clone();
+        let profile = self.profiles[self.active_profile].name.clone();
+        self.rotation_error = None;
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            runtime.block_on(async {
+                let client = ApiClient::new(api_key);
+                let response = client.expand_link(&domain, &path).await;
+
+                ctx.request_repaint();
+
+                match response {
+                    Ok(link) => scheduler.add_rotation(Rotation {
+                        link_id: link.id,
+                        short_url: link.short_url,
+                        interval_days,
+                        next_run,
+                        queue,
+                        profile,
+                        last_error: None,
+                    }),
+                    Err(e) => ctx.data_mut(|data| {
+                        data.insert_temp(egui::Id::new("rotation_error"), Some(e.to_string()));
+                    }),
+                }
+            });
+        });
+    }
+
+    fn show_rotations_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_rotations;
+        egui::Window::new("🔁 Link Rotations")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().max_height(260.0).show(ui, |ui| {
+                    let rotations = self.scheduler.rotations();
+                    if rotations.is_empty() {
+                        ui.label(egui::RichText::new("No rotations yet").weak());
+                    }
+                    for rotation in &rotations {
+                        ui.group(|ui| {
+                            ui.set_min_width(ui.available_width());
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new(&rotation.short_url).strong());
+                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                    if ui.small_button("✖").on_hover_text("Stop rotating").clicked() {
+                                        self.scheduler.remove_rotation(&rotation.link_id);
+                                        self.rotation_drafts.remove(&rotation.link_id);
+                                    }
+                                });
+                            });
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "Every {} day(s), next at {}",
+                                    rotation.interval_days,
+                                    tz::local(rotation.next_run).format("%Y-%m-%d %H:%M")
+                                ))
+                                .weak()
+                                .size(11.0),
+                            );
+                            if let Some(error) = &rotation.last_error {
+                                ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("Last rotation failed: {}", error));
+                            }
+
+                            ui.label(format!("Queue ({} URLs, one per line):", rotation.queue.len()));
+                            let draft = self
+                                .rotation_drafts
+                                .entry(rotation.link_id.clone())
+                                .or_insert_with(|| rotation.queue.join("\n"));
+                            ui.add(
+                                egui::TextEdit::multiline(draft)
+                                    .desired_rows(3)
+                                    .desired_width(f32::INFINITY),
+                            );
+                            if ui.button("Save queue").clicked() {
+                                self.scheduler.set_queue(&rotation.link_id, parse_url_lines(draft));
+                            }
+                        });
+                    }
+                });
+
+                ui.separator();
+                ui.label(egui::RichText::new("New rotation").strong());
+                ui.label("Path on the current domain:");
+                ui.add(egui::TextEdit::singleline(&mut self.rotation_path).hint_text("latest"));
+                ui.label(format!("First rotation ({}):", tz::zone().name()));
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.rotation_start)
+                        .hint_text("YYYY-MM-DD HH:MM")
+                        .desired_width(160.0),
+                );
+                ui.horizontal(|ui| {
+                    ui.label("Repeat every");
+                    ui.add(egui::DragValue::new(&mut self.rotation_interval_days).range(1..=365));
+                    ui.label("day(s)");
+                });
+                ui.label("Queue (one URL per line):");
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.rotation_queue)
+                        .desired_rows(3)
+                        .desired_width(f32::INFINITY),
+                );
+                if ui.button("Add rotation").clicked() {
+                    self.add_rotation(ctx.clone());
+                }
+                if let Some(error) = &self.rotation_error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("❌ {}", error));
+                }
+            });
+        self.show_rotations = open;
+    }
+
+    fn show_watch_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_watch;
+        egui::Window::new("👁 Watch File")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.set_min_width(400.0);
+                ui.label("Shortens every URL appended to a text or CSV file and writes the results to a companion file.");
+                ui.add_space(8.0);
+
+                if let Some(watcher) = &self.watcher {
+                    let status = watcher.status();
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(format!("Watching {}", watcher.path.display()));
+                    });
+                    ui.label(egui::RichText::new(format!("Writing to {}", watcher.output.display())).weak().size(11.0));
+                    ui.label(format!("Shortened so far: {}", status.shortened));
+                    if let Some(error) = &status.last_error {
+                        ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("❌ {}", error));
+                    }
+                    ui.add_space(8.0);
+                    if ui.button("Stop watching").clicked() {
+                        self.watcher = None;
+                    }
+                    ctx.request_repaint_after(std::time::Duration::from_secs(2));
+                } else if ui.button("Choose file…").clicked() {
+                    if self.api_key.is_empty() {
+                        self.error = Some("API key is required. Click settings (⚙) to configure.".to_string());
+                    } else if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("Text or CSV", &["txt", "csv"])
+                        .pick_file()
+                    {
+                        let domain = if self.domain.is_empty() { None } else { Some(self.domain.clone()) };
+                        self.watcher = Some(FileWatcher::start(path, self.api_key.clone(), domain, ctx.clone()));
+                    }
+                }
+            });
+        self.show_watch = open;
+    }
+
+    /// Calendars of when links were created and clicked, from local history
+    /// and whatever click stats have been fetched.
+    fn show_insights_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_insights;
+        egui::Window::new("📅 Insights")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let (created, recent_ids) = self.history.read(|entries| {
+                    let mut created: HashMap<NaiveDate, u64> = HashMap::new();
+                    for entry in entries {
+                        *created.entry(tz::local(entry.created_at).date_naive()).or_default() += 1;
+                    }
+                    let recent_ids: Vec<String> = entries.iter().rev().take(60).map(|entry| entry.link_id.clone()).collect();
+                    (created, recent_ids)
+                });
+
+                ui.label(egui::RichText::new("Links created").strong());
+                heatmap::show(ui, &created, egui::Color32::from_rgb(64, 160, 255), "links created");
+                if let Some((day, count)) = created.iter().max_by_key(|(day, count)| (**count, **day)) {
+                    ui.label(egui::RichText::new(format!("Busiest day: {} with {} links", day.format("%b %-d, %Y"), count)).weak().size(11.0));
+                }
+                ui.add_space(10.0);
+
+                ui.label(egui::RichText::new("Clicks").strong());
+                let (clicks, links) = self.stats_prefetch.daily_totals();
+                heatmap::show(ui, &clicks, egui::Color32::from_rgb(60, 180, 90), "clicks");
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(format!("Across {} link(s) with stats loaded", links)).weak().size(11.0));
+                    if links < recent_ids.len() && ui.small_button("Load recent links").on_hover_text("Fetch click stats for the latest 60 links").clicked() {
+                        for link_id in &recent_ids {
+                            self.stats_prefetch.request(&self.api_key, link_id, ctx);
+                        }
+                    }
+                });
+                ui.add_space(10.0);
+
+                ui.label(egui::RichText::new("Campaigns").strong());
+                self.show_campaigns(ui);
+            });
+        self.show_insights = open;
+    }
+
+    /// Each campaign's progress towards its goal as a burn-up chart, and a
+    /// form for starting one from the links selected in History.
+    fn show_campaigns(&mut self, ui: &mut egui::Ui) {
+        let mut remove = None;
+        for (index, campaign) in self.campaigns.items.iter().enumerate() {
+            let burn_up = campaign.burn_up(|link_id| self.stats_prefetch.daily(link_id));
+            let total = burn_up.last().map(|(_, total)| *total).unwrap_or_default();
+            ui.group(|ui| {
+                ui.set_min_width(ui.available_width());
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(&campaign.name).strong());
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} – {}, {} link(s)",
+                            campaign.start.format("%b %-d"),
+                            campaign.end.format("%b %-d, %Y"),
+                            campaign.link_ids.len()
+                        ))
+                        .weak()
+                        .size(11.0),
+                    );
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("✖").on_hover_text("Delete campaign").clicked() {
+                            remove = Some(index);
+                        }
+                    });
+                });
+                let progress = total as f32 / campaign.goal.max(1) as f32;
+                ui.add(egui::ProgressBar::new(progress.min(1.0)).text(format!("{} / {} clicks", total, campaign.goal)));
+
+                let days = (campaign.end - campaign.start).num_days().max(1) as f64;
+                let start = campaign.start;
+                egui_plot::Plot::new(("campaign", index))
+                    .height(120.0)
+                    .include_x(days)
+                    .include_y(campaign.goal as f64)
+                    .allow_drag(false)
+                    .allow_zoom(false)
+                    .allow_scroll(false)
+                    .x_axis_formatter(move |mark, _| {
+                        if mark.value >= 0.0 {
+                            (start + chrono::Days::new(mark.value as u64)).format("%b %d").to_string()
+                        } else {
+                            String::new()
+                        }
+                    })
+                    .show(ui, |plot_ui| {
+                        let points: egui_plot::PlotPoints = burn_up
+                            .iter()
+                            .map(|(day, total)| [(*day - start).num_days() as f64, *total as f64])
+                            .collect();
+                        plot_ui.line(egui_plot::Line::new(points).name("Clicks"));
+                        let pace: egui_plot::PlotPoints = vec![[0.0, 0.0], [days, campaign.goal as f64]].into();
+                        plot_ui.line(egui_plot::Line::new(pace).style(egui_plot::LineStyle::dashed_loose()).name("Goal pace"));
+                    });
+            });
+        }
+        if let Some(index) = remove {
+            self.campaigns.items.remove(index);
+            self.campaigns.save();
+        }
+
+        ui.collapsing("New campaign", |ui| {
+            egui::Grid::new("new_campaign").num_columns(2).show(ui, |ui| {
+                ui.label("Name:");
+                ui.text_edit_singleline(&mut self.new_campaign_name);
+                ui.end_row();
+                ui.label("Clicks goal:");
+                ui.add(egui::DragValue::new(&mut self.new_campaign_goal).range(1..=u64::MAX));
+                ui.end_row();
+                ui.label("From:");
+                ui.add(egui::TextEdit::singleline(&mut self.new_campaign_start).hint_text("YYYY-MM-DD"));
+                ui.end_row();
+                ui.label("Until:");
+                ui.add(egui::TextEdit::singleline(&mut self.new_campaign_end).hint_text("YYYY-MM-DD"));
+                ui.end_row();
+            });
+            let selected = self.history_selected.len();
+            ui.label(
+                egui::RichText::new(format!("Links: the {} selected in History", selected))
+                    .weak()
+                    .size(11.0),
+            );
+            let ready = selected > 0 && !self.new_campaign_name.trim().is_empty();
+            if ui.add_enabled(ready, egui::Button::new("Create campaign")).clicked() {
+                self.create_campaign(ui.ctx());
+            }
+            if let Some(error) = &self.campaign_error {
+                ui.colored_label(egui::Color32::from_rgb(220, 60, 60), error);
+            }
+        });
+    }
+
+    fn create_campaign(&mut self, ctx: &egui::Context) {
+        let parse = |text: &str| NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d");
+        let (Ok(start), Ok(end)) = (parse(&self.new_campaign_start), parse(&self.new_campaign_end)) else {
+            self.campaign_error = Some("Dates must be in the format YYYY-MM-DD".to_string());
+            return;
+        };
+        if end < start {
+            self.campaign_error = Some("The end date is before the start date".to_string());
+            return;
+        }
+        let link_ids: Vec<String> = self.history_selected.drain().collect();
+        for link_id in &link_ids {
+            self.stats_prefetch.request(&self.api_key, link_id, ctx);
+        }
+        self.campaigns.items.push(Campaign {
+            name: std::mem::take(&mut self.new_campaign_name).trim().to_string(),
+            link_ids,
+            goal: self.new_campaign_goal,
+            start,
+            end,
+            reached: false,
+        });
+        self.campaigns.save();
+        self.campaign_error = None;
+    }
+
+    /// Tells the user, once, when a campaign reaches its clicks goal.
+    fn check_campaign_goals(&mut self) {
+        let mut changed = false;
+        for campaign in self.campaigns.items.iter_mut().filter(|campaign| !campaign.reached) {
+            let total = campaign
+                .burn_up(|link_id| self.stats_prefetch.daily(link_id))
+                .last()
+                .map(|(_, total)| *total)
+                .unwrap_or_default();
+            if total >= campaign.goal {
+                campaign.reached = true;
+                changed = true;
+                self.toasts.info(format!("🎯 {} reached its goal of {} clicks", campaign.name, campaign.goal));
+            }
+        }
+        if changed {
+            self.campaigns.save();
+        }
+    }
+
+    fn show_history_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_history;
+        let mut slack_copy: Option<(String, String, Option<String>)> = None;
+        let mut popout: Option<(PopoutKind, String, String)> = None;
+        let mut qr_job: Option<(JobOutput, Vec<JobItem>)> = None;
+        let mut sync = false;
+        egui::Window::new("🕘 History")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(460.0)
+            .show(ctx, |ui| {
+                self.history.read(|entries| {
+                    if entries.is_empty() {
+                        ui.label(egui::RichText::new("No links created yet").weak());
+                        return;
+                    }
+                    if !self.history_selected.is_empty() {
+                        let mut output = None;
+                        ui.horizontal(|ui| {
+                            ui.label(format!("{} selected", self.history_selected.len()));
+                            if ui.button("🔳 QR sheet PDF…").on_hover_text("One QR code per page").clicked() {
+                                if let Some(path) = rfd::FileDialog::new()
+                                    .set_file_name("qr-codes.pdf")
+                                    .add_filter("PDF", &["pdf"])
+                                    .save_file()
+                                {
+                                    output = Some(JobOutput::QrSheet { path });
+                                }
+                            }
+                            if ui.button("🔳 QR images…").on_hover_text("One PNG per link, named by path").clicked() {
+                                if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                                    output = Some(JobOutput::QrImages { dir });
+                                }
+                            }
+                            if ui.small_button("Clear").on_hover_text("Clear selection").clicked() {
+                                self.history_selected.clear();
+                            }
+                        });
+                        if let Some(output) = output {
+                            let items = entries
+                                .iter()
+                                .filter(|entry| self.history_selected.contains(&entry.link_id))
+                                .map(|entry| {
+                                    let path = urls::path(&entry.short_url);
+                                    JobItem {
+                                        title: Some(if path.is_empty() { entry.short_url.clone() } else { path.to_string() }),
+                                        short_url: Some(entry.short_url.clone()),
+                                        ..JobItem::new(entry.original_url.clone())
+                                    }
+                                })
+                                .collect();
+                            qr_job = Some((output, items));
+                        }
+                        ui.separator();
+                    }
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.history_query)
+                                .hint_text("🔍 Search URLs and titles")
+                                .desired_width(220.0),
+                        );
+                        if !self.history_tags.is_empty() && ui.small_button("✖ Tags").on_hover_text("Clear tag filter").clicked() {
+                            self.history_tags.clear();
+                        }
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if self.sync_daemon.is_running() {
+                                ui.spinner();
+                            } else if ui
+                                .small_button("⟳")
+                                .on_hover_text("Check for links deleted or edited on the short.io dashboard")
+                                .clicked()
+                            {
+                                sync = true;
+                            }
+                        });
+                    });
+                    show_tag_cloud(ui, entries, &mut self.history_tags);
+                    let query = self.history_query.trim().to_lowercase();
+                    let visible: Vec<&HistoryEntry> = entries
+                        .iter()
+                        .rev()
+                        .filter(|entry| self.history_tags.iter().all(|tag| entry.tags.contains(tag)))
+                        .filter(|entry| {
+                            query.is_empty()
+                                || entry.short_url.to_lowercase().contains(&query)
+                                || entry.original_url.to_lowercase().contains(&query)
+                                || entry.title.as_ref().is_some_and(|title| title.to_lowercase().contains(&query))
+                        })
+                        .collect();
+                    if visible.is_empty() {
+                        ui.label(egui::RichText::new("No links match").weak());
+                    }
+                    let mut grid = self.nav.history_grid();
+                    ui.horizontal(|ui| {
+                        ui.selectable_value(&mut grid, false, "☰ List");
+                        ui.selectable_value(&mut grid, true, "▦ Cards");
+                    });
+                    self.nav.set_history_grid(grid);
+                    let offset = if grid {
+                        const CARD: egui::Vec2 = egui::vec2(150.0, 150.0);
+                        let spacing = ui.spacing().item_spacing;
+                        let columns = (((ui.available_width() + spacing.x) / (CARD.x + spacing.x)) as usize).max(1);
+                        let rows = visible.len().div_ceil(columns);
+                        let output = self
+                            .nav
+                            .scroll_area(Panel::History, egui::ScrollArea::vertical().max_height(420.0))
+                            .auto_shrink([false, true])
+                            .show_rows(ui, CARD.y, rows, |ui, rows| {
+                                for row in rows {
+                                    ui.horizontal(|ui| {
+                                        for entry in visible.iter().skip(row * columns).take(columns) {
+                                            ui.allocate_ui(CARD, |ui| {
+                                                ui.set_min_size(CARD);
+                                                egui::Frame::group(ui.style()).show(ui, |ui| {
+                                                    ui.set_width(CARD.x - 14.0);
+                                                    ui.set_height(CARD.y - 14.0);
+                                                    let size = egui::vec2(
+                                                        ui.available_width(),
+                                                        ui.available_width() * thumbnails::HEIGHT as f32 / thumbnails::WIDTH as f32,
+                                                    );
+                                                    let texture =
+                                                        self.show_thumbnails.then(|| self.thumbnails.get(&entry.original_url, ctx)).flatten();
+                                                    match texture {
+                                                        Some(texture) => {
+                                                            ui.add(egui::Image::new(&texture).fit_to_exact_size(size).rounding(3.0));
+                                                        }
+                                                        None => {
+                                                            let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+                                                            ui.painter().rect_filled(rect, 3.0, ui.visuals().faint_bg_color);
+                                                            ui.painter().text(
+                                                                rect.center(),
+                                                                egui::Align2::CENTER_CENTER,
+                                                                urls::host(&entry.original_url),
+                                                                egui::FontId::proportional(11.0),
+                                                                ui.visuals().weak_text_color(),
+                                                            );
+                                                        }
+                                                    }
+                                                    let title = entry.title.clone().unwrap_or_else(|| urls::host(&entry.original_url));
+                                                    ui.add(egui::Label::new(egui::RichText::new(title).size(12.0)).truncate())
+                                                        .on_hover_text(&entry.original_url);
+                                                    let mut path =
+                                                        egui::RichText::new(format!("/{}", urls::path(&entry.short_url))).strong().monospace();
+                                                    if entry.deleted_remotely {
+                                                        path = path.strikethrough();
+                                                    }
+                                                    ui.add(egui::Label::new(path).truncate());
+                                                    ui.horizontal(|ui| {
+                                                        if ui.small_button("📋").on_hover_text(&entry.short_url).clicked() {
+                                                            clipboard::copy(&entry.short_url);
+                                                            self.toasts.info("Copied");
+                                                        }
+                                                        if ui.small_button("🔳").on_hover_text("QR code in its own window").clicked() {
+                                                            popout = Some((PopoutKind::Qr, entry.link_id.clone(), entry.short_url.clone()));
+                                                        }
+                                                        match self.stats_prefetch.clicks(&entry.link_id) {
+                                                            Some(clicks) => {
+                                                                ui.label(egui::RichText::new(format!("👆 {}", clicks)).weak().size(11.0));
+                                                            }
+                                                            None => self.stats_prefetch.request(&self.api_key, &entry.link_id, ctx),
+                                                        }
+                                                    });
+                                                });
+                                            });
+                                        }
+                                    });
+                                }
+                            });
+                        output.state.offset.y
+                    } else {
+                        // Rows all have the same layout, so only the visible ones
+                        // are built. The height is measured once from a real row.
+                        let row_height = self.history_row_height.unwrap_or(2.0 * ui.spacing().interact_size.y);
+                        let mut measured: f32 = 0.0;
+                        let output = self
+                            .nav
+                            .scroll_area(Panel::History, egui::ScrollArea::vertical().max_height(360.0))
+                            .auto_shrink([false, true])
+                            .show_rows(ui, row_height, visible.len(), |ui, rows| {
+                                for row in rows {
+                                    let entry = visible[row];
+                                    let response = ui.horizontal(|ui| {
+                                        if self.show_thumbnails {
+                                            let size = egui::vec2(64.0, 64.0 * thumbnails::HEIGHT as f32 / thumbnails::WIDTH as f32);
+                                            match self.thumbnails.get(&entry.original_url, ctx) {
+                                                Some(texture) => {
+                                                    ui.add(egui::Image::new(&texture).fit_to_exact_size(size).rounding(3.0));
+                                                }
+                                                None => {
+                                                    ui.allocate_exact_size(size, egui::Sense::hover());
+                                                }
+                                            }
+                                        }
+                                        ui.vertical(|ui| {
+                                            ui.horizontal(|ui| {
+                                                let mut selected = self.history_selected.contains(&entry.link_id);
+                                                if ui.checkbox(&mut selected, "").on_hover_text("Select for QR export").changed() {
+                                                    if selected {
+                                                        self.history_selected.insert(entry.link_id.clone());
+                                                    } else {
+                                                        self.history_selected.remove(&entry.link_id);
+                                                    }
+                                                }
+                                                if ui.small_button("📋").on_hover_text("Copy").clicked() {
+                                                    clipboard::copy(&entry.short_url);
+                                                    self.toasts.info("Copied");
+                                                }
+                                                if ui.small_button("💬").on_hover_text("Copy for Slack").clicked() {
+                                                    slack_copy = Some((
+                                                        entry.short_url.clone(),
+                                                        entry.original_url.clone(),
+                                                        entry.title.clone(),
+                                                    ));
+                                                }
+                                                if ui.small_button("🔳").on_hover_text("QR code in its own window").clicked() {
+                                                    popout = Some((PopoutKind::Qr, entry.link_id.clone(), entry.short_url.clone()));
+                                                }
+                                                if ui.small_button("📈").on_hover_text("Clicks chart in its own window").clicked() {
+                                                    popout = Some((PopoutKind::Stats, entry.link_id.clone(), entry.short_url.clone()));
+                                                }
+                                                if ui
+                                                    .selectable_label(self.pins.contains(&entry.link_id), "📌")
+                                                    .on_hover_text("Pin for the email signature")
+                                                    .clicked()
+                                                {
+                                                    self.pins.toggle(PinnedLink {
+                                                        link_id: entry.link_id.clone(),
+                                                        short_url: entry.short_url.clone(),
+                                                        original_url: entry.original_url.clone(),
+                                                        label: entry.title.clone().unwrap_or_default(),
+                                                        utm: Default::default(),
+                                                    });
+                                                }
+                                                if entry.deleted_remotely {
+                                                    ui.label(egui::RichText::new(&entry.short_url).strong().strikethrough())
+                                                        .on_hover_text("Deleted outside the app; found by the last sync");
+                                                    ui.colored_label(
+                                                        egui::Color32::from_rgb(220, 60, 60),
+                                                        egui::RichText::new("deleted remotely").size(11.0),
+                                                    );
+                                                    return;
+                                                }
+                                                ui.label(egui::RichText::new(&entry.short_url).strong());
+                                                ui.label(
+                                                    egui::RichText::new(tz::local(entry.created_at).format("%Y-%m-%d %H:%M").to_string())
+                                                        .weak()
+                                                        .size(11.0),
+                                                );
+                                                match self.stats_prefetch.clicks(&entry.link_id) {
+                                                    Some(clicks) => match entry.clicks_limit.filter(|limit| *limit > 0) {
+                                                        Some(limit) => {
+                                                            let fraction = clicks as f32 / limit as f32;
+                                                            let mut bar = egui::ProgressBar::new(fraction.min(1.0))
+                                                                .desired_width(90.0)
+                                                                .desired_height(12.0)
+                                                                .text(egui::RichText::new(format!("{} / {}", clicks, limit)).size(10.0));
+                                                            if f64::from(fraction) >= renew::THRESHOLD {
+                                                                bar = bar.fill(egui::Color32::from_rgb(220, 140, 40));
+                                                            }
+                                                            ui.add(bar).on_hover_text(if entry.renewed {
+                                                                "Clicks limit; a fresh copy was created"
+                                                            } else {
+                                                                "Clicks so far out of the link's clicks limit"
+                                                            });
+                                                        }
+                                                        None => {
+                                                            ui.label(egui::RichText::new(format!("👆 {}", clicks)).weak().size(11.0));
+                                                        }
+                                                    },
+                                                    None => self.stats_prefetch.request(&self.api_key, &entry.link_id, ctx),
+                                                }
+                                            });
+                                            let mut detail = entry.title.clone().unwrap_or_else(|| entry.original_url.clone());
+                                            if !entry.tags.is_empty() {
+                                                detail.push_str(&format!("  🏷 {}", entry.tags.join(", ")));
+                                            }
+                                            ui.add(egui::Label::new(egui::RichText::new(detail).weak().size(11.0)).truncate());
+                                        });
+                                    });
+                                    measured = measured.max(response.response.rect.height());
+                                }
+                            });
+                        if measured > 0.0 && self.history_row_height != Some(measured) {
+                            self.history_row_height = Some(measured);
+                            ctx.request_repaint();
+                        }
+                        output.state.offset.y
+                    };
+                    self.nav.scrolled(Panel::History, offset);
+                });
+            });
+        self.show_history = open;
+        if let Some((short_url, original_url, title)) = slack_copy {
+            self.copy_for_slack(&short_url, &original_url, title.as_deref(), ctx.clone());
+        }
+        if let Some((kind, link_id, short_url)) = popout {
+            self.open_popout(kind, &link_id, &short_url, ctx);
+        }
+        if let Some((output, items)) = qr_job {
+            let name = format!("QR codes for {} links", items.len());
+            self.jobs.start(Job::new(name, self.profiles[self.active_profile].name.clone(), None, items, output));
+            self.history_selected.clear();
+            self.show_jobs = true;
+        }
+        if sync {
+            self.sync_daemon.sync_now();
+        }
+    }
+
+    /// Ambient state at a glance: connection, account, queued work, quota
+    /// and sync, so problems that aren't about the current form stay out of
+    /// its error banner.
+    fn show_status_bar(&mut self, ctx: &egui::Context) {
+        self.connectivity.start(ctx);
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let small = |text: String| egui::RichText::new(text).size(11.0);
+                let since = self
+                    .connectivity
+                    .since()
+                    .map(|at| format!(" since {}", tz::local(at).format("%H:%M")))
+                    .unwrap_or_default();
+                match self.connectivity.connection() {
+                    Connection::Online => {
+                        ui.colored_label(egui::Color32::from_rgb(60, 160, 60), small("● Online".to_string()))
+                            .on_hover_text(format!("api.short.io is reachable{}", since));
+                    }
+                    Connection::Offline => {
+                        ui.colored_label(egui::Color32::from_rgb(220, 60, 60), small("● Offline".to_string()))
+                            .on_hover_text(format!(
+                                "Can't reach api.short.io{}: {}",
+                                since,
+                                self.connectivity.error().unwrap_or_default()
+                            ));
+                    }
+                    Connection::Unknown => {
+                        ui.label(small("● Checking…".to_string()).weak());
+                    }
+                }
+                if let Some(latency) = api::latency() {
+                    let text = small(format!("⏱ {} ms", latency.last.as_millis()));
+                    ui.label(if latency.median >= api::SLOW_REQUEST {
+                        text.color(egui::Color32::from_rgb(220, 140, 40))
+                    } else {
+                        text.weak()
+                    })
+                    .on_hover_text(format!(
+                        "The last request to short.io took {} ms; the median of the last {} is {} ms",
+                        latency.last.as_millis(),
+                        latency.samples,
+                        latency.median.as_millis()
+                    ));
+                }
+                ui.separator();
+                if self.api_key.is_empty() {
+                    if ui
+                        .add(
+                            egui::Label::new(small("⚠ No API key".to_string()).color(egui::Color32::from_rgb(220, 140, 40)))
+                                .sense(egui::Sense::click()),
+                        )
+                        .on_hover_text("Open settings")
+                        .clicked()
+                    {
+                        self.show_settings = true;
+                    }
+                } else {
+                    let profile = &self.profiles[self.active_profile].name;
+                    let domain = if self.domain.is_empty() { "no domain" } else { self.domain.as_str() };
+                    ui.label(small(format!("{} · {}", profile, domain)).weak());
+                }
+
+                let queued: usize = self
+                    .jobs
+                    .snapshot()
+                    .iter()
+                    .filter(|job| matches!(job.state, JobState::Running | JobState::Paused))
+                    .map(|job| job.items.len() - job.done())
+                    .sum();
+                let scheduled = self.scheduler.pending().len();
+                if queued + scheduled > 0 {
+                    ui.separator();
+                    if ui
+                        .add(egui::Label::new(small(format!("⏳ {} pending", queued + scheduled)).weak()).sense(egui::Sense::click()))
+                        .on_hover_text(format!("{} links waiting in jobs, {} scheduled to go live", queued, scheduled))
+                        .clicked()
+                    {
+                        self.show_jobs = true;
+                    }
+                }
+
+                if let Some(quota) = api::quota(&self.api_key) {
+                    ui.separator();
+                    let text = match quota.limit {
+                        Some(limit) => format!("{} / {} requests left", quota.remaining, limit),
+                        None => format!("{} requests left", quota.remaining),
+                    };
+                    let low = quota.limit.is_some_and(|limit| quota.remaining * 10 < limit);
+                    let text = small(text);
+                    ui.label(if low { text.color(egui::Color32::from_rgb(220, 140, 40)) } else { text.weak() })
+                        .on_hover_text("short.io's rate limit, as of the latest response");
+                }
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if self.sync_daemon.is_running() {
+                        ui.spinner();
+                    } else if ui
+                        .add_enabled(!self.api_key.is_empty(), egui::Button::new("⟳").small())
+                        .on_hover_text("Sync history and click counts now")
+                        .clicked()
+                    {
+                        self.sync_daemon.sync_now();
+                    }
+                    match (self.sync_daemon.last_error(), self.sync_daemon.last_synced()) {
+                        (Some(error), _) => {
+                            if ui
+                                .add(
+                                    egui::Label::new(
+                                        egui::RichText::new("⚠ Sync failed").color(egui::Color32::from_rgb(220, 140, 40)).size(11.0),
+                                    )
+                                    .sense(egui::Sense::click()),
+                                )
+                                .on_hover_text(error)
+                                .clicked()
+                            {
+                                self.show_sync_report = true;
+                            }
+                        }
+                        (None, Some(at)) => {
+                            ui.label(egui::RichText::new(format!("Last synced {}", sync::ago(at))).weak().size(11.0))
+                                .on_hover_text(tz::local(at).format("%Y-%m-%d %H:%M").to_string());
+                        }
+                        (None, None) => {
+                            ui.label(egui::RichText::new("Not synced yet").weak().size(11.0));
+                        }
+                    }
+                });
+            });
+        });
+        // Keeps "N min ago" current
+        ctx.request_repaint_after(std::time::Duration::from_secs(60));
+    }
+
+    /// What the last sync changed in history, so nothing shifts silently.
+    fn show_sync_report_window(&mut self, ctx: &egui::Context) {
+        let Some(report) = &self.sync_report else {
+            return;
+        };
+        egui::Window::new("⟳ Sync Summary")
+            .open(&mut self.show_sync_report)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                let report = match report {
+                    Ok(report) => report,
+                    Err(e) => {
+                        ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("❌ Sync failed: {}", e));
+                        return;
+                    }
+                };
+                ui.label(format!(
+                    "Checked {} links at {}",
+                    report.checked,
+                    tz::local(report.at).format("%Y-%m-%d %H:%M")
+                ));
+                if !report.skipped_domains.is_empty() {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "Skipped links on {}, which this profile's account doesn't have",
+                            report.skipped_domains.join(", ")
+                        ))
+                        .weak()
+                        .size(11.0),
+                    );
+                }
+                if report.is_clean() {
+                    ui.colored_label(egui::Color32::from_rgb(60, 160, 60), "✔ History matches short.io");
+                    return;
+                }
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    if !report.deleted.is_empty() {
+                        ui.add_space(4.0);
+                        ui.strong(format!("Deleted remotely ({})", report.deleted.len()));
+                        for short_url in &report.deleted {
+                            ui.label(egui::RichText::new(short_url).strikethrough());
+                        }
+                    }
+                    if !report.edited.is_empty() {
+                        ui.add_space(4.0);
+                        ui.strong(format!("Edited remotely ({})", report.edited.len()));
+                        egui::Grid::new("sync_edits").num_columns(4).striped(true).spacing([12.0, 6.0]).show(ui, |ui| {
+                            ui.strong("");
+                            ui.strong("");
+                            ui.strong("Was");
+                            ui.strong("Now");
+                            ui.end_row();
+                            for difference in &report.edited {
+                                let (start, old, new, end) = edit::split_difference(&difference.local, &difference.remote);
+                                let text_color = ui.visuals().text_color();
+                                ui.label(&difference.short_url);
+                                ui.label(difference.field);
+                                ui.label(diff_text(start, old, end, text_color, egui::Color32::from_rgb(220, 60, 60)));
+                                ui.label(diff_text(start, new, end, text_color, egui::Color32::from_rgb(60, 160, 60)));
+                                ui.end_row();
+                            }
+                        });
+                    }
+                });
+            });
+    }
+
+    fn show_import_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_import;
+        egui::Window::new("📚 Import Bookmarks")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.set_min_width(400.0);
+
+                if ui.button("Open bookmarks export…").clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("Bookmarks", &["html", "htm", "json"])
+                        .pick_file()
+                    {
+                        match bookmarks::load(&path) {
+                            Ok(folders) => {
+                                self.bookmark_folders = folders;
+                                self.bookmark_folder = 0;
+                                self.import_error = None;
+                            }
+                            Err(e) => self.import_error = Some(format!("Failed to read bookmarks: {}", e)),
+                        }
+                    }
+                }
+
+                if !self.bookmark_folders.is_empty() {
+                    ui.add_space(8.0);
+                    ui.label("Folder:");
+                    let selected = &self.bookmark_folders[self.bookmark_folder];
+                    egui::ComboBox::from_id_salt("bookmark_folder")
+                        .selected_text(format!("{} ({})", selected.label, selected.bookmarks.len()))
+                        .width(380.0)
+                        .show_ui(ui, |ui| {
+                            for (i, folder) in self.bookmark_folders.iter().enumerate() {
+                                ui.selectable_value(
+                                    &mut self.bookmark_folder,
+                                    i,
+                                    format!("{} ({})", folder.label, folder.bookmarks.len()),
+                                );
+                            }
+                        });
+
+                    ui.add_space(8.0);
+                    let count = self.bookmark_folders[self.bookmark_folder].bookmarks.len();
+                    if ui.button(format!("Shorten {} bookmarks", count)).clicked() {
+                        if self.api_key.is_empty() {
+                            self.import_error = Some("API key is required. Click settings (⚙) to configure.".to_string());
+                        } else if let Some(export) = rfd::FileDialog::new()
+                            .set_file_name("bookmarks-shortened.csv")
+                            .add_filter("CSV", &["csv"])
+                            .save_file()
+                        {
+                            let folder = &self.bookmark_folders[self.bookmark_folder];
+                            let items = folder
+                                .bookmarks
+                                .iter()
+                                .map(|bookmark| JobItem {
+                                    title: Some(bookmark.title.clone()),
+                                    tags: bookmark.tags.clone(),
+                                    ..JobItem::new(bookmark.url.clone())
+                                })
+                                .collect();
+                            let domain = if self.domain.is_empty() { None } else { Some(self.domain.clone()) };
+                            self.jobs.start(Job::new(
+                                format!("Bookmarks: {}", folder.label),
+                                self.profiles[self.active_profile].name.clone(),
+                                domain,
+                                items,
+                                JobOutput::Csv { path: export },
+                            ));
+                            self.bookmark_folders.clear();
+                            self.import_error = None;
+                            self.show_import = false;
+                            self.show_jobs = true;
+                        }
+                    }
+                }
+
+                if let Some(error) = &self.import_error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("❌ {}", error));
+                }
+            });
+        // The window may have closed itself after starting a job
+        self.show_import &= open;
+    }
+
+    fn show_markdown_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_markdown;
+        egui::Window::new("📝 Markdown Rewriter")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.set_min_width(400.0);
+                ui.label("Shortens every link in a Markdown file and saves the rewritten copy.");
+                ui.add_space(8.0);
+
+                ui.label("Never shorten links to (comma-separated):");
+                let exclude_response = ui.add_enabled(
+                    !self.kiosk_locked(),
+                    egui::TextEdit::singleline(&mut self.exclude_domains)
+                        .hint_text("example.com, docs.example.org")
+                        .desired_width(f32::INFINITY),
+                );
+                if exclude_response.lost_focus() {
+                    self.save_config();
+                }
+                ui.add_space(8.0);
+
+                if ui.button("Open Markdown file…").clicked() {
+                    if self.api_key.is_empty() {
+                        self.error = Some("API key is required. Click settings (⚙) to configure.".to_string());
+                    } else if let Some(input) = rfd::FileDialog::new()
+                        .add_filter("Markdown", &["md", "markdown", "txt"])
+                        .pick_file()
+                    {
+                        let stem = input.file_stem().and_then(|s| s.to_str()).unwrap_or("document").to_string();
+                        let mut save = rfd::FileDialog::new().set_file_name(format!("{}.short.md", stem));
+                        if let Some(parent) = input.parent() {
+                            save = save.set_directory(parent);
+                        }
+                        match fs::read_to_string(&input) {
+                            Ok(source) => {
+                                if let Some(output) = save.save_file() {
+                                    self.start_markdown_job(&input, output, source);
+                                }
+                            }
+                            Err(e) => self.markdown_error = Some(format!("Cannot read {}: {}", input.display(), e)),
+                        }
+                    }
+                }
+
+                if let Some(error) = &self.markdown_error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("❌ {}", error));
+                }
+            });
+        // The window may have closed itself after starting a job
+        self.show_markdown &= open;
+    }
+
+    fn start_markdown_job(&mut self, input: &std::path::Path, output: PathBuf, source: String) {
+        let mut exclude = split_list(&self.exclude_domains);
+        // Never re-shorten links that are already on our short domain
+        if !self.domain.is_empty() {
+            exclude.push(self.domain.clone());
+        }
+        let spans = markdown::find_links(&source, &exclude);
+        if spans.is_empty() {
+            self.markdown_error = Some("No links to shorten in this file".to_string());
+            return;
+        }
+
+        let mut urls: Vec<&str> = spans.iter().map(|span| &source[span.clone()]).collect();
+        urls.sort_unstable();
+        urls.dedup();
+        let items = urls.into_iter().map(|url| JobItem::new(url.to_string())).collect();
+        let spans = spans.iter().map(|span| (span.start, span.end)).collect();
+
+        let domain = if self.domain.is_empty() { None } else { Some(self.domain.clone()) };
+        let name = input.file_name().and_then(|n| n.to_str()).unwrap_or("document");
+        self.jobs.start(Job::new(
+            format!("Markdown: {}", name),
+            self.profiles[self.active_profile].name.clone(),
+            domain,
+            items,
+            JobOutput::Markdown { path: output, source, spans },
+        ));
+        self.markdown_error = None;
+        self.show_markdown = false;
+        self.show_jobs = true;
+    }
+
+    fn show_jobs_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_jobs;
+        egui::Window::new("⏳ Jobs")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(440.0)
+            .show(ctx, |ui| {
+                let jobs = self.jobs.snapshot();
+                if jobs.is_empty() {
+                    ui.label(egui::RichText::new("No jobs").weak());
+                }
+                let output = self.nav.scroll_area(Panel::Jobs, egui::ScrollArea::vertical().max_height(400.0)).show(ui, |ui| {
+                    for job in jobs.iter().rev() {
+                        ui.group(|ui| {
+                            ui.set_min_width(ui.available_width());
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new(&job.name).strong());
+                                ui.label(
+                                    egui::RichText::new(job.created_at.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+                                        .weak()
+                                        .size(11.0),
+                                );
+                            });
+
+                            let done = job.done();
+                            let total = job.items.len();
+                            let fraction = if total == 0 { 1.0 } else { done as f32 / total as f32 };
+                            let state = match job.state {
+                                JobState::Running => "Running",
+                                JobState::Paused => "Paused",
+                                JobState::Cancelled => "Cancelled",
+                                JobState::Finished => "Finished",
+                            };
+                            ui.add(egui::ProgressBar::new(fraction).text(format!("{} / {} · {}", done, total, state)));
+
+                            ui.horizontal(|ui| match job.state {
+                                JobState::Running => {
+                                    if ui.button("⏸ Pause").clicked() {
+                                        self.jobs.set_state(job.id, JobState::Paused);
+                                    }
+                                    if ui.button("✖ Cancel").clicked() {
+                                        self.jobs.set_state(job.id, JobState::Cancelled);
+                                    }
+                                }
+                                JobState::Paused => {
+                                    if ui.button("▶ Resume").clicked() {
+                                        self.jobs.set_state(job.id, JobState::Running);
+                                    }
+                                    if ui.button("✖ Cancel").clicked() {
+                                        self.jobs.set_state(job.id, JobState::Cancelled);
+                                    }
+                                }
+                                JobState::Cancelled | JobState::Finished => {
+                                    if job.items.iter().any(|item| item.error.is_some()) && ui.button("↻ Retry failed").clicked() {
+                                        self.jobs.retry_failed(job.id);
+                                    }
+                                    if ui.button("🗑 Remove").clicked() {
+                                        self.jobs.remove(job.id);
+                                    }
+                                }
+                            });
+
+                            let errors: Vec<String> = job.errors().collect();
+                            if !errors.is_empty() {
+                                ui.collapsing(format!("{} error(s)", errors.len()), |ui| {
+                                    for error in &errors {
+                                        ui.colored_label(egui::Color32::from_rgb(220, 60, 60), error);
+                                    }
+                                });
+                            }
+                        });
+                    }
+                });
+                self.nav.scrolled(Panel::Jobs, output.state.offset.y);
+            });
+        if self.jobs.any_running() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(500));
+        }
+        self.show_jobs = open;
+    }
+
+    /// Checks the network, API key, clipboard and settings folder. `asked`
+    /// shows the results even when everything passed.
+    fn run_diagnostics(&mut self, asked: bool, ctx: egui::Context) {
+        self.diagnostics_busy = true;
+        let clipboard = diagnostics::clipboard();
+        let api_key = self.api_key.clone();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let mut checks = runtime.block_on(diagnostics::run(&api_key));
+            checks.push(clipboard);
+            ctx.data_mut(|data| data.insert_temp(egui::Id::new("diagnostics"), (checks, asked)));
+            ctx.request_repaint();
+        });
+    }
+
+    fn show_diagnostics_window(&mut self, ctx: &egui::Context) {
+        let mut run_again = false;
+        egui::Window::new("🩺 Troubleshooting")
+            .open(&mut self.show_diagnostics)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(460.0)
+            .show(ctx, |ui| {
+                let Some(checks) = &self.diagnostics else {
+                    ui.spinner();
+                    return;
+                };
+                for check in checks {
+                    ui.horizontal(|ui| {
+                        match &check.outcome {
+                            Ok(_) => ui.colored_label(egui::Color32::from_rgb(60, 160, 60), "✔"),
+                            Err(_) => ui.colored_label(egui::Color32::from_rgb(220, 60, 60), "❌"),
+                        };
+                        ui.strong(check.name);
+                        let (Ok(text) | Err(text)) = &check.outcome;
+                        ui.add(egui::Label::new(egui::RichText::new(text).weak().size(11.0)).truncate())
+                            .on_hover_text(text);
+                    });
+                    if let Some(hint) = &check.hint {
+                        ui.indent(check.name, |ui| {
+                            ui.label(egui::RichText::new(format!("💡 {}", hint)).size(11.0));
+                        });
+                    }
+                }
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!self.diagnostics_busy, egui::Button::new("Run again")).clicked() {
+                        run_again = true;
+                    }
+                    if ui.button("📋 Copy report").on_hover_text("For a bug report").clicked() {
+                        let report: Vec<String> = checks
+                            .iter()
+                            .map(|check| match &check.outcome {
+                                Ok(text) => format!("OK    {}: {}", check.name, text),
+                                Err(text) => format!("FAIL  {}: {}", check.name, text),
+                            })
+                            .collect();
+                        clipboard::copy(&format!(
+                            "Shortyio {} on {}\n{}",
+                            env!("CARGO_PKG_VERSION"),
+                            std::env::consts::OS,
+                            report.join("\n")
+                        ));
+                        self.toasts.info("Copied");
+                    }
+                    if self.diagnostics_busy {
+                        ui.spinner();
+                    }
+                });
+            });
+        if run_again {
+            self.run_diagnostics(true, ctx.clone());
+        }
+    }
+
+    /// Everything copied this session, for when a later copy replaced the
+    /// one that was needed.
+    fn show_copied_window(&mut self, ctx: &egui::Context) {
+        egui::Window::new("📋 Copied This Session")
+            .open(&mut self.show_copied)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(380.0)
+            .show(ctx, |ui| {
+                let history = clipboard::history();
+                if history.is_empty() {
+                    ui.label(egui::RichText::new("Nothing copied yet").weak());
+                    return;
+                }
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    for copied in &history {
+                        ui.horizontal(|ui| {
+                            if ui.small_button("📋").on_hover_text("Copy again").clicked() {
+                                copied.recopy();
+                            }
+                            ui.add(egui::Label::new(copied.label()).truncate());
+                        });
+                    }
+                });
+            });
+    }
+
+    fn show_usage_window(&mut self, ctx: &egui::Context) {
+        let Some(usage) = &self.usage else {
+            return;
+        };
+        let mut open = true;
+        egui::Window::new("📊 Usage")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(380.0)
+            .show(ctx, |ui| {
+                let last_30 = usage.last_days(30);
+                let last_7: u32 = last_30.iter().rev().take(7).map(|(_, count)| count).sum();
+                let today = last_30.last().map(|(_, count)| *count).unwrap_or(0);
+                ui.horizontal(|ui| {
+                    ui.label(format!("Today: {}", today));
+                    ui.separator();
+                    ui.label(format!("7 days: {}", last_7));
+                    ui.separator();
+                    ui.label(format!("30 days: {}", last_30.iter().map(|(_, count)| count).sum::<u32>()));
+                    ui.separator();
+                    ui.label(format!("All time: {}", usage.total()));
+                });
+
+                let points: egui_plot::PlotPoints = last_30
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (_, count))| [i as f64, f64::from(*count)])
+                    .collect();
+                egui_plot::Plot::new("usage_sparkline")
+                    .height(60.0)
+                    .show_axes(false)
+                    .show_grid(false)
+                    .show_x(false)
+                    .show_y(false)
+                    .allow_drag(false)
+                    .allow_zoom(false)
+                    .allow_scroll(false)
+                    .allow_boxed_zoom(false)
+                    .include_y(0.0)
+                    .show(ui, |plot_ui| plot_ui.line(egui_plot::Line::new(points)));
+                ui.label(egui::RichText::new("Links created per day, last 30 days").weak().size(11.0));
+
+                for (heading, counts) in [("Per profile", &usage.profiles), ("Per domain", &usage.domains), ("Per tag", &usage.tags)] {
+                    ui.add_space(8.0);
+                    ui.label(egui::RichText::new(heading).strong());
+                    let mut ranked: Vec<_> = counts.iter().collect();
+                    ranked.sort_by(|a, b| b.1.cmp(a.1));
+                    egui::Grid::new(heading).striped(true).show(ui, |ui| {
+                        for (key, count) in ranked.into_iter().take(10) {
+                            ui.label(key);
+                            ui.label(count.to_string());
+                            ui.end_row();
+                        }
+                    });
+                }
+
+                ui.add_space(8.0);
+                if ui.button("Export CSV…").clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .set_file_name("shortyio-usage.csv")
+                        .add_filter("CSV", &["csv"])
+                        .save_file()
+                    {
+                        if let Err(e) = usage.export_csv(&path) {
+                            elog!("Failed to export usage: {}", e);
+                        }
+                    }
+                }
+            });
+        if !open {
+            self.usage = None;
+        }
+    }
+
+    fn fetch_segment_stats(&mut self, ctx: egui::Context) {
+        if self.api_key.is_empty() {
+            self.segment_error = Some("API key is required. Click settings (⚙) to configure.".to_string());
+            return;
+        }
+        if self.domain.is_empty() {
+            self.segment_error = Some("Select a domain in settings to see prefix stats".to_string());
+            return;
+        }
+
+        let api_key = self.api_key.clone();
+        let domain = self.domain.clone();
+        let prefix = self.profiles[self.active_profile].path_prefix.clone();
+        self.segment_loading = true;
+        self.segment_error = None;
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            runtime.block_on(async {
+                let client = ApiClient::new(api_key);
+                let result = segments::fetch(&client, &domain, &prefix).await.map_err(|e| e.to_string());
+
+                ctx.data_mut(|data| {
+                    data.insert_temp(egui::Id::new("segment_stats"), result);
+                });
+                ctx.request_repaint();
+            });
+        });
+    }
+
+    fn fetch_rollups(&mut self, ctx: egui::Context) {
+        if self.api_key.is_empty() {
+            self.rollup_error = Some("API key is required. Click settings (⚙) to configure.".to_string());
+            return;
+        }
+        if self.domain.is_empty() {
+            self.rollup_error = Some("Select a domain in settings to see campaign totals".to_string());
+            return;
+        }
+
+        let api_key = self.api_key.clone();
+        let domain = self.domain.clone();
+        let period = self.rollup_period.clone();
+        self.rollup_loading = true;
+        self.rollup_error = None;
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            runtime.block_on(async {
+                let client = ApiClient::new(api_key);
+                let result = rollups::fetch(&client, &domain, &period).await.map_err(|e| e.to_string());
+
+                ctx.data_mut(|data| {
+                    data.insert_temp(egui::Id::new("rollup_stats"), result);
+                });
+                ctx.request_repaint();
+            });
+        });
+    }
+
+    fn show_rollups_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_rollups;
+        egui::Window::new("🏷 Campaign totals")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let label = rollups::period_label(&self.rollup_period).to_string();
+                    egui::ComboBox::from_id_salt("rollup_period").selected_text(label).show_ui(ui, |ui| {
+                        for (period, label) in rollups::PERIODS {
+                            ui.selectable_value(&mut self.rollup_period, period.to_string(), label);
+                        }
+                    });
+                    ui.selectable_value(&mut self.rollup_by_folder, false, "By tag");
+                    ui.selectable_value(&mut self.rollup_by_folder, true, "By folder");
+                    if self.rollup_loading {
+                        ui.spinner();
+                    } else if ui.button("↻ Count clicks").clicked() {
+                        self.fetch_rollups(ctx.clone());
+                    }
+                });
+
+                if let Some(error) = &self.rollup_error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("❌ {}", error));
+                }
+
+                ui.add_space(6.0);
+                egui::CollapsingHeader::new("Weekly report").show(ui, |ui| {
+                    match self.scheduler.report() {
+                        Some(report) => {
+                            ui.label(format!(
+                                "{} report of the last 7 days saved to {}",
+                                report.format.label(),
+                                report.folder.display()
+                            ));
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "Next: {}",
+                                    tz::local(report.next_run).format("%a %b %-d, %H:%M")
+                                ))
+                                .weak(),
+                            );
+                            if let Some(file) = &report.last_file {
+                                ui.label(egui::RichText::new(format!("Last: {}", file.display())).weak());
+                            }
+                            if let Some(error) = &report.last_error {
+                                ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("❌ {}", error));
+                            }
+                            if ui.button("Stop weekly report").clicked() {
+                                self.scheduler.set_report(None, report.format, &report.profile);
+                            }
+                        }
+                        None => {
+                            ui.horizontal(|ui| {
+                                for format in ReportFormat::ALL {
+                                    ui.selectable_value(&mut self.report_format, format, format.label());
+                                }
+                                if ui
+                                    .button("Schedule weekly…")
+                                    .on_hover_text("Tag and folder totals plus the top 10 links, written to a folder every week")
+                                    .clicked()
+                                {
+                                    if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                                        self.scheduler.set_report(Some(folder), self.report_format, &self.profiles[self.active_profile].name);
+                                    }
+                                }
+                            });
+                        }
+                    }
+                });
+
+                // Totals for another domain or period would be misleading
+                let Some(stats) = self
+                    .rollup_stats
+                    .as_ref()
+                    .filter(|stats| stats.domain == self.domain && stats.period == self.rollup_period)
+                else {
+                    return;
+                };
+                let rows = if self.rollup_by_folder { &stats.by_folder } else { &stats.by_tag };
+                let most = rows.first().map_or(1, |row| row.total_clicks.max(1));
+                ui.add_space(8.0);
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    egui::Grid::new("rollups").striped(true).num_columns(5).show(ui, |ui| {
+                        ui.label(egui::RichText::new("#").weak());
+                        ui.label(egui::RichText::new(if self.rollup_by_folder { "Folder" } else { "Tag" }).weak());
+                        ui.label(egui::RichText::new("Links").weak());
+                        ui.label(egui::RichText::new("Clicks").weak());
+                        ui.end_row();
+                        for (rank, row) in rows.iter().enumerate() {
+                            ui.label((rank + 1).to_string());
+                            ui.label(&row.name);
+                            ui.label(row.links.to_string());
+                            ui.label(row.total_clicks.to_string());
+                            ui.add(egui::ProgressBar::new(row.total_clicks as f32 / most as f32).desired_width(100.0));
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        self.show_rollups = open;
+    }
+
+    fn fetch_expiring(&mut self, ctx: egui::Context) {
+        if self.api_key.is_empty() {
+            self.expiring_error = Some("API key is required. Click settings (⚙) to configure.".to_string());
+            return;
+        }
+        if self.domain.is_empty() {
+            self.expiring_error = Some("Select a domain in settings to see expiring links".to_string());
+            return;
+        }
+
+        let api_key = self.api_key.clone();
+        let domain = self.domain.clone();
+        let days = self.expiring_days;
+        self.expiring_loading = true;
+        self.expiring_error = None;
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let client = ApiClient::new(api_key);
+            let result = runtime
+                .block_on(expiring::fetch(&client, &domain, days))
+                .map_err(|e| e.to_string());
+            ctx.data_mut(|data| {
+                data.insert_temp(egui::Id::new("expiring"), result);
+            });
+            ctx.request_repaint();
+        });
+    }
+
+    fn extend_link(&mut self, link: LinkResponse, ctx: egui::Context) {
+        let api_key = self.api_key.clone();
+        self.extending = Some(link.id.clone());
+        self.expiring_error = None;
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let result = runtime
+                .block_on(expiring::extend(&ApiClient::new(api_key), &link))
+                .map_err(|e| e.to_string());
+            ctx.data_mut(|data| {
+                data.insert_temp(egui::Id::new("extended"), (link.id, result));
+            });
+            ctx.request_repaint();
+        });
+    }
+
+    fn show_expiring_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut extend = None;
+        egui::Window::new("⌛ Expiring Soon")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(440.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Expiring within");
+                    ui.add(egui::DragValue::new(&mut self.expiring_days).range(1..=365));
+                    ui.label("days");
+                    if ui.add_enabled(!self.expiring_loading, egui::Button::new("🔄 Refresh")).clicked() {
+                        self.fetch_expiring(ctx.clone());
+                    }
+                });
+                if let Some(error) = &self.expiring_error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 60, 60), error);
+                }
+                ui.separator();
+                if self.expiring_loading {
+                    ui.spinner();
+                    return;
+                }
+                let Some(links) = &self.expiring else {
+                    return;
+                };
+                if links.is_empty() {
+                    ui.label(egui::RichText::new("Nothing expires in that time").weak());
+                }
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    for link in links {
+                        let Some(expires_at) = link.expires_at else {
+                            continue;
+                        };
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new(&link.short_url).strong());
+                            let days = (expires_at - Utc::now()).num_days();
+                            let color = if days < 3 {
+                                egui::Color32::from_rgb(220, 60, 60)
+                            } else {
+                                egui::Color32::from_rgb(220, 140, 40)
+                            };
+                            ui.colored_label(
+                                color,
+                                egui::RichText::new(format!(
+                                    "{} (in {} days)",
+                                    tz::local(expires_at).format("%Y-%m-%d %H:%M"),
+                                    days
+                                ))
+                                .size(11.0),
+                            );
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                if self.extending.as_deref() == Some(link.id.as_str()) {
+                                    ui.spinner();
+                                } else if ui
+                                    .add_enabled(self.extending.is_none(), egui::Button::new("+30 days"))
+                                    .on_hover_text("Extend the expiration by 30 days")
+                                    .clicked()
+                                {
+                                    extend = Some(link.clone());
+                                }
+                            });
+                        });
+                    }
+                });
+            });
+        if let Some(link) = extend {
+            self.extend_link(link, ctx.clone());
+        }
+        self.show_expiring &= open;
+    }
+
+    fn find_retag_matches(&mut self, ctx: egui::Context) {
+        if self.api_key.is_empty() || self.domain.is_empty() {
+            self.retag_status = Some("An API key and domain are required. Click settings (⚙) to configure.".to_string());
+            return;
+        }
+        let created_before = if self.retag_before.trim().is_empty() {
+            None
+        } else {
+            match NaiveDate::parse_from_str(self.retag_before.trim(), "%Y-%m-%d") {
+                Ok(date) => Some(date),
+                Err(_) => {
+                    self.retag_status = Some("Created before must be in the format YYYY-MM-DD".to_string());
+                    return;
+                }
+            }
+        };
+        let query = retag::Query {
+            url_contains: self.retag_url_contains.clone(),
+            created_before,
+        };
+        let api_key = self.api_key.clone();
+        let domain = self.domain.clone();
+        self.retag_busy = true;
+        self.retag_status = None;
+        self.retag_matches = None;
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let result = runtime
+                .block_on(retag::find(&ApiClient::new(api_key), &domain, &query))
+                .map_err(|e| e.to_string());
+            ctx.data_mut(|data| {
+                data.insert_temp(egui::Id::new("retag_matches"), result);
+            });
+            ctx.request_repaint();
+        });
+    }
+
+    fn apply_retag(&mut self, ctx: egui::Context) {
+        let Some(links) = self.retag_matches.clone() else {
+            return;
+        };
+        let add = split_list(&self.retag_add);
+        let remove = split_list(&self.retag_remove);
+        let api_key = self.api_key.clone();
+        self.retag_busy = true;
+        self.retag_status = Some(format!("Updating 0 of {}…", links.len()));
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let client = ApiClient::new(api_key);
+            let (mut changed, mut failed) = (0, 0);
+            for (index, link) in links.iter().enumerate() {
+                match runtime.block_on(retag::apply(&client, link, &add, &remove)) {
+                    Ok(true) => changed += 1,
+                    Ok(false) => {}
+                    Err(e) => {
+                        elog!("Failed to retag {}: {}", link.short_url, e);
+                        failed += 1;
+                    }
+                }
+                ctx.data_mut(|data| {
+                    data.insert_temp(
+                        egui::Id::new("retag_status"),
+                        format!("Updating {} of {}…", index + 1, links.len()),
+                    );
+                });
+                ctx.request_repaint();
+            }
+            ctx.data_mut(|data| {
+                data.insert_temp(
+                    egui::Id::new("retag_done"),
+                    format!("Updated {} links, {} unchanged, {} failed", changed, links.len() - changed - failed, failed),
+                );
+            });
+            ctx.request_repaint();
+        });
+    }
+
+    fn show_retag_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        egui::Window::new("🏷 Bulk Re-tag")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(440.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("retag_query").num_columns(2).show(ui, |ui| {
+                    ui.label("Original URL contains:");
+                    ui.text_edit_singleline(&mut self.retag_url_contains);
+                    ui.end_row();
+                    ui.label("Created before:");
+                    ui.add(egui::TextEdit::singleline(&mut self.retag_before).hint_text("YYYY-MM-DD"));
+                    ui.end_row();
+                });
+                if ui.add_enabled(!self.retag_busy, egui::Button::new("🔍 Find links")).clicked() {
+                    self.find_retag_matches(ctx.clone());
+                }
+                if let Some(status) = &self.retag_status {
+                    ui.label(egui::RichText::new(status).size(11.0));
+                }
+
+                let Some(links) = &self.retag_matches else {
+                    if self.retag_busy {
+                        ui.spinner();
+                    }
+                    return;
+                };
+                ui.separator();
+                ui.label(egui::RichText::new(format!("{} matching links", links.len())).strong());
+                egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                    for link in links {
+                        let preview = retag::retagged(&link.tags, &split_list(&self.retag_add), &split_list(&self.retag_remove));
+                        let mut line = format!("{}  🏷 {}", link.short_url, link.tags.join(", "));
+                        if preview != link.tags {
+                            line.push_str(&format!("  →  {}", preview.join(", ")));
+                        }
+                        ui.add(egui::Label::new(egui::RichText::new(line).size(11.0)).truncate())
+                            .on_hover_text(&link.original_url);
+                    }
+                });
+                ui.separator();
+                egui::Grid::new("retag_tags").num_columns(2).show(ui, |ui| {
+                    ui.label("Add tags:");
+                    ui.add(egui::TextEdit::singleline(&mut self.retag_add).hint_text("spring-2025, email"));
+                    ui.end_row();
+                    ui.label("Remove tags:");
+                    ui.add(egui::TextEdit::singleline(&mut self.retag_remove).hint_text("spring-2024"));
+                    ui.end_row();
+                });
+                let has_changes = !self.retag_add.trim().is_empty() || !self.retag_remove.trim().is_empty();
+                if ui
+                    .add_enabled(
+                        !self.retag_busy && has_changes && !links.is_empty(),
+                        egui::Button::new(format!("Apply to {} links", links.len())),
+                    )
+                    .clicked()
+                {
+                    self.apply_retag(ctx.clone());
+                }
+            });
+        self.show_retag &= open;
+    }
+
+    fn scan_duplicates(&mut self, ctx: egui::Context) {
+        if self.api_key.is_empty() || self.domain.is_empty() {
+            self.duplicates_status = Some("An API key and domain are required. Click settings (⚙) to configure.".to_string());
+            return;
+        }
+        let api_key = self.api_key.clone();
+        let domain = self.domain.clone();
+        self.duplicates_busy = true;
+        self.duplicates_status = None;
+        self.duplicate_groups = None;
+        self.duplicate_survivors.clear();
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let result = runtime
+                .block_on(duplicates::find(&ApiClient::new(api_key), &domain))
+                .map_err(|e| e.to_string());
+            ctx.data_mut(|data| {
+                data.insert_temp(egui::Id::new("duplicate_groups"), result);
+            });
+            ctx.request_repaint();
+        });
+    }
+
+    fn merge_duplicates(&mut self, group: DuplicateGroup, survivor: String, ctx: egui::Context) {
+        let api_key = self.api_key.clone();
+        let redirect = self.duplicates_redirect;
+        self.duplicates_busy = true;
+        self.duplicates_status = None;
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let result = runtime
+                .block_on(duplicates::merge(&ApiClient::new(api_key), &group, &survivor, redirect))
+                .map_err(|e| e.to_string());
+            ctx.data_mut(|data| {
+                data.insert_temp(egui::Id::new("duplicates_merged"), (group.original_url, redirect, result));
+            });
+            ctx.request_repaint();
+        });
+    }
+
+    fn show_duplicates_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        let mut merge = None;
+        egui::Window::new("🧹 Duplicate Links")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!self.duplicates_busy, egui::Button::new("🔍 Scan")).clicked() {
+                        self.scan_duplicates(ctx.clone());
+                    }
+                    ui.checkbox(&mut self.duplicates_redirect, "Redirect extras to the kept link before deleting")
+                        .on_hover_text("If one is restored from the trash, its visitors land on the kept link");
+                });
+                if let Some(status) = &self.duplicates_status {
+                    ui.label(egui::RichText::new(status).size(11.0));
+                }
+                if self.duplicates_busy {
+                    ui.spinner();
+                }
+                let Some(groups) = &self.duplicate_groups else {
+                    return;
+                };
+                ui.separator();
+                if groups.is_empty() {
+                    ui.label(egui::RichText::new("No duplicates on this domain").weak());
+                    return;
+                }
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    for group in groups {
+                        ui.group(|ui| {
+                            ui.set_min_width(ui.available_width());
+                            ui.add(egui::Label::new(egui::RichText::new(&group.original_url).strong()).truncate());
+                            let survivor = self
+                                .duplicate_survivors
+                                .entry(group.original_url.clone())
+                                .or_insert_with(|| group.links[0].0.id.clone());
+                            for (link, clicks) in &group.links {
+                                ui.horizontal(|ui| {
+                                    ui.radio_value(survivor, link.id.clone(), &link.short_url)
+                                        .on_hover_text("Keep this one");
+                                    ui.label(egui::RichText::new(format!("👆 {}", clicks)).weak().size(11.0));
+                                });
+                            }
+                            let action = if self.duplicates_redirect { "Redirect & trash" } else { "Trash" };
+                            if ui
+                                .add_enabled(
+                                    !self.duplicates_busy,
+                                    egui::Button::new(format!("{} {} others", action, group.links.len() - 1)),
+                                )
+                                .clicked()
+                            {
+                                merge = Some((group.clone(), survivor.clone()));
+                            }
+                        });
+                    }
+                });
+            });
+        if let Some((group, survivor)) = merge {
+            self.merge_duplicates(group, survivor, ctx.clone());
+        }
+        self.show_duplicates &= open;
+    }
+
+    fn show_segment_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_segment;
+        let prefix = self.profiles[self.active_profile].path_prefix.clone();
+        egui::Window::new(format!("📈 {}{}", self.domain, prefix))
+            .id(egui::Id::new("segment_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(400.0)
+            .show(ctx, |ui| {
+                if self.segment_loading {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Counting clicks...");
+                    });
+                } else if ui.button("↻ Refresh").clicked() {
+                    self.segment_stats = None;
+                    self.fetch_segment_stats(ctx.clone());
+                }
+
+                if let Some(error) = &self.segment_error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("❌ {}", error));
+                }
+
+                // Stats for another profile's prefix are stale once it changes
+                let Some(stats) = self.segment_stats.as_ref().filter(|s| s.prefix == prefix) else {
+                    return;
+                };
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    ui.label(format!("Links: {}", stats.links.len()));
+                    ui.separator();
+                    ui.label(format!("Clicks: {}", stats.total_clicks));
+                    ui.separator();
+                    ui.label(format!("Human clicks: {}", stats.human_clicks));
+                });
+                ui.add_space(8.0);
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    egui::Grid::new("segment_links").striped(true).show(ui, |ui| {
+                        for link in &stats.links {
+                            ui.label(&link.short_url);
+                            ui.label(link.total_clicks.to_string());
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        self.show_segment = open;
+    }
+
+    fn fetch_links_page(&mut self, ctx: egui::Context) {
+        if self.api_key.is_empty() {
+            self.link_list.error = Some("API key is required. Click settings (⚙) to configure.".to_string());
+            return;
+        }
+        if self.domain.is_empty() {
+            self.link_list.error = Some("Select a domain in settings to list its links".to_string());
+            return;
+        }
+
+        let api_key = self.api_key.clone();
+        let domain = self.domain.clone();
+        let page_token = self.link_list.next_page.clone();
+        self.link_list.loading = true;
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            runtime.block_on(async {
+                let client = ApiClient::new(api_key);
+                let result = match client.find_domain(&domain).await {
+                    Ok(Some(found)) => client
+                        .list_links_page(found.id, page_token.as_deref())
+                        .await
+                        .map_err(|e| e.to_string()),
+                    Ok(None) => Err(format!("Domain {} not found on this account", domain)),
+                    Err(e) => Err(e.to_string()),
+                };
+
+                ctx.data_mut(|data| {
+                    data.insert_temp(egui::Id::new("links_page"), (domain, result));
+                });
+                ctx.request_repaint();
+            });
+        });
+    }
+
+    fn show_links_window(&mut self, ctx: &egui::Context) {
+        if self.link_list.domain != self.domain {
+            self.link_list.reset(&self.domain);
+        }
+        if self.link_list.links.is_empty() && self.link_list.wants_more() {
+            self.fetch_links_page(ctx.clone());
+        }
+
+        let kiosk = self.kiosk_locked();
+        let mut edit = None;
+        let mut trashed = None;
+        let mut open = self.show_links;
+        egui::Window::new("🔗 My Links")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(460.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let loaded = self.link_list.links.len();
+                    match self.link_list.total {
+                        Some(total) => ui.label(format!("{} of {} links on {}", loaded, total, self.domain)),
+                        None => ui.label(format!("{} links on {}", loaded, self.domain)),
+                    };
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.add_enabled(!self.link_list.loading, egui::Button::new("↻")).on_hover_text("Refresh").clicked() {
+                            cache::forget(&self.api_key, "/api/links");
+                            self.link_list.reset(&self.domain);
+                            self.fetch_links_page(ctx.clone());
+                        }
+                        if !self.kiosk_locked()
+                            && ui.button("🧹").on_hover_text("Find links pointing at the same destination").clicked()
+                        {
+                            self.show_duplicates = true;
+                            if self.duplicate_groups.is_none() && !self.duplicates_busy {
+                                self.scan_duplicates(ctx.clone());
+                            }
+                        }
+                        if !self.kiosk_locked() && ui.button("🗑").on_hover_text("Trash").clicked() {
+                            self.open_trash();
+                        }
+                    });
+                });
+                ui.separator();
+
+                let row_height = ui.spacing().interact_size.y;
+                let links = &self.link_list.links;
+                let output = self
+                    .nav
+                    .scroll_area(Panel::Links, egui::ScrollArea::vertical().max_height(380.0))
+                    .auto_shrink([false, true])
+                    .show_rows(ui, row_height, links.len(), |ui, rows| {
+                        for link in &links[rows.clone()] {
+                            ui.horizontal(|ui| {
+                                if ui.small_button("📋").on_hover_text("Copy").clicked() {
+                                    clipboard::copy(&link.short_url);
+                                    self.toasts.info("Copied");
+                                }
+                                if !kiosk && ui.small_button("✏").on_hover_text("Edit").clicked() {
+                                    edit = Some(link.clone());
+                                }
+                                if !kiosk && ui.small_button("🗑").on_hover_text("Move to the trash").clicked() {
+                                    trashed = Some(link.clone());
+                                }
+                                ui.label(egui::RichText::new(&link.short_url).strong());
+                                ui.add(egui::Label::new(egui::RichText::new(&link.original_url).weak().size(11.0)).truncate());
+                            });
+                        }
+                        rows.end
+                    });
+                self.nav.scrolled(Panel::Links, output.state.offset.y);
+                let visible = output.inner;
+
+                // Load the next page shortly before the user reaches the end
+                if visible + 20 >= self.link_list.links.len() && self.link_list.wants_more() {
+                    self.fetch_links_page(ctx.clone());
+                }
+                if self.link_list.loading {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Loading links...");
+                    });
+                }
+                if let Some(error) = &self.link_list.error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("❌ {}", error));
+                }
+            });
+        self.show_links = open;
+        if let Some(link) = edit {
+            self.link_edit = Some(LinkEdit::new(link));
+        }
+        if let Some(link) = trashed {
+            self.run_trash(trash::Action::Trash(link), ctx.clone());
+        }
+    }
+
+    fn open_trash(&mut self) {
+        self.show_trash = true;
+        self.trash_items = trash::items(&ApiClient::new(self.api_key.clone()));
+        self.trash_error = None;
+    }
+
+    /// Deleted links waiting out their time before they're gone for good.
+    fn show_trash_window(&mut self, ctx: &egui::Context) {
+        let mut action = None;
+        let mut open = self.show_trash;
+        egui::Window::new("🗑 Trash")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(440.0)
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "Links here are archived on short.io and deleted for good after {} days",
+                        self.trash_days
+                    ))
+                    .weak()
+                    .size(11.0),
+                );
+                ui.separator();
+                if self.trash_items.is_empty() {
+                    ui.label(egui::RichText::new("The trash is empty").weak());
+                }
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    for item in &self.trash_items {
+                        ui.horizontal(|ui| {
+                            ui.add_enabled_ui(!self.trash_busy, |ui| {
+                                if ui.small_button("↩").on_hover_text("Restore").clicked() {
+                                    action = Some(trash::Action::Restore(item.clone()));
+                                }
+                                if ui.small_button("✖").on_hover_text("Delete now").clicked() {
+                                    action = Some(trash::Action::DeleteNow(item.clone()));
+                                }
+                            });
+                            ui.label(egui::RichText::new(&item.short_url).strong());
+                            let left = i64::from(self.trash_days) - (Utc::now() - item.deleted_at).num_days();
+                            ui.label(egui::RichText::new(format!("{} days left", left.max(0))).weak().size(11.0));
+                        });
+                        ui.add(egui::Label::new(egui::RichText::new(&item.original_url).weak().size(11.0)).truncate());
+                    }
+                });
+                if self.trash_busy {
+                    ui.spinner();
+                }
+                if let Some(error) = &self.trash_error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("❌ {}", error));
+                }
+            });
+        self.show_trash = open;
+        if let Some(action) = action {
+            self.run_trash(action, ctx.clone());
+        }
+    }
+
+    fn run_trash(&mut self, action: trash::Action, ctx: egui::Context) {
+        self.trash_busy = true;
+        self.trash_error = None;
+        let api_key = self.api_key.clone();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let client = ApiClient::new(api_key);
+            let result = runtime.block_on(action.run(&client)).map_err(|e| e.to_string());
+            ctx.data_mut(|data| data.insert_temp(egui::Id::new("trash_done"), (action, result)));
+            ctx.request_repaint();
+        });
+    }
+
+    fn purge_trash(&self, ctx: egui::Context) {
+        let api_key = self.api_key.clone();
+        let days = self.trash_days;
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            match runtime.block_on(trash::purge(&ApiClient::new(api_key), days)) {
+                Ok(0) => {}
+                Ok(purged) => {
+                    ctx.data_mut(|data| data.insert_temp(egui::Id::new("trash_purged"), purged));
+                    ctx.request_repaint();
+                }
+                Err(e) => elog!("Failed to empty old links from the trash: {}", e),
+            }
+        });
+    }
+
+    /// Edits a link's destination, tags and expiration. Since an edit
+    /// re-routes live traffic, what will change is shown for confirmation
+    /// before anything is saved.
+    fn show_edit_window(&mut self, ctx: &egui::Context) {
+        let Some(edit) = &mut self.link_edit else {
+            return;
+        };
+        let mut open = true;
+        let mut save = None;
+        egui::Window::new(format!("✏ Edit {}", edit.link.short_url))
+            .id(egui::Id::new("link_edit"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                if edit.reviewing {
+                    let changes = edit.changes().unwrap_or_default();
+                    if changes.is_empty() {
+                        ui.label(egui::RichText::new("Nothing has changed").weak());
+                    } else {
+                        ui.label("Check the changes before saving:");
+                        ui.add_space(4.0);
+                        egui::Grid::new("edit_diff").num_columns(3).striped(true).spacing([12.0, 6.0]).show(ui, |ui| {
+                            ui.strong("");
+                            ui.strong("Before");
+                            ui.strong("After");
+                            ui.end_row();
+                            for change in &changes {
+                                let (start, old, new, end) = edit::split_difference(&change.old, &change.new);
+                                let text_color = ui.visuals().text_color();
+                                ui.label(change.field);
+                                ui.label(diff_text(start, old, end, text_color, egui::Color32::from_rgb(220, 60, 60)));
+                                ui.label(diff_text(start, new, end, text_color, egui::Color32::from_rgb(60, 160, 60)));
+                                ui.end_row();
+                            }
+                        });
+                    }
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        let ready = !edit.saving && !changes.is_empty();
+                        if ui.add_enabled(ready, egui::Button::new("✔ Confirm and save")).clicked() {
+                            save = edit.request().ok();
+                        }
+                        if ui.add_enabled(!edit.saving, egui::Button::new("Back")).clicked() {
+                            edit.reviewing = false;
+                        }
+                        if edit.saving {
+                            ui.spinner();
+                        }
+                    });
+                } else {
+                    ui.label("Destination:");
+                    ui.add(egui::TextEdit::singleline(&mut edit.original_url).desired_width(f32::INFINITY));
+                    ui.label("Tags (comma-separated):");
+                    ui.add(egui::TextEdit::singleline(&mut edit.tags).desired_width(f32::INFINITY));
+                    ui.label(format!("Expires ({}):", tz::zone().name()));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut edit.expires)
+                            .hint_text("in 2 weeks, friday 5pm, end of month…")
+                            .desired_width(f32::INFINITY),
+                    );
+                    let mut review = false;
+                    if !edit.versions.is_empty() {
+                        ui.add_space(4.0);
+                        egui::CollapsingHeader::new(format!("Change history ({})", edit.versions.len()))
+                            .id_salt("edit_versions")
+                            .show(ui, |ui| {
+                                egui::ScrollArea::vertical().max_height(160.0).show(ui, |ui| {
+                                    for version in &edit.versions {
+                                        ui.horizontal(|ui| {
+                                            if ui
+                                                .small_button("↩ Revert")
+                                                .on_hover_text(format!("Point the link back at {}", version.from))
+                                                .clicked()
+                                            {
+                                                edit.original_url = version.from.clone();
+                                                review = true;
+                                            }
+                                            ui.label(
+                                                egui::RichText::new(tz::local(version.at).format("%Y-%m-%d %H:%M").to_string())
+                                                    .weak()
+                                                    .size(11.0),
+                                            );
+                                            ui.add(egui::Label::new(format!("{} → {}", version.from, version.to)).truncate())
+                                                .on_hover_text(format!("{}\n→ {}", version.from, version.to));
+                                        });
+                                    }
+                                });
+                            });
+                    }
+                    ui.add_space(8.0);
+                    review |= ui.button("Review changes…").clicked();
+                    if review {
+                        match edit.changes() {
+                            Ok(_) => {
+                                edit.error = None;
+                                edit.reviewing = true;
+                            }
+                            Err(e) => edit.error = Some(e),
+                        }
+                    }
+                }
+                if let Some(error) = &edit.error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("❌ {}", error));
+                }
+            });
+        if let Some(request) = save {
+            self.save_link_edit(request, ctx.clone());
+        }
+        if !open {
+            self.link_edit = None;
+        }
+    }
+
+    fn save_link_edit(&mut self, request: UpdateLinkRequest, ctx: egui::Context) {
+        let Some(edit) = &mut self.link_edit else {
+            return;
+        };
+        edit.saving = true;
+        edit.error = None;
+        let api_key = self.api_key.clone();
+        let link_id = edit.link.id.clone();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let result = runtime
+                .block_on(ApiClient::new(api_key).update_link(&link_id, &request))
+                .map_err(|e| e.to_string());
+            ctx.data_mut(|data| data.insert_temp(egui::Id::new("link_edited"), (link_id, result)));
+            ctx.request_repaint();
+        });
+    }
+
+    fn show_share_sheet_panel(&mut self, ctx: &egui::Context) {
+        egui::SidePanel::right("share_sheet")
+            .resizable(true)
+            .default_width(240.0)
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("🗒 Share sheet").strong());
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("✖").on_hover_text("Turn off").clicked() {
+                            self.share_sheet.enabled = false;
+                        }
+                    });
+                });
+                ui.label(egui::RichText::new("Links you create are added here").weak().size(11.0));
+                ui.add_space(4.0);
+
+                let mut remove = None;
+                egui::ScrollArea::vertical().max_height(220.0).show(ui, |ui| {
+                    for (i, item) in self.share_sheet.items.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            if ui.small_button("✖").on_hover_text("Remove").clicked() {
+                                remove = Some(i);
+                            }
+                            ui.add(egui::TextEdit::singleline(&mut item.title).desired_width(f32::INFINITY));
+                        });
+                        ui.label(egui::RichText::new(&item.short_url).weak().size(11.0));
+                    }
+                });
+                if let Some(i) = remove {
+                    let item = self.share_sheet.items.remove(i);
+                    self.toasts.with_undo("Removed from share sheet", Undo::ShareItem(i, item));
+                }
+
+                ui.separator();
+                let mut markdown = self.share_sheet.to_markdown();
+                egui::ScrollArea::vertical().id_salt("share_sheet_preview").max_height(160.0).show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut markdown)
+                            .font(egui::TextStyle::Monospace)
+                            .interactive(false)
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!self.share_sheet.items.is_empty(), egui::Button::new("📋 Copy all")).clicked() {
+                        clipboard::copy(&markdown);
+                        self.toasts.info("Share sheet copied");
+                    }
+                    if ui.button("Clear").clicked() {
+                        self.share_sheet.items.clear();
+                    }
+                });
+            });
+    }
+
+    /// Looks for free custom paths based on the destination's title and URL.
+    fn suggest_paths(&mut self, ctx: egui::Context) {
+        let api_key = self.api_key.clone();
+        let hostname = self.domain.clone();
+        let prefix = self.profiles[self.active_profile].path_prefix.clone();
+        let url = self.original_url.trim().to_string();
+        self.suggestions_loading = true;
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let client = ApiClient::new(api_key);
+            let suggestions = runtime
+                .block_on(vanity::suggest(&client, &hostname, &prefix, &url))
+                .unwrap_or_else(|e| {
+                    elog!("Failed to suggest paths for {}: {}", url, e);
+                    Vec::new()
+                });
+            ctx.data_mut(|data| data.insert_temp(egui::Id::new("path_suggestions"), (url, suggestions)));
+            ctx.request_repaint();
+        });
+    }
+
+    /// Checks whether the destination refuses to be shown inside a cloaked link's iframe.
+    fn preview_framing(&mut self, ctx: egui::Context) {
+        let url = self.original_url.trim().to_string();
+        let short_domain = if self.domain.is_empty() { "short.gy".to_string() } else { self.domain.to_ascii_lowercase() };
+        self.framing_loading = true;
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let result = runtime
+                .block_on(framing::check(&url, &short_domain))
+                .map_err(|e| e.to_string());
+            ctx.data_mut(|data| data.insert_temp(egui::Id::new("framing"), (url, result)));
+            ctx.request_repaint();
+        });
+    }
+
+    /// Loads the domain's fallback URLs, or saves them when `save` is given.
+    fn sync_domain_fallbacks(&mut self, save: Option<DomainFallbacks>, ctx: egui::Context) {
+        let api_key = self.api_key.clone();
+        let hostname = self.domain.clone();
+        self.domain_fallbacks_busy = true;
+        self.domain_fallbacks_status = None;
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let result = runtime.block_on(async {
+                let client = ApiClient::new(api_key);
+                let domain = client
+                    .find_domain(&hostname)
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!("Domain {} not found on this account", hostname))?;
+                if let Some(fallbacks) = &save {
+                    client.set_domain_fallbacks(domain.id, fallbacks).await?;
+                }
+                anyhow::Ok(client.domain_fallbacks(domain.id).await?)
+            });
+            ctx.data_mut(|data| {
+                data.insert_temp(
+                    egui::Id::new("domain_fallbacks"),
+                    (hostname, save.is_some(), result.map_err(|e| e.to_string())),
+                );
+            });
+            ctx.request_repaint();
+        });
+    }
+
+    fn fetch_domains(&mut self, ctx: egui::Context) {
+        if self.api_key.is_empty() {
+            return;
+        }
+
+        let api_key = self.api_key.clone();
+        self.domains_loading = true;
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            runtime.block_on(async {
+                let client = ApiClient::new(api_key);
+                let response = client.list_domains().await;
+
+                ctx.request_repaint();
+
+                let domains = response.unwrap_or_else(|e| {
+                    elog!("Failed to fetch domains: {}", e);
+                    Vec::new()
+                });
+                ctx.data_mut(|data| {
+                    data.insert_temp(egui::Id::new("domains"), domains);
+                    data.insert_temp(egui::Id::new("domains_loading"), false);
+                });
+            });
+        });
+    }
+}
+
+#[cfg(feature = "gui")]
+impl eframe::App for ShortyApp {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        if let Some(bench) = &mut self.history_bench {
+            if let Some(summary) = frame.info().cpu_usage.and_then(|seconds| bench.record(seconds)) {
+                elog!("{}", summary);
+            }
+            ctx.request_repaint();
+        }
+        if !self.config_watched {
+            self.config_watched = true;
+            if let Some(path) = Config::config_path() {
+                config_watch::start(path, ctx.clone());
+            }
+        }
+        if !self.plugins_loaded {
+            self.load_plugins(ctx.clone());
+        }
+        if !self.campaigns_requested {
+            self.campaigns_requested = true;
+            for campaign in &self.campaigns.items {
+                for link_id in &campaign.link_ids {
+                    self.stats_prefetch.request(&self.api_key, link_id, ctx);
+                }
+            }
+        }
+        self.check_campaign_goals();
+        if ctx.data_mut(|data| data.remove_temp::<bool>(egui::Id::new("config_changed"))).is_some() {
+            match Config::load() {
+                Some(config) => {
+                    self.apply_config(config);
+                    self.toasts.info("Settings reloaded from the config file");
+                }
+                None => self.toasts.info("Config file changed but couldn't be read; keeping current settings"),
+            }
+        }
+        ctx.data_mut(|data| {
+            if let Some(result) = data.get_temp::<Option<LinkResponse>>(egui::Id::new("result")) {
+                if let Some(link) = &result {
+                    self.recent.add(&link.short_url, &link.original_url);
+                    self.show_required = false;
+                }
+                if let Some(link) = result.as_ref().filter(|_| self.share_sheet.enabled) {
+                    self.share_sheet.add(None, &link.original_url, &link.short_url);
+                }
+                self.result = result;
+                data.remove::<Option<LinkResponse>>(egui::Id::new("result"));
+            }
+            if let Some(error) = data.get_temp::<Option<String>>(egui::Id::new("error")) {
+                if error.is_some() {
+                    // So the status bar shows whether the network was to blame
+                    self.connectivity.probe_now();
+                }
+                self.error = error;
+                data.remove::<Option<String>>(egui::Id::new("error"));
+            }
+            if let Some(loading) = data.get_temp::<bool>(egui::Id::new("loading")) {
+                self.loading = loading;
+                data.remove::<bool>(egui::Id::new("loading"));
+            }
+            if let Some(domains) = data.get_temp::<Vec<Domain>>(egui::Id::new("domains")) {
+                self.domains = domains;
+                data.remove::<Vec<Domain>>(egui::Id::new("domains"));
+            }
+            if let Some(domains_loading) = data.get_temp::<bool>(egui::Id::new("domains_loading")) {
+                self.domains_loading = domains_loading;
+                data.remove::<bool>(egui::Id::new("domains_loading"));
+            }
+            if let Some(error) = data.get_temp::<Option<String>>(egui::Id::new("rotation_error")) {
+                self.rotation_error = error;
+                data.remove::<Option<String>>(egui::Id::new("rotation_error"));
+            }
+            if let Some(release) = data.get_temp::<Option<update::Release>>(egui::Id::new("update_available")) {
+                self.update_available = release;
+                data.remove::<Option<update::Release>>(egui::Id::new("update_available"));
+            }
+            if let Some(short_url) = data.get_temp::<String>(egui::Id::new("og_missing")) {
+                self.og_missing = Some(short_url);
+                data.remove::<String>(egui::Id::new("og_missing"));
+            }
+            if let Some(result) = data.get_temp::<Result<PathBuf, String>>(egui::Id::new("og_card")) {
+                match result {
+                    Ok(path) => {
+                        self.og_status = Some(format!("Saved {}. Upload it, then paste its URL to attach it.", path.display()));
+                        self.og_card = Some(path);
+                    }
+                    Err(e) => self.og_status = Some(format!("Couldn't make the card: {}", e)),
+                }
+                self.og_busy = false;
+                data.remove::<Result<PathBuf, String>>(egui::Id::new("og_card"));
+            }
+            if let Some(result) = data.get_temp::<Result<(), String>>(egui::Id::new("og_attached")) {
+                match result {
+                    Ok(()) => {
+                        self.og_status = Some("✔ Share image attached to the link".to_string());
+                        self.og_missing = None;
+                        self.og_card = None;
+                        self.og_image_url.clear();
+                    }
+                    Err(e) => self.og_status = Some(format!("Couldn't attach the image: {}", e)),
+                }
+                self.og_busy = false;
+                data.remove::<Result<(), String>>(egui::Id::new("og_attached"));
+            }
+            if let Some(health) = data.remove_temp::<Health>(egui::Id::new("redirect_test")) {
+                self.redirect_test_busy = false;
+                self.redirect_test = Some(health);
+            }
+            if let Some(status) = data.get_temp::<Result<String, String>>(egui::Id::new("nfc_status")) {
+                self.nfc_busy = false;
+                self.nfc_status = Some(status);
+                data.remove::<Result<String, String>>(egui::Id::new("nfc_status"));
+            }
+            if let Some(result) = data.remove_temp::<Result<Option<String>, String>>(egui::Id::new("bio_published")) {
+                self.bio_busy = false;
+                self.bio_status = Some(result.map(|short_url| {
+                    self.bio.published_at = Some(Utc::now());
+                    match short_url {
+                        Some(short_url) => {
+                            self.bio.short_url = short_url;
+                            format!("Published at {}", self.bio.short_url)
+                        }
+                        None => "Published".to_string(),
+                    }
+                }));
+                self.bio.save();
+            }
+            if let Some(status) = data.get_temp::<Result<String, String>>(egui::Id::new("plugin_status")) {
+                self.plugin_busy = false;
+                self.plugin_status = Some(status);
+                data.remove::<Result<String, String>>(egui::Id::new("plugin_status"));
+            }
+            if let Some(actions) = data.remove_temp::<Vec<plugins::Action>>(egui::Id::new("plugin_actions")) {
+                self.plugin_actions = actions;
+            }
+            if let Some(status) = data.get_temp::<Option<String>>(egui::Id::new("update_status")) {
+                self.update_status = status;
+                data.remove::<Option<String>>(egui::Id::new("update_status"));
+            }
+            if let Some((link_id, result)) = data.remove_temp::<(String, Result<LinkResponse, String>)>(egui::Id::new("link_edited")) {
+                match result {
+                    Ok(link) => {
+                        if let Some(listed) = self.link_list.links.iter_mut().find(|listed| listed.id == link_id) {
+                            *listed = LinkResponse { id: link_id.clone(), ..link.clone() };
+                        }
+                        self.history.update(&link_id, |entry| {
+                            entry.original_url = link.original_url.clone();
+                            entry.tags = link.tags.clone();
+                        });
+                        self.link_edit = None;
+                        self.toasts.info("Link updated");
+                    }
+                    Err(e) => {
+                        if let Some(edit) = &mut self.link_edit {
+                            edit.saving = false;
+                            edit.error = Some(e);
+                        }
+                    }
+                }
+            }
+            if let Some((action, result)) = data.remove_temp::<(trash::Action, Result<(), String>)>(egui::Id::new("trash_done")) {
+                self.trash_busy = false;
+                match result {
+                    Ok(()) => {
+                        self.trash_items = trash::items(&ApiClient::new(self.api_key.clone()));
+                        match action {
+                            trash::Action::Trash(link) => {
+                                self.link_list.links.retain(|listed| listed.id != link.id);
+                                if let Some(item) = self.trash_items.iter().find(|item| item.link_id == link.id) {
+                                    self.toasts.with_undo("Moved to the trash", Undo::Trashed(item.clone()));
+                                }
+                            }
+                            trash::Action::Restore(item) => {
+                                // Back in the list on the next fetch
+                                cache::forget(&self.api_key, "/api/links");
+                                self.link_list.reset(&self.domain);
+                                self.toasts.info(format!("Restored {}", item.short_url));
+                            }
+                            trash::Action::DeleteNow(item) => self.toasts.info(format!("Deleted {} for good", item.short_url)),
+                        }
+                    }
+                    Err(e) if self.show_trash => self.trash_error = Some(e),
+                    Err(e) => self.toasts.info(format!("❌ {}", e)),
+                }
+            }
+            if let Some((checks, asked)) = data.remove_temp::<(Vec<diagnostics::Check>, bool)>(egui::Id::new("diagnostics")) {
+                // At startup, only a failure is worth interrupting for
+                self.show_diagnostics |= asked || checks.iter().any(|check| check.outcome.is_err());
+                self.diagnostics = Some(checks);
+                self.diagnostics_busy = false;
+            }
+            if let Some(url) = data.remove_temp::<String>(egui::Id::new("clipboard_url")) {
+                self.autofill(url);
+            }
+            if let Some(purged) = data.remove_temp::<usize>(egui::Id::new("trash_purged")) {
+                self.toasts.info(format!("Deleted {} links that were in the trash over {} days", purged, self.trash_days));
+            }
+            if let Some((domain, result)) = data.get_temp::<(String, Result<LinkPage, String>)>(egui::Id::new("links_page")) {
+                // Ignore pages for a domain the list has since switched away from
+                if domain == self.link_list.domain {
+                    match result {
+                        Ok(page) => self.link_list.append(page),
+                        Err(e) => {
+                            self.link_list.error = Some(e);
+                            self.link_list.loading = false;
+                        }
+                    }
+                }
+                data.remove::<(String, Result<LinkPage, String>)>(egui::Id::new("links_page"));
+            }
+            if let Some((short_url, original_url, title)) =
+                data.get_temp::<(String, String, Option<String>)>(egui::Id::new("slack_title"))
+            {
+                // Copied here because the clipboard belongs to the UI thread
+                clipboard::copy(&titles::slack_link(&short_url, title.as_deref()));
+                self.toasts.info("Copied for Slack");
+                self.page_titles.insert(original_url, title);
+                self.slack_loading = false;
+                data.remove::<(String, String, Option<String>)>(egui::Id::new("slack_title"));
+            }
+            if let Some((original_url, title)) = data.remove_temp::<(String, Option<String>)>(egui::Id::new("compose_title")) {
+                if let Some(compose) = self.compose.as_mut().filter(|c| c.original_url == original_url) {
+                    // Only replace the suggestion if it hasn't been edited yet
+                    if compose.text == compose.short_url {
+                        compose.text = social::suggested_text(title.as_deref(), &compose.short_url);
+                    }
+                }
+                self.page_titles.insert(original_url, title);
+            }
+            if let Some((id, result)) = data.get_temp::<(String, Result<LinkResponse, String>)>(egui::Id::new("draft_result")) {
+                match result {
+                    Ok(link) => {
+                        self.drafts.remove(&id);
+                        self.draft_status = Some(format!("Created {}", link.short_url));
+                    }
+                    Err(e) => self.draft_status = Some(e),
+                }
+                self.draft_busy = None;
+                data.remove::<(String, Result<LinkResponse, String>)>(egui::Id::new("draft_result"));
+            }
+            if let Some(result) = data.get_temp::<Result<Vec<LinkResponse>, String>>(egui::Id::new("expiring")) {
+                match result {
+                    Ok(links) => self.expiring = Some(links),
+                    Err(e) => self.expiring_error = Some(e),
+                }
+                self.expiring_loading = false;
+                data.remove::<Result<Vec<LinkResponse>, String>>(egui::Id::new("expiring"));
+            }
+            if let Some((id, result)) = data.get_temp::<(String, Result<DateTime<Utc>, String>)>(egui::Id::new("extended")) {
+                match result {
+                    Ok(expires_at) => {
+                        if let Some(links) = &mut self.expiring {
+                            if let Some(link) = links.iter_mut().find(|link| link.id == id) {
+                                link.expires_at = Some(expires_at);
+                            }
+                            // Extended links usually fall outside the window now
+                            let until = Utc::now() + chrono::Duration::days(self.expiring_days);
+                            links.retain(|link| link.expires_at.is_some_and(|at| at <= until));
+                        }
+                    }
+                    Err(e) => self.expiring_error = Some(e),
+                }
+                self.extending = None;
+                data.remove::<(String, Result<DateTime<Utc>, String>)>(egui::Id::new("extended"));
+            }
+            if let Some(result) = data.get_temp::<Result<Vec<LinkResponse>, String>>(egui::Id::new("retag_matches")) {
+                match result {
+                    Ok(links) => self.retag_matches = Some(links),
+                    Err(e) => self.retag_status = Some(e),
+                }
+                self.retag_busy = false;
+                data.remove::<Result<Vec<LinkResponse>, String>>(egui::Id::new("retag_matches"));
+            }
+            if let Some(status) = data.get_temp::<String>(egui::Id::new("retag_status")) {
+                self.retag_status = Some(status);
+                data.remove::<String>(egui::Id::new("retag_status"));
+            }
+            if let Some(status) = data.get_temp::<String>(egui::Id::new("retag_done")) {
+                self.retag_status = Some(status);
+                self.retag_busy = false;
+                // Tags changed on the server; find again to see the new state
+                self.retag_matches = None;
+                data.remove::<String>(egui::Id::new("retag_done"));
+            }
+            if let Some(result) = data.get_temp::<Result<Vec<DuplicateGroup>, String>>(egui::Id::new("duplicate_groups")) {
+                match result {
+                    Ok(groups) => self.duplicate_groups = Some(groups),
+                    Err(e) => self.duplicates_status = Some(e),
+                }
+                self.duplicates_busy = false;
+                data.remove::<Result<Vec<DuplicateGroup>, String>>(egui::Id::new("duplicate_groups"));
+            }
+            if let Some((original_url, redirect, result)) =
+                data.get_temp::<(String, bool, Result<usize, String>)>(egui::Id::new("duplicates_merged"))
+            {
+                match result {
+                    Ok(count) => {
+                        if let Some(groups) = &mut self.duplicate_groups {
+                            groups.retain(|group| group.original_url != original_url);
+                        }
+                        self.duplicates_status = Some(if redirect {
+                            format!("Redirected {} duplicate links and moved them to the trash", count)
+                        } else {
+                            format!("Moved {} duplicate links to the trash", count)
+                        });
+                    }
+                    Err(e) => self.duplicates_status = Some(e),
+                }
+                self.duplicates_busy = false;
+                data.remove::<(String, bool, Result<usize, String>)>(egui::Id::new("duplicates_merged"));
+            }
+            if let Some((link_id, daily)) =
+                data.get_temp::<(String, Result<Vec<(NaiveDate, u64)>, String>)>(egui::Id::new("popout_stats"))
+            {
+                for popout in &mut self.popouts {
+                    if popout.link_id == link_id {
+                        popout.daily = Some(daily.clone());
+                    }
+                    if let Some(compare) = popout.compare.as_mut().filter(|compare| compare.link_id == link_id) {
+                        compare.daily = Some(daily.clone());
+                    }
+                }
+                data.remove::<(String, Result<Vec<(NaiveDate, u64)>, String>)>(egui::Id::new("popout_stats"));
+            }
+            if let Some(suggestions) = data.get_temp::<(String, Vec<String>)>(egui::Id::new("path_suggestions")) {
+                self.path_suggestions = Some(suggestions);
+                self.suggestions_loading = false;
+                data.remove::<(String, Vec<String>)>(egui::Id::new("path_suggestions"));
+            }
+            if let Some((url, result)) = data.get_temp::<(String, Result<Framing, String>)>(egui::Id::new("framing")) {
+                self.framing.insert(url, result);
+                self.framing_loading = false;
+                data.remove::<(String, Result<Framing, String>)>(egui::Id::new("framing"));
+            }
+            if let Some((hostname, saved, result)) =
+                data.get_temp::<(String, bool, Result<DomainFallbacks, String>)>(egui::Id::new("domain_fallbacks"))
+            {
+                match result {
+                    Ok(fallbacks) => {
+                        self.domain_fallbacks = Some((hostname, fallbacks));
+                        if saved {
+                            self.domain_fallbacks_status = Some("Saved to short.io".to_string());
+                        }
+                    }
+                    Err(e) => self.domain_fallbacks_status = Some(e),
+                }
+                self.domain_fallbacks_busy = false;
+                data.remove::<(String, bool, Result<DomainFallbacks, String>)>(egui::Id::new("domain_fallbacks"));
+            }
+            if let Some(result) = data.get_temp::<Result<RollupStats, String>>(egui::Id::new("rollup_stats")) {
+                match result {
+                    Ok(stats) => self.rollup_stats = Some(stats),
+                    Err(e) => self.rollup_error = Some(e),
+                }
+                self.rollup_loading = false;
+                data.remove::<Result<RollupStats, String>>(egui::Id::new("rollup_stats"));
+            }
+            if let Some(result) = data.get_temp::<Result<SegmentStats, String>>(egui::Id::new("segment_stats")) {
+                match result {
+                    Ok(stats) => self.segment_stats = Some(stats),
+                    Err(e) => self.segment_error = Some(e),
+                }
+                self.segment_loading = false;
+                data.remove::<Result<SegmentStats, String>>(egui::Id::new("segment_stats"));
+            }
+        });
+
+        self.health.watch(&self.pins.items, ctx);
+        self.renewer.configure(&self.api_key, self.clicks_limit_renew, &self.history);
+        self.sync_daemon.configure(&self.api_key, self.sync_interval_minutes, &self.history, ctx);
+        if let Some((result, manual)) = self.sync_daemon.take_report() {
+            if result.is_ok() {
+                self.stats_prefetch.refresh();
+            }
+            // Background syncs only interrupt when something changed
+            self.show_sync_report |= manual || result.as_ref().is_ok_and(|report| !report.is_clean());
+            self.sync_report = Some(result);
+        }
+
+        let focused = ctx.input(|i| i.focused);
+        if (focused && !self.window_focused) || !self.clipboard_read {
+            self.clipboard_read = true;
+            self.read_clipboard(ctx.clone());
+        }
+        self.window_focused = focused;
+
+        if self.check_updates && !self.update_checked {
+            self.update_checked = true;
+            update::check(ctx.clone());
+        }
+
+        if self.startup_check && !self.startup_checked {
+            self.startup_checked = true;
+            self.run_diagnostics(false, ctx.clone());
+        }
+
+        if !self.trash_purged && !self.api_key.is_empty() {
+            self.trash_purged = true;
+            self.purge_trash(ctx.clone());
+        }
+
+        if !self.loading && ctx.input_mut(|i| i.consume_shortcut(&PASTE_AND_SHORTEN)) {
+            self.paste_and_shorten(ctx.clone());
+        }
+
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape) || i.viewport().close_requested()) {
+            self.nav.save();
+        }
+
+        if self.show_settings {
+            // Fetch domains if we haven't yet and API key is set
+            if self.domains.is_empty() && !self.domains_loading && !self.api_key.is_empty() {
+                self.fetch_domains(ctx.clone());
+            }
+
+            egui::Window::new("⚙ Settings")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.set_min_width(400.0);
+
+                    if self.kiosk_locked() {
+                        self.show_kiosk_lock(ui);
+                        return;
+                    }
+
+                    ui.label("Profile:");
+                    let mut switch_to = None;
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_salt("profile_selector")
+                            .selected_text(&self.profiles[self.active_profile].name)
+                            .show_ui(ui, |ui| {
+                                for (i, profile) in self.profiles.iter().enumerate() {
+                                    if ui.selectable_label(i == self.active_profile, &profile.name).clicked() {
+                                        switch_to = Some(i);
+                                    }
+                                }
+                            });
+                        self.show_domain_badge(ui);
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.new_profile_name)
+                                .hint_text("New profile name")
+                                .desired_width(140.0),
+                        );
+                        if ui.button("➕ Add").clicked() && !self.new_profile_name.trim().is_empty() {
+                            self.profiles.push(Profile::named(self.new_profile_name.trim()));
+                            self.new_profile_name.clear();
+                            switch_to = Some(self.profiles.len() - 1);
+                        }
+                    });
+                    if let Some(index) = switch_to.filter(|&i| i != self.active_profile) {
+                        self.switch_profile(index);
+                    }
+                    let profile = &mut self.profiles[self.active_profile];
+                    ui.horizontal(|ui| {
+                        let mut branded = profile.accent.is_some();
+                        if ui
+                            .checkbox(&mut branded, "Accent color")
+                            .on_hover_text("Shown in the header and on QR posters to tell profiles apart")
+                            .changed()
+                        {
+                            profile.accent = branded.then_some([26, 115, 232]);
+                        }
+                        if let Some(accent) = &mut profile.accent {
+                            ui.color_edit_button_srgb(accent);
+                        }
+                        ui.separator();
+                        ui.label("Logo:");
+                        let logo = std::path::Path::new(profile.logo.trim()).file_name().map(|name| name.to_string_lossy());
+                        ui.label(egui::RichText::new(logo.as_deref().unwrap_or("None")).weak());
+                        if ui.small_button("Browse…").clicked() {
+                            if let Some(path) = rfd::FileDialog::new().add_filter("Images", &["png", "jpg", "jpeg"]).pick_file() {
+                                profile.logo = path.display().to_string();
+                            }
+                        }
+                        if !profile.logo.is_empty() && ui.small_button("✖").on_hover_text("No logo").clicked() {
+                            profile.logo.clear();
+                        }
+                    });
+                    ui.checkbox(&mut profile.kiosk, "Kiosk mode")
+                        .on_hover_text("Only allow creating links: hides the API key and locks settings and edit/delete actions");
+                    if profile.kiosk {
+                        ui.horizontal(|ui| {
+                            ui.label("Unlock PIN (optional):");
+                            ui.add(egui::TextEdit::singleline(&mut profile.kiosk_pin).password(true).desired_width(80.0));
+                        });
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label("Path prefix:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut profile.path_prefix)
+                                .hint_text("ev/")
+                                .desired_width(100.0),
+                        )
+                        .on_hover_text("Added in front of every custom path created with this profile");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Default redirect:");
+                        let mut code = profile.default_redirect();
+                        if redirects::picker(ui, "default_redirect", &mut code) {
+                            profile.default_redirect = Some(code);
+                        }
+                    });
+                    ui.label("Team presets URL (optional):");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut profile.presets_url)
+                            .hint_text("https://intranet.example.com/shortyio.json")
+                            .desired_width(f32::INFINITY),
+                    )
+                    .on_hover_text("Shared domain, tags, UTM templates and blocked domains, refreshed hourly");
+                    ui.label("Webhook URL (optional):");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut profile.webhook_url)
+                            .hint_text("https://hooks.example.com/shortyio")
+                            .desired_width(f32::INFINITY),
+                    )
+                    .on_hover_text("Receives a JSON POST with the short URL, original URL, tags and profile for every new link");
+                    if !profile.presets_url.is_empty() {
+                        match self.presets.status() {
+                            (_, Some(error)) => {
+                                ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("Presets: {}", error));
+                            }
+                            (Some(updated_at), None) => {
+                                ui.weak(format!(
+                                    "Presets updated {}",
+                                    updated_at.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+                                ));
+                            }
+                            (None, None) => {
+                                ui.weak("Presets not loaded yet");
+                            }
+                        }
+                    }
+                    ui.collapsing("Reserved paths", |ui| {
+                        ui.label("Custom paths to warn about, one per line:")
+                            .on_hover_text("Words like api or admin, brand names you can't use, or anything else that shouldn't become a short link");
+                        ui.add(
+                            egui::TextEdit::multiline(&mut self.reserved_draft)
+                                .desired_rows(4)
+                                .desired_width(f32::INFINITY),
+                        );
+                        if ui.small_button("Reset to defaults").clicked() {
+                            self.reserved_draft = reserved::DEFAULTS.iter().map(|path| format!("{}\n", path)).collect();
+                        }
+                    });
+                    ui.collapsing("Network", |ui| {
+                        ui.label("User agent (optional):");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut profile.user_agent)
+                                .hint_text(concat!("shortyio/", env!("CARGO_PKG_VERSION")))
+                                .desired_width(f32::INFINITY),
+                        );
+                        ui.label("Extra headers, one `Name: value` per line:");
+                        ui.add(
+                            egui::TextEdit::multiline(&mut self.headers_draft)
+                                .hint_text("X-Gateway-Token: abc123")
+                                .desired_rows(2)
+                                .desired_width(f32::INFINITY),
+                        );
+                        for (label, hover, hint, path) in [
+                            (
+                                "CA certificate (optional):",
+                                "Trusted in addition to the system roots, for proxies that inspect HTTPS",
+                                "PEM or DER file",
+                                &mut profile.ca_cert,
+                            ),
+                            (
+                                "Pinned certificate (optional):",
+                                "Only this certificate is trusted, whoever issued it, and the CA certificate is ignored; \
+                                 requests fail if short.io presents another",
+                                "SHA-256 fingerprint, or PEM or DER file",
+                                &mut profile.pinned_cert,
+                            ),
+                        ] {
+                            ui.label(label).on_hover_text(hover);
+                            ui.horizontal(|ui| {
+                                ui.add(egui::TextEdit::singleline(path).hint_text(hint));
+                                if ui.button("Browse…").clicked() {
+                                    if let Some(picked) = rfd::FileDialog::new()
+                                        .add_filter("Certificate", &["pem", "crt", "cer", "der"])
+                                        .pick_file()
+                                    {
+                                        *path = picked.display().to_string();
+                                    }
+                                }
+                            });
+                        }
+                    });
+                    ui.add_space(8.0);
+
+                    ui.label("API Key:");
+                    if profiles::env_override(profiles::ENV_API_KEY).is_some() {
+                        ui.label(egui::RichText::new("Set by SHORTYIO_API_KEY; changes here last until restart").weak().size(11.0));
+                    }
+                    let api_key_changed = ui.add(
+                        egui::TextEdit::singleline(&mut self.api_key)
+                            .password(true)
+                            .hint_text("Enter your short.io API key"),
+                    ).changed();
+
+                    // If API key changed, fetch domains
+                    if api_key_changed && !self.api_key.is_empty() {
+                        self.fetch_domains(ctx.clone());
+                    }
+
+                    ui.add_space(8.0);
+
+                    ui.label("Domain:");
+                    if profiles::env_override(profiles::ENV_DOMAIN).is_some() {
+                        ui.label(egui::RichText::new("Set by SHORTYIO_DOMAIN; changes here last until restart").weak().size(11.0));
+                    }
+                    if self.domains_loading {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("Loading domains...");
+                        });
+                    } else if !self.domains.is_empty() {
+                        let mut selected_label = if self.domain.is_empty() {
+                            "No custom domain".to_string()
+                        } else {
+                            self.domain.clone()
+                        };
+
+                        egui::ComboBox::from_id_salt("domain_selector")
+                            .selected_text(&selected_label)
+                            .show_ui(ui, |ui| {
+                                if ui.selectable_value(&mut selected_label, "No custom domain".to_string(), "No custom domain").clicked() {
+                                    self.domain = String::new();
+                                }
+                                for domain in &self.domains {
+                                    if ui.selectable_value(&mut selected_label, domain.hostname.clone(), &domain.hostname).clicked() {
+                                        self.domain = domain.hostname.clone();
+                                    }
+                                }
+                            });
+                    } else {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.domain)
+                                .hint_text("Enter API key to load domains"),
+                        );
+                    }
+                    if !self.domain.is_empty() && !self.api_key.is_empty() {
+                        egui::CollapsingHeader::new("Not-found and expired links")
+                            .id_salt("domain_fallbacks")
+                            .show(ui, |ui| {
+                                let loaded = self.domain_fallbacks.as_ref().is_some_and(|(host, _)| *host == self.domain);
+                                if !loaded && !self.domain_fallbacks_busy && self.domain_fallbacks_status.is_none() {
+                                    self.sync_domain_fallbacks(None, ctx.clone());
+                                }
+                                if let Some(status) = &self.domain_fallbacks_status {
+                                    ui.label(egui::RichText::new(status).size(11.0));
+                                }
+                                if self.domain_fallbacks_busy {
+                                    ui.spinner();
+                                    return;
+                                }
+                                let Some((_, fallbacks)) = self.domain_fallbacks.as_mut().filter(|_| loaded) else {
+                                    if ui.button("Retry").clicked() {
+                                        self.sync_domain_fallbacks(None, ctx.clone());
+                                    }
+                                    return;
+                                };
+                                for (label, url) in [
+                                    ("Unknown paths go to:", &mut fallbacks.not_found_url),
+                                    ("Expired links go to:", &mut fallbacks.expired_url),
+                                ] {
+                                    ui.label(label);
+                                    let mut text = url.clone().unwrap_or_default();
+                                    if ui
+                                        .add(
+                                            egui::TextEdit::singleline(&mut text)
+                                                .hint_text("short.io's default page")
+                                                .desired_width(f32::INFINITY),
+                                        )
+                                        .changed()
+                                    {
+                                        *url = Some(text.trim().to_string()).filter(|text| !text.is_empty());
+                                    }
+                                }
+                                let fallbacks = fallbacks.clone();
+                                if ui.button("Save to domain").on_hover_text("Applies immediately on short.io").clicked() {
+                                    self.sync_domain_fallbacks(Some(fallbacks), ctx.clone());
+                                }
+                            });
+                    }
+                    ui.add_space(8.0);
+
+                    ui.checkbox(&mut self.check_updates, "Check for updates on startup");
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.startup_check, "Check the connection and setup on startup")
+                            .on_hover_text("Shows what's wrong, and how to fix it, only when something fails");
+                        if ui.add_enabled(!self.diagnostics_busy, egui::Button::new("🩺 Run now")).clicked() {
+                            self.run_diagnostics(true, ctx.clone());
+                        }
+                    });
+                    ui.checkbox(&mut self.cache_on_disk, "Keep domain and link lists cached between sessions")
+                        .on_hover_text("Speeds up startup on slow connections; lists are refreshed in the background once stale");
+                    if cfg!(target_os = "linux") {
+                        ui.checkbox(&mut self.keep_clipboard, "Keep copied link on the clipboard after closing")
+                            .on_hover_text("Shortyio keeps running in the background until something else is copied, for up to 10 minutes");
+                        ui.checkbox(&mut self.primary_selection, "Also copy to the primary selection")
+                            .on_hover_text("Lets you paste the short link with a middle click");
+                    }
+                    ui.checkbox(&mut self.show_thumbnails, "Show destination thumbnails in history")
+                        .on_hover_text("Uses each page's share image, fetched once and cached");
+                    if self.show_thumbnails {
+                        ui.horizontal(|ui| {
+                            ui.label("Screenshot service:");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.screenshot_service)
+                                    .hint_text("Optional, e.g. https://shots.example.com/?url={url}")
+                                    .desired_width(f32::INFINITY),
+                            )
+                            .on_hover_text("Used instead of share images; {url} is replaced with the destination");
+                        });
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label("Time zone:");
+                        let system = format!("System ({})", tz::system().name());
+                        egui::ComboBox::from_id_salt("time_zone")
+                            .selected_text(if self.time_zone.is_empty() { system.as_str() } else { self.time_zone.as_str() })
+                            .height(300.0)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.time_zone, String::new(), system.as_str());
+                                for zone in chrono_tz::TZ_VARIANTS {
+                                    ui.selectable_value(&mut self.time_zone, zone.name().to_string(), zone.name());
+                                }
+                            });
+                    })
+                    .response
+                    .on_hover_text("Schedules are entered in this zone, and dates and click charts are shown in it");
+                    ui.add_space(8.0);
+
+                    ui.label("Blocked destinations, one `pattern # reason` per line:")
+                        .on_hover_text("A domain (subdomains included) or a URL prefix like example.com/promo/, * as a wildcard");
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.blocked_destinations)
+                            .hint_text("competitor.com # Competitor brand")
+                            .desired_rows(2)
+                            .desired_width(f32::INFINITY),
+                    );
+                    ui.add_space(8.0);
+
+                    ui.horizontal(|ui| {
+                        ui.label("Near a clicks limit:");
+                        egui::ComboBox::from_id_salt("clicks_limit_renew")
+                            .selected_text(self.clicks_limit_renew.label())
+                            .show_ui(ui, |ui| {
+                                for mode in [RenewMode::Off, RenewMode::Raise, RenewMode::Duplicate] {
+                                    ui.selectable_value(&mut self.clicks_limit_renew, mode, mode.label());
+                                }
+                            });
+                    })
+                    .response
+                    .on_hover_text("Checked every 15 minutes for links at 90% of their clicks limit");
+                    ui.add_space(8.0);
+
+                    ui.label("History:");
+                    ui.horizontal(|ui| {
+                        ui.label("Keep at most");
+                        ui.add(egui::DragValue::new(&mut self.retention.max_entries).range(0..=100_000));
+                        ui.label("links and");
+                        ui.add(egui::DragValue::new(&mut self.retention.max_age_days).range(0..=3650));
+                        ui.label("days");
+                    })
+                    .response
+                    .on_hover_text("0 keeps everything");
+                    ui.horizontal(|ui| {
+                        ui.label("Sync with short.io every");
+                        ui.add(egui::DragValue::new(&mut self.sync_interval_minutes).range(0..=1440));
+                        ui.label("minutes");
+                    })
+                    .response
+                    .on_hover_text("Checks history for links deleted or edited on the dashboard and refreshes click counts; 0 syncs only when asked");
+                    ui.horizontal(|ui| {
+                        ui.label("Keep deleted links in the trash for");
+                        ui.add(egui::DragValue::new(&mut self.trash_days).range(1..=3650));
+                        ui.label("days");
+                    });
+                    if self.confirm_clear_history {
+                        ui.horizontal(|ui| {
+                            ui.colored_label(egui::Color32::from_rgb(220, 60, 60), "Erase all history?");
+                            if ui.button("Erase").clicked() {
+                                if let Err(e) = self.history.wipe() {
+                                    elog!("Failed to clear history: {}", e);
+                                }
+                                if let Err(e) = self.recent.wipe() {
+                                    elog!("Failed to clear recent results: {}", e);
+                                }
+                                if let Err(e) = trash::wipe() {
+                                    elog!("Failed to clear the trash: {}", e);
+                                }
+                                if let Err(e) = versions::wipe() {
+                                    elog!("Failed to clear link versions: {}", e);
+                                }
+                                if let Err(e) = cache::wipe() {
+                                    elog!("Failed to clear the API cache: {}", e);
+                                }
+                                self.duplicate = None;
+                                self.confirm_clear_history = false;
+                            }
+                            if ui.button("Keep").clicked() {
+                                self.confirm_clear_history = false;
+                            }
+                        });
+                    } else if ui.button("🗑 Clear history…").clicked() {
+                        self.confirm_clear_history = true;
+                    }
+                    if ui
+                        .button("Export audit log…")
+                        .on_hover_text("Signed JSON lines of every link created, edited or deleted")
+                        .clicked()
+                    {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .set_file_name("shortyio-audit.jsonl")
+                            .add_filter("JSON lines", &["jsonl"])
+                            .save_file()
+                        {
+                            if let Err(e) = audit::export(&path) {
+                                elog!("Failed to export audit log: {}", e);
+                            }
+                        }
+                    }
+                    if ui
+                        .button("Edit hooks script…")
+                        .on_hover_text("A Rhai script that can rewrite links before they're created and pass them on afterwards")
+                        .clicked()
+                    {
+                        match hooks::ensure_script() {
+                            Ok(path) => crash::open_path(&path),
+                            Err(e) => elog!("Failed to create hooks script: {}", e),
+                        }
+                    }
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button("Plugins folder…")
+                            .on_hover_text("Programs here can add buttons to the result panel")
+                            .clicked()
+                        {
+                            match plugins::ensure_dir() {
+                                Ok(dir) => crash::open_path(&dir),
+                                Err(e) => elog!("Failed to create plugins folder: {}", e),
+                            }
+                        }
+                        if ui.button("⟳ Reload plugins").clicked() {
+                            self.load_plugins(ctx.clone());
+                        }
+                        ui.label(egui::RichText::new(format!("{} actions", self.plugin_actions.len())).weak());
+                    });
+                    ui.add_space(12.0);
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Save").clicked() {
+                            self.history.set_retention(self.retention);
+                            let profile = &mut self.profiles[self.active_profile];
+                            profile.set_headers_text(&self.headers_draft);
+                            profile.set_reserved_text(&self.reserved_draft);
+                            api::configure(profile.request_options());
+                            api::warm_up();
+                            self.presets.set_url(&profile.presets_url);
+                            webhook::configure(&profile.webhook_url, &profile.name);
+                            cache::set_persistent(self.cache_on_disk);
+                            clipboard::set_primary_selection(self.primary_selection);
+                            self.thumbnails.set_service(&self.screenshot_service);
+                            tz::set(&self.time_zone);
+                            policy::set_local(policy::parse_rules(&self.blocked_destinations));
+                            self.save_config();
+                            self.show_settings = false;
+                            self.kiosk_unlocked = false;
+                            self.toasts.info("Settings saved");
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.show_settings = false;
+                            self.kiosk_unlocked = false;
+                        }
+                    });
+                });
+        }
+
+        if let Some(problem) = self.storage_problem.clone() {
+            egui::Window::new("Nowhere to save settings")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label("Shortyio couldn't find a home or config directory on this system, so settings and history would be lost on exit.");
+                    ui.label("Pick a folder to keep them in. It's remembered next to the Shortyio executable when possible.");
+                    if !problem.is_empty() {
+                        ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("❌ {}", problem));
+                    }
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        if ui.button("Choose folder…").clicked() {
+                            if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                                match storage::choose_dir(dir) {
+                                    Ok(()) => {
+                                        self.storage_problem = None;
+                                        self.save_config();
+                                    }
+                                    Err(e) => self.storage_problem = Some(e.to_string()),
+                                }
+                            }
+                        }
+                        if ui.button("Continue without saving").clicked() {
+                            self.storage_problem = None;
+                        }
+                    });
+                });
+        }
+
+        if let Some(report) = self.crash_report.clone() {
+            egui::Window::new("Shortyio closed unexpectedly")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label("Sorry! Shortyio crashed last time it ran. A report was saved to:");
+                    ui.label(egui::RichText::new(report.display().to_string()).monospace().size(11.0));
+                    ui.label("Attaching it to a GitHub issue helps get the problem fixed.");
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        if ui.button("Open report").clicked() {
+                            crash::open_path(&report);
+                            crash::dismiss();
+                            self.crash_report = None;
+                        }
+                        if ui.button("Dismiss").clicked() {
+                            crash::dismiss();
+                            self.crash_report = None;
+                        }
+                    });
+                });
+        }
+
+        if self.show_rotations {
+            self.show_rotations_window(ctx);
+        }
+
+        if self.show_watch {
+            self.show_watch_window(ctx);
+        }
+
+        if self.show_history {
+            self.show_history_window(ctx);
+        }
+        if self.show_sync_report {
+            self.show_sync_report_window(ctx);
+        }
+        if self.show_insights {
+            self.show_insights_window(ctx);
+        }
+        if self.show_rollups {
+            self.show_rollups_window(ctx);
+        }
+
+        if self.show_markdown {
+            self.show_markdown_window(ctx);
+        }
+
+        if self.show_jobs {
+            self.show_jobs_window(ctx);
+        }
+
+        if self.show_copied {
+            self.show_copied_window(ctx);
+        }
+
+        if self.show_diagnostics {
+            self.show_diagnostics_window(ctx);
+        }
+
+        if self.show_trash {
+            self.show_trash_window(ctx);
+        }
+
+        self.show_usage_window(ctx);
+
+        if self.show_segment {
+            self.show_segment_window(ctx);
+        }
+
+        if self.show_links {
+            self.show_links_window(ctx);
+        }
+        self.show_edit_window(ctx);
+
+        if self.show_import {
+            self.show_import_window(ctx);
+        }
+
+        if self.show_drafts {
+            self.show_drafts_window(ctx);
+        }
+
+        if self.show_signature {
+            self.show_signature_window(ctx);
+        }
+
+        if self.show_expiring {
+            self.show_expiring_window(ctx);
+        }
+
+        if self.show_retag {
+            self.show_retag_window(ctx);
+        }
+
+        if !self.popouts.is_empty() {
+            self.show_popouts(ctx);
+        }
+
+        if self.show_live_qr {
+            self.show_live_qr_viewport(ctx);
+        }
+
+        if self.show_burst {
+            self.show_burst_window(ctx);
+        }
+
+        if self.compose.is_some() {
+            self.show_compose_window(ctx);
+        }
+
+        for (panel, open) in [
+            (Panel::Links, self.show_links),
+            (Panel::History, self.show_history),
+            (Panel::Jobs, self.show_jobs),
+            (Panel::Drafts, self.show_drafts),
+        ] {
+            self.nav.set_open(panel, open);
+        }
+
+        if self.show_duplicates {
+            self.show_duplicates_window(ctx);
         }
 
+        if self.share_sheet.enabled {
+            self.show_share_sheet_panel(ctx);
+        }
+
+        self.show_status_bar(ctx);
+
+        let logo = self.profile_logo(ctx);
         egui::CentralPanel::default().show(ctx, |ui| {
+            let profile = &self.profiles[self.active_profile];
+            let accent = profile.accent.map(|[r, g, b]| egui::Color32::from_rgb(r, g, b));
+            if let Some(accent) = accent {
+                let top = ui.max_rect();
+                ui.painter().rect_filled(egui::Rect::from_min_size(top.min, egui::vec2(top.width(), 4.0)), 2.0, accent);
+            }
             ui.vertical_centered(|ui| {
                 ui.add_space(16.0);
+                if let Some(logo) = &logo {
+                    let size = logo.size_vec2();
+                    ui.image((logo.id(), size * (40.0 / size.y)));
+                }
                 ui.heading(egui::RichText::new("Shortyio").size(28.0).strong());
                 ui.label(egui::RichText::new("Lightning-fast custom URL shortening").size(12.0).weak());
+                if accent.is_some() || logo.is_some() {
+                    let name = egui::RichText::new(format!("● {}", profile.name)).strong();
+                    ui.label(match accent {
+                        Some(accent) => name.color(accent),
+                        None => name,
+                    });
+                }
             });
 
+            if let Some(release) = self.update_available.clone() {
+                ui.add_space(8.0);
+                ui.group(|ui| {
+                    ui.set_min_width(ui.available_width());
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label(egui::RichText::new(format!("⬆ Shortyio {} is available", release.version)).strong());
+                        ui.hyperlink_to("Release notes", &release.url);
+                        if update::can_self_update() && self.update_status.is_none() && ui.button("Install").clicked() {
+                            self.update_status = Some("Downloading update...".to_string());
+                            update::install(ctx.clone());
+                        }
+                        if ui.small_button("✖").on_hover_text("Dismiss").clicked() {
+                            self.update_available = None;
+                        }
+                    });
+                    if let Some(status) = &self.update_status {
+                        ui.label(egui::RichText::new(status).size(11.0));
+                    }
+                });
+            }
+
+            let broken = self.health.broken();
+            if broken > 0 {
+                ui.add_space(8.0);
+                ui.group(|ui| {
+                    ui.set_min_width(ui.available_width());
+                    ui.horizontal_wrapped(|ui| {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(220, 60, 60),
+                            format!("⚠ {} pinned link(s) no longer redirect as expected", broken),
+                        );
+                        if ui.button("Show").clicked() {
+                            self.show_signature = true;
+                        }
+                    });
+                });
+            }
+
             ui.add_space(20.0);
 
             ui.group(|ui| {
@@ -448,10 +5970,84 @@ impl eframe::App for ShortyApp {
 
                 ui.horizontal(|ui| {
                     ui.label(egui::RichText::new("URL").strong());
+                    self.show_domain_badge(ui);
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         if ui.button("⚙").on_hover_text("Settings").clicked() {
                             self.show_settings = true;
                         }
+                        if !self.kiosk_locked() && ui.button("🔁").on_hover_text("Link rotations").clicked() {
+                            self.show_rotations = true;
+                        }
+                        if ui.button("👁").on_hover_text("Watch a file").clicked() {
+                            self.show_watch = true;
+                        }
+                        if ui.button("📚").on_hover_text("Import bookmarks").clicked() {
+                            self.show_import = true;
+                        }
+                        if ui.button("📝").on_hover_text("Rewrite Markdown links").clicked() {
+                            self.show_markdown = true;
+                        }
+                        if ui
+                            .selectable_label(self.share_sheet.enabled, "🗒")
+                            .on_hover_text("Share sheet: collect links created this session into a Markdown list")
+                            .clicked()
+                        {
+                            self.share_sheet.enabled = !self.share_sheet.enabled;
+                        }
+                        if ui.button("🔗").on_hover_text("My Links").clicked() {
+                            self.show_links = true;
+                        }
+                        if ui.button("🕘").on_hover_text("History").clicked() {
+                            self.show_history = true;
+                        }
+                        if ui
+                            .selectable_label(self.show_live_qr, "📽")
+                            .on_hover_text("Live QR: a full-window QR code of the newest link, for projectors")
+                            .clicked()
+                        {
+                            self.show_live_qr = !self.show_live_qr;
+                        }
+                        if ui.button("⚡").on_hover_text("Burst mode: one link per Enter, for event check-ins").clicked() {
+                            self.show_burst = true;
+                        }
+                        if !self.kiosk_locked() && ui.button("⌛").on_hover_text("Links expiring soon").clicked() {
+                            self.show_expiring = true;
+                            if self.expiring.is_none() && !self.expiring_loading {
+                                self.fetch_expiring(ctx.clone());
+                            }
+                        }
+                        if !self.kiosk_locked() && ui.button("🏷").on_hover_text("Bulk re-tag links").clicked() {
+                            self.show_retag = true;
+                        }
+                        if ui.button("✉").on_hover_text("Pinned links and email signature").clicked() {
+                            self.show_signature = true;
+                        }
+                        if ui.button("🗂").on_hover_text("Drafts awaiting approval").clicked() {
+                            self.show_drafts = true;
+                        }
+                        if ui.button("⏳").on_hover_text("Jobs").clicked() {
+                            self.show_jobs = true;
+                        }
+                        if ui.button("📋").on_hover_text("Copied this session").clicked() {
+                            self.show_copied = true;
+                        }
+                        if ui.button("📅").on_hover_text("Insights").clicked() {
+                            self.show_insights = true;
+                        }
+                        if ui.button("🏆").on_hover_text("Clicks per tag and folder").clicked() {
+                            self.show_rollups = true;
+                        }
+                        if ui.button("📊").on_hover_text("Usage").clicked() {
+                            self.usage = Some(Usage::load());
+                        }
+                        if !self.profiles[self.active_profile].path_prefix.is_empty()
+                            && ui.button("📈").on_hover_text("Clicks under this profile's path prefix").clicked()
+                        {
+                            self.show_segment = true;
+                            if self.segment_stats.is_none() && !self.segment_loading {
+                                self.fetch_segment_stats(ctx.clone());
+                            }
+                        }
                     });
                 });
 
@@ -460,59 +6056,130 @@ impl eframe::App for ShortyApp {
                         .hint_text("https://example.com/your-long-url")
                         .desired_width(f32::INFINITY),
                 );
+                if std::mem::take(&mut self.focus_url) {
+                    url_response.request_focus();
+                }
                 if url_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
                     self.create_short_link(ctx.clone());
                 }
+                form::show_error(ui, &url_response, self.field_errors().url.as_ref());
 
                 ui.add_space(8.0);
                 ui.label(egui::RichText::new("Custom Path (optional)").strong());
-                let path_response = ui.add(
-                    egui::TextEdit::singleline(&mut self.custom_path)
-                        .hint_text("my-custom-link")
-                        .desired_width(f32::INFINITY),
-                );
+                let path_response = ui
+                    .horizontal(|ui| {
+                        let prefix = &self.profiles[self.active_profile].path_prefix;
+                        if !prefix.is_empty() {
+                            ui.label(egui::RichText::new(prefix).monospace().weak());
+                        }
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut self.custom_path)
+                                .hint_text("my-custom-link")
+                                .desired_width(ui.available_width() - 32.0),
+                        )
+                        .on_hover_text("Placeholders {date}, {counter}, {domain} and {title-slug} are filled in when the link is created");
+                        ui.menu_button("😀", |ui| {
+                            ui.set_max_width(240.0);
+                            ui.horizontal_wrapped(|ui| {
+                                for emoji in slugs::EMOJI {
+                                    if ui.button(egui::RichText::new(*emoji).size(18.0)).clicked() {
+                                        self.custom_path.push_str(emoji);
+                                        ui.close_menu();
+                                    }
+                                }
+                            });
+                        })
+                        .response
+                        .on_hover_text("Insert an emoji");
+                        response
+                    })
+                    .inner;
                 if path_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
                     self.create_short_link(ctx.clone());
                 }
+                let path_error = self.field_errors().path;
+                form::show_error(ui, &path_response, path_error.as_ref());
+                let has_path_error = path_error.is_some();
+                if !has_path_error {
+                    if let Some(warning) = self.path_warning() {
+                        ui.colored_label(egui::Color32::from_rgb(220, 140, 40), format!("⚠ {}", warning));
+                    }
+                }
+                if let Some(key) = self.counter_key() {
+                    self.show_counter(ui, &key);
+                }
+                let url = self.original_url.trim().to_string();
+                if self.custom_path.is_empty() && url.starts_with("http") && !self.api_key.is_empty() {
+                    ui.horizontal_wrapped(|ui| {
+                        let suggestions = self.path_suggestions.as_ref().filter(|(for_url, _)| *for_url == url);
+                        if self.suggestions_loading {
+                            ui.spinner();
+                        } else if let Some((_, paths)) = suggestions {
+                            if paths.is_empty() {
+                                ui.weak("No free paths to suggest");
+                            }
+                            for path in paths.clone() {
+                                if ui.small_button(&path).on_hover_text("Use this path").clicked() {
+                                    self.custom_path = path;
+                                }
+                            }
+                        } else if ui
+                            .small_button("✨ Suggest paths")
+                            .on_hover_text("Suggest free paths from the page title and URL")
+                            .clicked()
+                        {
+                            self.suggest_paths(ctx.clone());
+                        }
+                    });
+                } else if !has_path_error && !self.custom_path.is_ascii() {
+                    let path = self.profiles[self.active_profile].prefixed_path(&self.custom_path);
+                    let domain = if self.domain.is_empty() { "short.gy" } else { &self.domain };
+                    ui.label(
+                        egui::RichText::new(format!("Registered as https://{}/{}", domain, slugs::encode(&path)))
+                            .monospace()
+                            .size(11.0)
+                            .weak(),
+                    );
+                }
 
                 ui.add_space(8.0);
+                ui.label(egui::RichText::new("Tags (optional)").strong());
+                let tags_response = ui.add(
+                    egui::TextEdit::singleline(&mut self.tags)
+                        .hint_text("campaign-spring, newsletter")
+                        .desired_width(f32::INFINITY),
+                );
+                form::show_error(ui, &tags_response, self.field_errors().tags.as_ref());
 
-                ui.collapsing(egui::RichText::new("Advanced Options").strong(), |ui| {
-                    ui.add_space(4.0);
-
-                    ui.checkbox(&mut self.cloaking, "Enable cloaking")
-                        .on_hover_text("Hide the redirect in an iframe");
-
-                    ui.add_space(4.0);
-                    ui.label("Password (optional):");
-                    ui.add(
-                        egui::TextEdit::singleline(&mut self.password)
-                            .password(true)
-                            .hint_text("Protect link with password"),
-                    );
-
-                    ui.checkbox(&mut self.password_contact, "Show contact for password")
-                        .on_hover_text("Provide email to users to get password");
+                ui.add_space(8.0);
 
-                    ui.add_space(4.0);
-                    ui.label("Clicks Limit (optional):");
-                    ui.add(
-                        egui::TextEdit::singleline(&mut self.clicks_limit)
-                            .hint_text("e.g., 100")
-                            .desired_width(100.0),
-                    ).on_hover_text("Disable link after this many clicks");
+                let advanced = egui::CollapsingHeader::new(egui::RichText::new("Advanced Options").strong())
+                    .show(ui, |ui| self.show_advanced_options(ui, ctx));
 
+                let chips = self.option_chips();
+                if !chips.is_empty() {
                     ui.add_space(4.0);
-                    ui.label("Redirect Type:");
-                    ui.horizontal(|ui| {
-                        ui.radio_value(&mut self.redirect_type, 301, "301 (Permanent)");
-                        ui.radio_value(&mut self.redirect_type, 302, "302 (Temporary)");
-                    });
-                    ui.horizontal(|ui| {
-                        ui.radio_value(&mut self.redirect_type, 307, "307 (Temporary)");
-                        ui.radio_value(&mut self.redirect_type, 308, "308 (Permanent)");
+                    ui.horizontal_wrapped(|ui| {
+                        for (chip, label) in chips {
+                            let response = ui
+                                .small_button(format!("{} ✖", label))
+                                .on_hover_text("Click to remove, right-click to edit");
+                            if response.clicked() {
+                                self.clear_option(chip);
+                            }
+                            if response.secondary_clicked() {
+                                self.advanced_tab = chip.tab();
+                                let mut state = egui::collapsing_header::CollapsingState::load_with_default_open(
+                                    ctx,
+                                    advanced.header_response.id,
+                                    false,
+                                );
+                                state.set_open(true);
+                                state.store(ctx);
+                            }
+                        }
                     });
-                });
+                }
 
                 ui.add_space(12.0);
 
@@ -524,6 +6191,25 @@ impl eframe::App for ShortyApp {
                     if ui.add_enabled(!self.loading, button).clicked() {
                         self.create_short_link(ctx.clone());
                     }
+
+                    ui.add_space(4.0);
+                    let paste = egui::Button::new("📋 Paste & Shorten").min_size(egui::vec2(200.0, 24.0));
+                    if ui
+                        .add_enabled(!self.loading, paste)
+                        .on_hover_text(format!("Shorten the URL on your clipboard ({})", ctx.format_shortcut(&PASTE_AND_SHORTEN)))
+                        .clicked()
+                    {
+                        self.paste_and_shorten(ctx.clone());
+                    }
+
+                    ui.add_space(4.0);
+                    if ui
+                        .add(egui::Button::new("💾 Save as Draft").min_size(egui::vec2(200.0, 24.0)))
+                        .on_hover_text("Keep this link for someone with an API key to approve")
+                        .clicked()
+                    {
+                        self.save_draft();
+                    }
                 });
 
                 ui.add_space(8.0);
@@ -538,6 +6224,39 @@ impl eframe::App for ShortyApp {
                 });
             }
 
+            if let Some(existing) = self.duplicate.clone() {
+                ui.add_space(8.0);
+                ui.group(|ui| {
+                    ui.set_min_width(ui.available_width());
+                    ui.label(egui::RichText::new("You've already shortened this URL").strong());
+                    ui.label(format!(
+                        "{} (created {})",
+                        existing.short_url,
+                        existing.created_at.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+                    ));
+                    ui.add_space(4.0);
+                    ui.horizontal(|ui| {
+                        if ui.button("📋 Copy existing").clicked() {
+                            clipboard::copy(&existing.short_url);
+                            self.toasts.info("Copied");
+                            self.result = Some(LinkResponse {
+                                id: existing.link_id.clone(),
+                                short_url: existing.short_url.clone(),
+                                original_url: existing.original_url.clone(),
+                                ..LinkResponse::default()
+                            });
+                            self.duplicate = None;
+                        }
+                        if ui.button("Create new anyway").clicked() {
+                            self.submit_link(ctx.clone(), true);
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.duplicate = None;
+                        }
+                    });
+                });
+            }
+
             if let Some(error) = &self.error {
                 ui.add_space(8.0);
                 ui.group(|ui| {
@@ -546,6 +6265,15 @@ impl eframe::App for ShortyApp {
                 });
             }
 
+            let mut slack_copy: Option<(String, String, Option<String>)> = None;
+            let mut shorten_another = false;
+            let mut write_nfc = None;
+            let mut test_redirect = None;
+            let mut run_plugin = None;
+            let mut compose = None;
+            let mut make_card = None;
+            let mut attach_card = None;
+            let mut popout: Option<(PopoutKind, String, String)> = None;
             if let Some(result) = &self.result {
                 ui.add_space(8.0);
                 ui.group(|ui| {
@@ -563,9 +6291,117 @@ impl eframe::App for ShortyApp {
                                 .desired_width(ui.available_width() - 70.0),
                         );
                         if ui.button("📋 Copy").clicked() {
-                            ui.output_mut(|o| o.copied_text = result.short_url.clone());
+                            clipboard::copy(&result.short_url);
+                            self.recent.mark_copied(&result.short_url);
+                            self.toasts.info("Copied");
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        if self.slack_loading {
+                            ui.spinner();
+                        } else if ui
+                            .button("💬 Copy for Slack")
+                            .on_hover_text("Copy as <url|page title> so it reads well in Slack")
+                            .clicked()
+                        {
+                            slack_copy = Some((result.short_url.clone(), result.original_url.clone(), None));
+                        }
+                        if ui.button("📣 Post…").on_hover_text("Write a Mastodon or X post with the link").clicked() {
+                            compose = Some((result.short_url.clone(), result.original_url.clone()));
+                        }
+                        if ui.button("🔳 QR").on_hover_text("Show a large QR code in its own window").clicked() {
+                            popout = Some((PopoutKind::Qr, result.id.clone(), result.short_url.clone()));
+                        }
+                        if ui.button("📈 Clicks").on_hover_text("Show a clicks chart in its own window").clicked() {
+                            popout = Some((PopoutKind::Stats, result.id.clone(), result.short_url.clone()));
+                        }
+                        if nfc::available() {
+                            if self.nfc_busy {
+                                ui.spinner();
+                            } else if ui.button("📶 NFC").on_hover_text("Write the short URL to a tag on the NFC reader").clicked() {
+                                write_nfc = Some(result.short_url.clone());
+                            }
+                        }
+                        if self.redirect_test_busy {
+                            ui.spinner();
+                        } else if ui
+                            .button("🔁 Test redirect")
+                            .on_hover_text("Request the short URL and check it redirects to the destination")
+                            .clicked()
+                        {
+                            test_redirect = Some((result.short_url.clone(), result.original_url.clone()));
+                        }
+                        if ui.button("➕ Shorten another").on_hover_text("Clear the form, keeping advanced options").clicked() {
+                            shorten_another = true;
                         }
                     });
+                    if !self.plugin_actions.is_empty() {
+                        ui.horizontal_wrapped(|ui| {
+                            for action in &self.plugin_actions {
+                                let button = ui.add_enabled(!self.plugin_busy, egui::Button::new(&action.label));
+                                let button = if action.hint.is_empty() { button } else { button.on_hover_text(&action.hint) };
+                                if button.clicked() {
+                                    run_plugin = Some(action.clone());
+                                }
+                            }
+                            if self.plugin_busy {
+                                ui.spinner();
+                            }
+                        });
+                    }
+
+                    if self.og_missing.as_ref() == Some(&result.short_url) {
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new("This page has no share image, so shared links show a bare URL.").weak());
+                            if self.og_busy {
+                                ui.spinner();
+                            } else if ui.button("🖼 Make card…").on_hover_text("Generate a 1200×630 preview image").clicked() {
+                                make_card = Some((result.short_url.clone(), result.original_url.clone()));
+                            }
+                        });
+                        if self.og_card.is_some() {
+                            ui.horizontal(|ui| {
+                                ui.label("Uploaded to:");
+                                ui.add(
+                                    egui::TextEdit::singleline(&mut self.og_image_url)
+                                        .hint_text("https://cdn.example.com/card.png")
+                                        .desired_width(ui.available_width() - 80.0),
+                                );
+                                let ready = !self.og_busy && self.og_image_url.trim().starts_with("http");
+                                if ui.add_enabled(ready, egui::Button::new("Attach")).clicked() {
+                                    attach_card = Some(result.id.clone());
+                                }
+                            });
+                        }
+                    }
+                    if let Some(status) = &self.og_status {
+                        ui.label(egui::RichText::new(status).weak());
+                    }
+                    if let Some(health) = &self.redirect_test {
+                        if health.ok {
+                            ui.colored_label(egui::Color32::from_rgb(60, 160, 60), format!("✔ {}", health.message));
+                        } else {
+                            ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("⚠ {}", health.message));
+                        }
+                    }
+                    match &self.nfc_status {
+                        Some(Ok(reader)) => {
+                            ui.colored_label(egui::Color32::from_rgb(60, 160, 60), format!("✔ Written to tag on {}", reader));
+                        }
+                        Some(Err(e)) => {
+                            ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("NFC: {}", e));
+                        }
+                        None => {}
+                    }
+                    match &self.plugin_status {
+                        Some(Ok(message)) => {
+                            ui.colored_label(egui::Color32::from_rgb(60, 160, 60), format!("✔ {}", message));
+                        }
+                        Some(Err(e)) => {
+                            ui.colored_label(egui::Color32::from_rgb(220, 60, 60), e);
+                        }
+                        None => {}
+                    }
 
                     ui.add_space(4.0);
                     ui.horizontal(|ui| {
@@ -577,14 +6413,404 @@ impl eframe::App for ShortyApp {
                 });
             }
 
+            let current = self.result.as_ref().map(|result| result.short_url.clone());
+            let earlier: Vec<_> = self
+                .recent
+                .items()
+                .iter()
+                .filter(|item| Some(&item.short_url) != current.as_ref())
+                .cloned()
+                .collect();
+            if !earlier.is_empty() {
+                ui.add_space(8.0);
+                egui::CollapsingHeader::new(egui::RichText::new(format!("Earlier results ({})", earlier.len())).strong())
+                    .id_salt("earlier_results")
+                    .default_open(earlier.iter().any(|item| !item.copied))
+                    .show(ui, |ui| {
+                        for item in &earlier {
+                            ui.horizontal(|ui| {
+                                if ui.small_button("📋").on_hover_text("Copy").clicked() {
+                                    clipboard::copy(&item.short_url);
+                                    self.recent.mark_copied(&item.short_url);
+                                    self.toasts.info("Copied");
+                                }
+                                if ui.small_button("✖").on_hover_text("Dismiss").clicked() {
+                                    self.recent.remove(&item.short_url);
+                                    self.toasts.with_undo("Result dismissed", Undo::Recent(item.clone()));
+                                }
+                                ui.label(egui::RichText::new(&item.short_url).monospace());
+                                let note = if item.copied { "" } else { " · not copied yet" };
+                                ui.add(
+                                    egui::Label::new(
+                                        egui::RichText::new(format!(
+                                            "{}{} → {}",
+                                            item.created_at.with_timezone(&Local).format("%H:%M"),
+                                            note,
+                                            item.original_url
+                                        ))
+                                        .weak()
+                                        .size(11.0),
+                                    )
+                                    .truncate(),
+                                );
+                            });
+                        }
+                    });
+            }
+
+            if let Some((short_url, original_url, title)) = slack_copy {
+                self.copy_for_slack(&short_url, &original_url, title.as_deref(), ctx.clone());
+            }
+            if shorten_another {
+                self.shorten_another();
+            }
+            if let Some(short_url) = write_nfc {
+                self.write_nfc(short_url, ctx.clone());
+            }
+            if let Some((short_url, original_url)) = test_redirect {
+                self.test_redirect(short_url, original_url, ctx.clone());
+            }
+            if let Some(action) = run_plugin {
+                self.run_plugin(action, ctx.clone());
+            }
+            if let Some((short_url, original_url)) = compose {
+                self.start_compose(short_url, original_url, ctx.clone());
+            }
+            if let Some((short_url, original_url)) = make_card {
+                self.make_og_card(&short_url, &original_url, ctx.clone());
+            }
+            if let Some(link_id) = attach_card {
+                self.attach_og_card(link_id, ctx.clone());
+            }
+            if let Some((kind, link_id, short_url)) = popout {
+                self.open_popout(kind, &link_id, &short_url, ctx);
+            }
+
+            let scheduled = self.scheduler.pending();
+            if !scheduled.is_empty() {
+                ctx.request_repaint_after(std::time::Duration::from_secs(30));
+                ui.add_space(8.0);
+                ui.collapsing(egui::RichText::new(format!("Scheduled ({})", scheduled.len())).strong(), |ui| {
+                    for activation in &scheduled {
+                        ui.horizontal(|ui| {
+                            if !self.kiosk_locked() && ui.small_button("✖").on_hover_text("Cancel go-live").clicked() {
+                                self.scheduler.cancel(&activation.link_id);
+                            }
+                            ui.label(&activation.short_url);
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "→ {} at {}",
+                                    activation.destination,
+                                    tz::local(activation.go_live_at).format("%Y-%m-%d %H:%M")
+                                ))
+                                .weak()
+                                .size(11.0),
+                            );
+                        });
+                        if let Some(error) = &activation.last_error {
+                            ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("Last attempt failed: {}", error));
+                        }
+                    }
+                });
+            }
+
             ui.add_space(8.0);
             ui.vertical_centered(|ui| {
                 ui.label(egui::RichText::new("Press ESC to exit").size(10.0).weak());
             });
         });
+
+        match self.toasts.show(ctx) {
+            Some(Undo::Recent(item)) => self.recent.restore(item),
+            Some(Undo::ShareItem(index, item)) => {
+                let index = index.min(self.share_sheet.items.len());
+                self.share_sheet.items.insert(index, item);
+            }
+            Some(Undo::Pin(index, pin)) => {
+                let index = index.min(self.pins.items.len());
+                self.pins.items.insert(index, pin);
+                self.pins.save();
+            }
+            Some(Undo::Trashed(item)) => self.run_trash(trash::Action::Restore(item), ctx.clone()),
+            None => {}
+        }
+    }
+}
+
+/// Every tag used in history as a toggle, sized by how often it's used.
+#[cfg(feature = "gui")]
+fn show_tag_cloud(ui: &mut egui::Ui, entries: &[HistoryEntry], selected: &mut HashSet<String>) {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for tag in entries.iter().flat_map(|entry| &entry.tags) {
+        // Every link the app creates has this one
+        if tag != "shortyio" {
+            *counts.entry(tag).or_default() += 1;
+        }
+    }
+    if counts.is_empty() {
+        return;
+    }
+    let mut tags: Vec<(&str, usize)> = counts.into_iter().collect();
+    tags.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let most = tags[0].1 as f32;
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing.x = 4.0;
+        for (tag, count) in tags.into_iter().take(40) {
+            let size = 11.0 + 5.0 * count as f32 / most;
+            let on = selected.contains(tag);
+            if ui
+                .selectable_label(on, egui::RichText::new(format!("🏷 {}", tag)).size(size))
+                .on_hover_text(format!("{} link(s)", count))
+                .clicked()
+            {
+                if on {
+                    selected.remove(tag);
+                } else {
+                    selected.insert(tag.to_string());
+                }
+            }
+        }
+    });
+}
+
+#[cfg(feature = "gui")]
+fn popout_viewport(kind: PopoutKind, short_url: &str) -> egui::ViewportId {
+    egui::ViewportId::from_hash_of(("popout", kind == PopoutKind::Qr, short_url))
+}
+
+/// Returns the id of a link picked to compare with, which needs its stats loaded.
+#[cfg(feature = "gui")]
+fn show_popout(ui: &mut egui::Ui, popout: &mut Popout, links: &[(String, String)]) -> Option<String> {
+    match popout.kind {
+        PopoutKind::Qr => {
+            ui.vertical_centered(|ui| {
+                let size = ui.available_width().min(ui.available_height() - 130.0).max(64.0);
+                qr::show(ui, &popout.short_url, size);
+                ui.add_space(8.0);
+                ui.label(egui::RichText::new(&popout.short_url).monospace().size(18.0));
+            });
+            ui.separator();
+            show_poster_form(ui, popout);
+            None
+        }
+        PopoutKind::Stats => {
+            let picked = show_compare_picker(ui, popout, links);
+            match &popout.daily {
+                None => {
+                    ui.spinner();
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("Failed to load clicks: {}", e));
+                }
+                Some(Ok(daily)) => {
+                    let other = popout.compare.as_ref().and_then(|compare| match &compare.daily {
+                        Some(Ok(daily)) => Some((compare.short_url.as_str(), daily)),
+                        _ => None,
+                    });
+                    show_click_comparison(ui, &popout.link_id, (popout.short_url.as_str(), daily), other);
+                }
+            }
+            picked
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+fn show_compare_picker(ui: &mut egui::Ui, popout: &mut Popout, links: &[(String, String)]) -> Option<String> {
+    let mut picked = None;
+    ui.horizontal(|ui| {
+        ui.label("Compare with:");
+        let selected = popout.compare.as_ref().map_or("Nothing", |compare| compare.short_url.as_str()).to_string();
+        egui::ComboBox::from_id_salt(("compare", &popout.link_id))
+            .selected_text(selected)
+            .width(260.0)
+            .show_ui(ui, |ui| {
+                if ui.selectable_label(popout.compare.is_none(), "Nothing").clicked() {
+                    popout.compare = None;
+                }
+                for (link_id, short_url) in links.iter().filter(|(link_id, _)| *link_id != popout.link_id) {
+                    let on = popout.compare.as_ref().is_some_and(|compare| compare.link_id == *link_id);
+                    if ui.selectable_label(on, short_url).clicked() && !on {
+                        popout.compare = Some(Comparison {
+                            link_id: link_id.clone(),
+                            short_url: short_url.clone(),
+                            daily: None,
+                        });
+                        picked = Some(link_id.clone());
+                    }
+                }
+            });
+        if let Some(compare) = &popout.compare {
+            match &compare.daily {
+                None => {
+                    ui.spinner();
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(egui::Color32::from_rgb(220, 60, 60), e);
+                }
+                Some(Ok(_)) => {}
+            }
+        }
+    });
+    picked
+}
+
+/// Daily clicks of one link, or two overlaid on shared dates with their
+/// totals and each one's share of the combined clicks.
+#[cfg(feature = "gui")]
+fn show_click_comparison(
+    ui: &mut egui::Ui,
+    link_id: &str,
+    this: (&str, &Vec<(NaiveDate, u64)>),
+    other: Option<(&str, &Vec<(NaiveDate, u64)>)>,
+) {
+    let series: Vec<(&str, &Vec<(NaiveDate, u64)>)> = std::iter::once(this).chain(other).collect();
+    let totals: Vec<u64> = series.iter().map(|(_, daily)| daily.iter().map(|(_, clicks)| clicks).sum()).collect();
+    match (other, totals.as_slice()) {
+        (Some((other_url, _)), &[a, b]) => {
+            let combined = (a + b).max(1) as f64;
+            ui.label(egui::RichText::new(format!("{}: {} clicks ({:.0}%)", this.0, a, 100.0 * a as f64 / combined)).strong());
+            ui.label(egui::RichText::new(format!("{}: {} clicks ({:.0}%)", other_url, b, 100.0 * b as f64 / combined)).strong());
+            let ratio = if b == 0 { "—".to_string() } else { format!("{:.2}×", a as f64 / b as f64) };
+            ui.label(egui::RichText::new(format!("Ratio: {}", ratio)).weak());
+        }
+        _ => {
+            ui.label(egui::RichText::new(format!("{} clicks in the last {} days", totals[0], this.1.len())).strong());
+        }
     }
+
+    let first_day = series.iter().filter_map(|(_, daily)| daily.first().map(|(day, _)| *day)).min();
+    egui_plot::Plot::new(("popout_stats", link_id))
+        .include_y(0.0)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .legend(egui_plot::Legend::default())
+        .x_axis_formatter(move |mark, _| match first_day {
+            Some(day) if mark.value >= 0.0 => (day + chrono::Days::new(mark.value as u64)).format("%b %d").to_string(),
+            _ => String::new(),
+        })
+        .show(ui, |plot_ui| {
+            for (short_url, daily) in &series {
+                let points: egui_plot::PlotPoints = daily
+                    .iter()
+                    .filter_map(|(day, clicks)| Some([(*day - first_day?).num_days() as f64, *clicks as f64]))
+                    .collect();
+                plot_ui.line(egui_plot::Line::new(points).name(*short_url));
+            }
+        });
 }
 
+/// One side of an edit's diff, with the part that differs highlighted.
+#[cfg(feature = "gui")]
+fn diff_text(start: &str, changed: &str, end: &str, color: egui::Color32, highlight: egui::Color32) -> egui::text::LayoutJob {
+    let plain = egui::TextFormat {
+        color,
+        ..Default::default()
+    };
+    let marked = egui::TextFormat {
+        color: highlight,
+        background: highlight.gamma_multiply(0.15),
+        ..Default::default()
+    };
+    let mut job = egui::text::LayoutJob::default();
+    job.append(start, 0.0, plain.clone());
+    job.append(changed, 0.0, marked);
+    job.append(end, 0.0, plain);
+    job.wrap.max_width = 200.0;
+    job
+}
+
+/// Title and logo for a printable poster of the QR code.
+#[cfg(feature = "gui")]
+fn show_poster_form(ui: &mut egui::Ui, popout: &mut Popout) {
+    ui.horizontal(|ui| {
+        ui.label("Poster title:");
+        ui.add(
+            egui::TextEdit::singleline(&mut popout.poster_title)
+                .hint_text("Optional")
+                .desired_width(f32::INFINITY),
+        );
+    });
+    ui.horizontal(|ui| {
+        ui.label("Logo:");
+        let logo = popout.poster_logo.as_ref().and_then(|path| path.file_name()).map(|name| name.to_string_lossy());
+        ui.label(egui::RichText::new(logo.as_deref().unwrap_or("None")).weak());
+        if ui.small_button("Browse…").clicked() {
+            if let Some(path) = rfd::FileDialog::new().add_filter("Images", &["png", "jpg", "jpeg"]).pick_file() {
+                popout.poster_logo = Some(path);
+            }
+        }
+        if popout.poster_logo.is_some() && ui.small_button("✖").on_hover_text("No logo").clicked() {
+            popout.poster_logo = None;
+        }
+    });
+    ui.horizontal(|ui| {
+        if ui.button("🖨 Print QR poster…").on_hover_text("Save an A4 PDF and open it for printing").clicked() {
+            if let Some(path) = rfd::FileDialog::new()
+                .set_file_name("qr-poster.pdf")
+                .add_filter("PDF", &["pdf"])
+                .save_file()
+            {
+                match poster::write(
+                    &path,
+                    &popout.short_url,
+                    &popout.poster_title,
+                    popout.poster_logo.as_deref(),
+                    popout.poster_accent,
+                ) {
+                    Ok(()) => {
+                        popout.poster_status = None;
+                        ui.ctx().open_url(egui::OpenUrl::new_tab(format!("file://{}", path.display())));
+                    }
+                    Err(e) => popout.poster_status = Some(format!("Failed to save poster: {}", e)),
+                }
+            }
+        }
+        if ui.button("📋 Copy QR image").clicked() {
+            match qr::image(&popout.short_url, 10) {
+                Ok(image) => {
+                    let rgba = image::DynamicImage::ImageLuma8(image).to_rgba8();
+                    let (width, height) = (rgba.width() as usize, rgba.height() as usize);
+                    clipboard::copy_image(&format!("QR code · {}", popout.short_url), width, height, std::sync::Arc::new(rgba.into_raw()));
+                }
+                Err(e) => popout.poster_status = Some(format!("Failed to make QR code: {}", e)),
+            }
+        }
+        if let Some(status) = &popout.poster_status {
+            ui.colored_label(egui::Color32::from_rgb(220, 60, 60), status);
+        }
+    });
+}
+
+/// The first URL found in the clipboard's text, if any.
+#[cfg(feature = "gui")]
+fn clipboard_url() -> Option<String> {
+    let text = clipboard::text()?;
+    urls::find_url(&text).map(str::to_string)
+}
+
+/// Splits pasted text into one URL per non-empty line.
+#[cfg(feature = "gui")]
+fn parse_url_lines(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Splits a comma-separated settings field into trimmed, non-empty items.
+#[cfg(feature = "gui")]
+fn split_list(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(feature = "gui")]
 fn load_icon() -> egui::IconData {
     let icon_bytes = include_bytes!("../icon.png");
     let image = image::load_from_memory(icon_bytes)
@@ -598,22 +6824,49 @@ fn load_icon() -> egui::IconData {
     }
 }
 
+#[cfg(feature = "gui")]
 fn main() -> Result<(), eframe::Error> {
-    let icon = load_icon();
+    crash::install();
+
+    let cli = cli::parse();
+    if cli.is_command() {
+        std::process::exit(cli::run(cli));
+    }
 
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([500.0, 520.0])
             .with_resizable(true)
             .with_min_inner_size([480.0, 400.0])
-            .with_icon(icon)
             .with_app_id("systems.weedmark.shortyio"),
         ..Default::default()
     };
 
-    eframe::run_native(
+    let result = eframe::run_native(
         "Shortyio",
         options,
-        Box::new(|_cc| Ok(Box::new(ShortyApp::default()))),
-    )
+        Box::new(|cc| {
+            // Decoding the icon can wait until the window is up
+            let ctx = cc.egui_ctx.clone();
+            std::thread::spawn(move || ctx.send_viewport_cmd(egui::ViewportCommand::Icon(Some(std::sync::Arc::new(load_icon())))));
+            Ok(Box::new(ShortyApp::default()))
+        }),
+    );
+    if Config::load().is_some_and(|c| c.keep_clipboard) {
+        clipboard::hold_after_exit();
+    }
+    result
+}
+
+/// Builds without the `gui` feature only have the terminal commands.
+#[cfg(not(feature = "gui"))]
+fn main() {
+    crash::install();
+
+    let cli = cli::parse();
+    if !cli.is_command() {
+        let _ = <cli::Cli as clap::CommandFactory>::command().print_help();
+        std::process::exit(1);
+    }
+    std::process::exit(cli::run(cli));
 }
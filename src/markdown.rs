@@ -0,0 +1,155 @@
+use crate::urls::host;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Byte ranges of every link destination in `text`, skipping images, fenced
+/// code blocks and URLs on excluded domains.
+pub fn find_links(text: &str, exclude: &[String]) -> Vec<Range<usize>> {
+    let code = fenced_code_ranges(text);
+    let in_code = |pos: usize| code.iter().any(|r| r.contains(&pos));
+    let mut spans: Vec<Range<usize>> = Vec::new();
+
+    // Inline links: [text](url "title")
+    let mut search = 0;
+    while let Some(found) = text[search..].find("](") {
+        let close = search + found;
+        let start = close + 2;
+        search = start;
+        if in_code(close) || is_image(text, close) {
+            continue;
+        }
+        let end = inline_destination_end(text, start);
+        let (start, end) = strip_angle_brackets(text, start, end);
+        if start < end {
+            spans.push(start..end);
+        }
+    }
+
+    // Reference definitions: [id]: url
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        if indent <= 3 && trimmed.starts_with('[') && !in_code(offset) {
+            if let Some(colon) = trimmed.find("]:") {
+                let after = &trimmed[colon + 2..];
+                let url_start = offset + indent + colon + 2 + (after.len() - after.trim_start().len());
+                let url_end = url_start + text[url_start..].find(char::is_whitespace).unwrap_or(text.len() - url_start);
+                let (start, end) = strip_angle_brackets(text, url_start, url_end);
+                if start < end {
+                    spans.push(start..end);
+                }
+            }
+        }
+        offset += line.len();
+    }
+
+    // Autolinks and bare URLs
+    let mut search = 0;
+    while let Some(found) = find_scheme(&text[search..]) {
+        let start = search + found;
+        let mut end = start + text[start..].find(|c: char| c.is_whitespace() || c == '<' || c == '>').unwrap_or(text.len() - start);
+        while end > start && text[..end].ends_with(['.', ',', ';', ':', '!', '?', ')', '"', '\'', ']', '*', '_']) {
+            end -= 1;
+        }
+        search = end.max(start + 1);
+        if in_code(start) || spans.iter().any(|s| s.contains(&start)) {
+            continue;
+        }
+        spans.push(start..end);
+    }
+
+    spans.retain(|span| {
+        let url = &text[span.clone()];
+        (url.starts_with("http://") || url.starts_with("https://")) && !is_excluded(url, exclude)
+    });
+    spans.sort_by_key(|span| span.start);
+    spans.dedup();
+    spans
+}
+
+/// Replaces each link span with its shortened form where one is available.
+pub fn rewrite(text: &str, spans: &[Range<usize>], shortened: &HashMap<String, String>) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut last = 0;
+    for span in spans {
+        output.push_str(&text[last..span.start]);
+        let url = &text[span.clone()];
+        output.push_str(shortened.get(url).map(String::as_str).unwrap_or(url));
+        last = span.end;
+    }
+    output.push_str(&text[last..]);
+    output
+}
+
+fn is_excluded(url: &str, exclude: &[String]) -> bool {
+    let host = host(url);
+    exclude.iter().any(|domain| {
+        let domain = domain.trim().trim_start_matches("*.").to_lowercase();
+        !domain.is_empty() && (host == domain || host.ends_with(&format!(".{}", domain)))
+    })
+}
+
+fn find_scheme(text: &str) -> Option<usize> {
+    match (text.find("http://"), text.find("https://")) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+fn is_image(text: &str, close: usize) -> bool {
+    let mut depth = 0;
+    for (i, c) in text[..close].char_indices().rev() {
+        match c {
+            ']' => depth += 1,
+            '[' if depth == 0 => return text[..i].ends_with('!'),
+            '[' => depth -= 1,
+            '\n' if text[..i].ends_with('\n') => return false,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// End of an inline link destination, allowing balanced parentheses.
+fn inline_destination_end(text: &str, start: usize) -> usize {
+    let mut depth = 0;
+    for (i, c) in text[start..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return start + i,
+            ')' => depth -= 1,
+            c if c.is_whitespace() => return start + i,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+fn strip_angle_brackets(text: &str, start: usize, end: usize) -> (usize, usize) {
+    let url = &text[start..end];
+    if url.starts_with('<') && url.ends_with('>') && url.len() >= 2 {
+        (start + 1, end - 1)
+    } else {
+        (start, end)
+    }
+}
+
+fn fenced_code_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut open: Option<usize> = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+            match open.take() {
+                Some(start) => ranges.push(start..offset + line.len()),
+                None => open = Some(offset),
+            }
+        }
+        offset += line.len();
+    }
+    if let Some(start) = open {
+        ranges.push(start..text.len());
+    }
+    ranges
+}
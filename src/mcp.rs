@@ -0,0 +1,147 @@
+use crate::cli;
+use anyhow::{Context, Result, anyhow};
+use clap::ValueEnum;
+use chrono::NaiveDate;
+use serde_json::{Value, json};
+use std::io::{self, BufRead, Write};
+
+/// Used when the client doesn't say which revision it speaks.
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Serves shorten, list and stats as Model Context Protocol tools over
+/// stdin/stdout, one JSON-RPC message per line, until stdin closes. Calls go
+/// through the same code as the terminal commands, so the assistant on the
+/// other end gets links and numbers but never the API key.
+pub fn serve(profile: Option<String>) -> Result<()> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout().lock();
+    for line in stdin.lock().lines() {
+        let line = line.context("Failed to read from stdin")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => handle(&message, profile.as_deref()),
+            Err(e) => Some(error(Value::Null, PARSE_ERROR, &e.to_string())),
+        };
+        if let Some(reply) = reply {
+            writeln!(stdout, "{}", reply)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// The reply to one message; notifications get none.
+fn handle(message: &Value, profile: Option<&str>) -> Option<Value> {
+    let id = message.get("id")?.clone();
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+    let result = match message["method"].as_str().unwrap_or_default() {
+        "initialize" => json!({
+            "protocolVersion": params["protocolVersion"].as_str().unwrap_or(PROTOCOL_VERSION),
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "shortyio", "version": env!("CARGO_PKG_VERSION") },
+        }),
+        "ping" => json!({}),
+        "tools/list" => json!({ "tools": tools() }),
+        "tools/call" => {
+            let Some(name) = params["name"].as_str() else {
+                return Some(error(id, INVALID_PARAMS, "Missing tool name"));
+            };
+            match call(name, &params["arguments"], profile) {
+                Ok(text) => json!({ "content": [{ "type": "text", "text": text }] }),
+                // Tool failures go back to the model as results it can read
+                Err(e) => json!({ "content": [{ "type": "text", "text": format!("{:#}", e) }], "isError": true }),
+            }
+        }
+        method => return Some(error(id, METHOD_NOT_FOUND, &format!("Unknown method {}", method))),
+    };
+    Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn tools() -> Value {
+    json!([
+        {
+            "name": "shorten",
+            "description": "Create a tracked short link for a URL on the user's short.io domain and return it.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string", "description": "The http(s) URL to shorten" },
+                    "path": { "type": "string", "description": "Optional custom path, e.g. spring-sale" },
+                    "tags": { "type": "array", "items": { "type": "string" }, "description": "Optional tags" },
+                },
+                "required": ["url"],
+            },
+        },
+        {
+            "name": "list_links",
+            "description": "List short links on the user's domain, newest first.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "tag": { "type": "string", "description": "Only links with this tag" },
+                    "folder": { "type": "string", "description": "Only links in this folder" },
+                    "since": { "type": "string", "description": "Only links created on or after this date (YYYY-MM-DD)" },
+                    "until": { "type": "string", "description": "Only links created on or before this date (YYYY-MM-DD)" },
+                },
+            },
+        },
+        {
+            "name": "link_stats",
+            "description": "Click totals and clicks per day for a short link.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "short_url": { "type": "string", "description": "The full short URL" },
+                    "period": {
+                        "type": "string",
+                        "enum": cli::Period::value_variants().iter().map(|period| period.as_str()).collect::<Vec<_>>(),
+                        "description": "Defaults to last30",
+                    },
+                },
+                "required": ["short_url"],
+            },
+        },
+    ])
+}
+
+/// Runs a tool and returns its result as pretty JSON.
+fn call(name: &str, arguments: &Value, profile: Option<&str>) -> Result<String> {
+    let text = |key: &str| arguments[key].as_str().map(str::to_string);
+    let date = |key: &str| -> Result<Option<NaiveDate>> {
+        text(key)
+            .map(|value| value.parse().with_context(|| format!("{} must be a YYYY-MM-DD date", key)))
+            .transpose()
+    };
+    let value = match name {
+        "shorten" => {
+            let url = text("url").ok_or_else(|| anyhow!("url is required"))?;
+            let tags = arguments["tags"]
+                .as_array()
+                .map(|tags| tags.iter().filter_map(|tag| tag.as_str().map(str::to_string)).collect())
+                .unwrap_or_default();
+            let link = cli::create(url, text("path"), tags, profile)?;
+            json!({ "short_url": link.short_url, "original_url": link.original_url })
+        }
+        "list_links" => serde_json::to_value(cli::find_links(text("tag"), text("folder"), date("since")?, date("until")?, profile)?)?,
+        "link_stats" => {
+            let short_url = text("short_url").ok_or_else(|| anyhow!("short_url is required"))?;
+            let period = match text("period") {
+                Some(period) => cli::Period::from_str(&period, false).map_err(|_| anyhow!("Unknown period {}", period))?,
+                None => cli::Period::Last30,
+            };
+            serde_json::to_value(cli::stats_report(short_url, period, profile)?)?
+        }
+        _ => return Err(anyhow!("Unknown tool {}", name)),
+    };
+    Ok(serde_json::to_string_pretty(&value)?)
+}
@@ -0,0 +1,52 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Conventions checked before a link is submitted. Empty or zero fields are
+/// not enforced.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct NamingRules {
+    /// Regex the custom path (without the profile's prefix) must match.
+    #[serde(default)]
+    pub path_pattern: String,
+    #[serde(default)]
+    pub max_path_length: usize,
+    /// Regex at least one tag must match, e.g. `^campaign-`.
+    #[serde(default)]
+    pub required_tag_pattern: String,
+}
+
+impl NamingRules {
+    /// Why `path` breaks the rules, if it does. An empty path lets short.io
+    /// pick one and is always allowed.
+    pub fn check_path(&self, path: &str) -> Option<String> {
+        if path.is_empty() {
+            return None;
+        }
+        if self.max_path_length > 0 && path.chars().count() > self.max_path_length {
+            return Some(format!("Path must be at most {} characters", self.max_path_length));
+        }
+        match compile(&self.path_pattern)? {
+            Ok(pattern) if !pattern.is_match(path) => Some(format!("Path must match {}", self.path_pattern)),
+            Ok(_) => None,
+            Err(e) => Some(e),
+        }
+    }
+
+    pub fn check_tags(&self, tags: &[String]) -> Option<String> {
+        match compile(&self.required_tag_pattern)? {
+            Ok(pattern) if !tags.iter().any(|tag| pattern.is_match(tag)) => {
+                Some(format!("Add a tag matching {}", self.required_tag_pattern))
+            }
+            Ok(_) => None,
+            Err(e) => Some(e),
+        }
+    }
+}
+
+/// `None` when the pattern is unset.
+fn compile(pattern: &str) -> Option<Result<Regex, String>> {
+    if pattern.is_empty() {
+        return None;
+    }
+    Some(Regex::new(pattern).map_err(|e| format!("Naming policy has an invalid pattern {}: {}", pattern, e)))
+}
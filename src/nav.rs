@@ -0,0 +1,93 @@
+use crate::storage;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+const NAV_FILE: &str = "navigation.json";
+
+/// Panels that keep their place between visits and across restarts.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Panel {
+    Links,
+    History,
+    Jobs,
+    Drafts,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Saved {
+    open: Vec<Panel>,
+    scroll: HashMap<Panel, f32>,
+    #[serde(default)]
+    history_grid: bool,
+}
+
+/// Which panels are open and where each was scrolled to, so closing and
+/// reopening a panel, or the app, picks up where the user left off.
+#[derive(Default)]
+pub struct Navigation {
+    saved: Saved,
+    /// Panels whose scroll position has been applied since they opened.
+    restored: HashSet<Panel>,
+}
+
+impl Navigation {
+    pub fn load() -> Self {
+        Self {
+            saved: storage::load_json(NAV_FILE).unwrap_or_default(),
+            restored: HashSet::new(),
+        }
+    }
+
+    pub fn was_open(&self, panel: Panel) -> bool {
+        self.saved.open.contains(&panel)
+    }
+
+    /// Scrolls to where the panel was left the first time it's shown after
+    /// opening, then leaves scrolling to the user.
+    pub fn scroll_area(&mut self, panel: Panel, area: egui::ScrollArea) -> egui::ScrollArea {
+        match self.saved.scroll.get(&panel) {
+            Some(&offset) if self.restored.insert(panel) => area.vertical_scroll_offset(offset),
+            _ => area,
+        }
+    }
+
+    pub fn scrolled(&mut self, panel: Panel, offset: f32) {
+        self.restored.insert(panel);
+        self.saved.scroll.insert(panel, offset);
+    }
+
+    /// Tracks whether a panel is open; saves when that changes.
+    pub fn set_open(&mut self, panel: Panel, open: bool) {
+        if open == self.was_open(panel) {
+            return;
+        }
+        if open {
+            self.saved.open.push(panel);
+        } else {
+            self.saved.open.retain(|other| *other != panel);
+            self.restored.remove(&panel);
+        }
+        self.save();
+    }
+
+    /// Whether History shows cards instead of a list.
+    pub fn history_grid(&self) -> bool {
+        self.saved.history_grid
+    }
+
+    pub fn set_history_grid(&mut self, grid: bool) {
+        if grid != self.saved.history_grid {
+            self.saved.history_grid = grid;
+            // Offsets from one layout make no sense in the other
+            self.saved.scroll.remove(&Panel::History);
+            self.save();
+        }
+    }
+
+    pub fn save(&self) {
+        if let Err(e) = storage::save_json(NAV_FILE, &self.saved) {
+            elog!("Failed to save navigation state: {}", e);
+        }
+    }
+}
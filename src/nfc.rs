@@ -0,0 +1,78 @@
+/// Whether this build can talk to NFC readers at all.
+pub fn available() -> bool {
+    cfg!(feature = "nfc")
+}
+
+/// URI identifier codes from the NFC Forum URI record type definition,
+/// longest prefix first so `https://www.` wins over `https://`.
+#[cfg_attr(not(feature = "nfc"), allow(dead_code))]
+const URI_PREFIXES: [(&str, u8); 4] = [
+    ("https://www.", 0x02),
+    ("http://www.", 0x01),
+    ("https://", 0x04),
+    ("http://", 0x03),
+];
+
+/// First user memory page on Type 2 tags; pages are four bytes each.
+#[cfg_attr(not(feature = "nfc"), allow(dead_code))]
+const FIRST_PAGE: u8 = 4;
+
+/// `url` as a single NDEF URI record wrapped in a TLV block, padded to
+/// whole pages.
+#[cfg_attr(not(feature = "nfc"), allow(dead_code))]
+fn ndef_message(url: &str) -> anyhow::Result<Vec<u8>> {
+    let (code, rest) = URI_PREFIXES
+        .iter()
+        .find_map(|(prefix, code)| url.strip_prefix(prefix).map(|rest| (*code, rest)))
+        .unwrap_or((0x00, url));
+    let payload_len = rest.len() + 1;
+    anyhow::ensure!(payload_len < 250, "URL is too long for a tag");
+
+    // MB, ME and SR set, well-known type
+    let mut record = vec![0xD1, 0x01, payload_len as u8, b'U', code];
+    record.extend_from_slice(rest.as_bytes());
+
+    let mut message = vec![0x03, record.len() as u8];
+    message.extend(record);
+    message.push(0xFE);
+    while message.len() % 4 != 0 {
+        message.push(0x00);
+    }
+    Ok(message)
+}
+
+/// Writes `url` to the tag on the first connected PC/SC reader, such as an
+/// ACR122U. Only NTAG21x and other Type 2 tags are supported. Returns the
+/// reader's name.
+#[cfg(feature = "nfc")]
+pub fn write(url: &str) -> anyhow::Result<String> {
+    use anyhow::Context;
+
+    let message = ndef_message(url)?;
+    let context = pcsc::Context::establish(pcsc::Scope::User).context("PC/SC service is not running")?;
+    let mut names = [0; 2048];
+    let reader = context
+        .list_readers(&mut names)?
+        .next()
+        .context("No NFC reader connected")?
+        .to_owned();
+    let card = match context.connect(&reader, pcsc::ShareMode::Shared, pcsc::Protocols::ANY) {
+        Err(pcsc::Error::NoSmartcard | pcsc::Error::RemovedCard) => anyhow::bail!("Hold a tag on the reader"),
+        result => result?,
+    };
+
+    let mut response = [0; pcsc::MAX_BUFFER_SIZE];
+    for (i, page) in message.chunks(4).enumerate() {
+        // UPDATE BINARY pseudo-APDU; readers pass it on as a Type 2 WRITE
+        let mut apdu = vec![0xFF, 0xD6, 0x00, FIRST_PAGE + i as u8, 0x04];
+        apdu.extend_from_slice(page);
+        let reply = card.transmit(&apdu, &mut response)?;
+        anyhow::ensure!(reply.ends_with(&[0x90, 0x00]), "Tag refused the write; is it locked?");
+    }
+    Ok(reader.to_string_lossy().into_owned())
+}
+
+#[cfg(not(feature = "nfc"))]
+pub fn write(_url: &str) -> anyhow::Result<String> {
+    anyhow::bail!("This build has no NFC support")
+}
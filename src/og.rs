@@ -0,0 +1,140 @@
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
+use image::{Rgba, RgbaImage};
+use std::path::Path;
+use std::time::Duration;
+
+/// Size Facebook, LinkedIn and Slack all crop cards from.
+pub const WIDTH: u32 = 1200;
+pub const HEIGHT: u32 = 630;
+
+const TIMEOUT: Duration = Duration::from_secs(8);
+const MAX_BYTES: usize = 256 * 1024;
+const MARGIN: f32 = 80.0;
+
+/// Background colors for cards, picked by domain so each domain keeps one.
+const BACKGROUNDS: [[u8; 3]; 6] = [
+    [37, 99, 235],
+    [124, 58, 237],
+    [219, 39, 119],
+    [5, 150, 105],
+    [217, 119, 6],
+    [51, 65, 85],
+];
+
+/// Whether the page at `url` declares an `og:image` for share cards.
+pub async fn has_image(url: &str) -> anyhow::Result<bool> {
+    Ok(image_url(url).await?.is_some())
+}
+
+/// The absolute URL of the page's `og:image`, or `twitter:image` failing that.
+pub async fn image_url(url: &str) -> anyhow::Result<Option<String>> {
+    let mut response = reqwest::Client::new()
+        .get(url)
+        .timeout(TIMEOUT)
+        .header("user-agent", concat!("shortyio/", env!("CARGO_PKG_VERSION")))
+        .send()
+        .await?
+        .error_for_status()?;
+    let base = response.url().clone();
+    let mut head = String::new();
+    while let Some(chunk) = response.chunk().await? {
+        head.push_str(&String::from_utf8_lossy(&chunk));
+        if head.len() >= MAX_BYTES || head.to_ascii_lowercase().contains("</head") {
+            break;
+        }
+    }
+    let meta = regex::Regex::new(r#"(?is)<meta\s[^>]*(?:property|name)\s*=\s*["'](og:image|twitter:image)["'][^>]*>"#)?;
+    let content = regex::Regex::new(r#"(?is)\scontent\s*=\s*["']([^"']+)["']"#)?;
+    let mut found: Vec<(bool, String)> = meta
+        .captures_iter(&head)
+        .filter_map(|tag| {
+            let is_og = tag[1].eq_ignore_ascii_case("og:image");
+            let src = content.captures(&tag[0])?[1].trim().to_string();
+            Some((is_og, src))
+        })
+        .collect();
+    found.sort_by_key(|(is_og, _)| !is_og);
+    Ok(found
+        .into_iter()
+        .find_map(|(_, src)| base.join(&src).ok())
+        .map(|url| url.to_string()))
+}
+
+/// A share card with the title over a background colored by `domain`, and
+/// the domain's logo and name along the bottom.
+pub fn render(title: &str, domain: &str, logo: Option<&Path>) -> anyhow::Result<RgbaImage> {
+    let fonts = eframe::egui::FontDefinitions::default();
+    let regular = fonts.font_data.get("Ubuntu-Light").ok_or_else(|| anyhow::anyhow!("Built-in font is missing"))?;
+    let font = FontRef::try_from_slice(&regular.font)?;
+
+    let hash = domain.bytes().fold(0usize, |hash, b| hash.wrapping_mul(31).wrapping_add(usize::from(b)));
+    let [r, g, b] = BACKGROUNDS[hash % BACKGROUNDS.len()];
+    let mut card = RgbaImage::from_pixel(WIDTH, HEIGHT, Rgba([r, g, b, 255]));
+
+    let scale = PxScale::from(72.0);
+    let line_height = font.as_scaled(scale).height() * 1.15;
+    let mut y = MARGIN;
+    for line in wrap(&font, scale, title, WIDTH as f32 - 2.0 * MARGIN).into_iter().take(4) {
+        draw_text(&mut card, &font, scale, &line, MARGIN, y);
+        y += line_height;
+    }
+
+    let footer_y = HEIGHT as f32 - MARGIN - 48.0;
+    let mut footer_x = MARGIN;
+    if let Some(logo) = logo.and_then(|path| image::open(path).ok()) {
+        let logo = logo.resize(48, 48, image::imageops::FilterType::Lanczos3).to_rgba8();
+        image::imageops::overlay(&mut card, &logo, footer_x as i64, footer_y as i64);
+        footer_x += 64.0;
+    }
+    draw_text(&mut card, &font, PxScale::from(40.0), domain, footer_x, footer_y + 2.0);
+    Ok(card)
+}
+
+/// Splits `text` into lines no wider than `max_width` at word boundaries.
+fn wrap(font: &FontRef, scale: PxScale, text: &str, max_width: f32) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+        if !line.is_empty() && text_width(font, scale, &candidate) > max_width {
+            lines.push(std::mem::replace(&mut line, word.to_string()));
+        } else {
+            line = candidate;
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+fn text_width(font: &FontRef, scale: PxScale, text: &str) -> f32 {
+    let font = font.as_scaled(scale);
+    text.chars().map(|c| font.h_advance(font.glyph_id(c))).sum()
+}
+
+/// Draws white `text` with its top-left corner at `x`, `y`.
+fn draw_text(card: &mut RgbaImage, font: &FontRef, scale: PxScale, text: &str, x: f32, y: f32) {
+    let scaled = font.as_scaled(scale);
+    let mut caret = x;
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        let glyph = id.with_scale_and_position(scale, ab_glyph::point(caret, y + scaled.ascent()));
+        caret += scaled.h_advance(id);
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            let (px, py) = (bounds.min.x as i32 + gx as i32, bounds.min.y as i32 + gy as i32);
+            if px < 0 || py < 0 || px >= WIDTH as i32 || py >= HEIGHT as i32 {
+                return;
+            }
+            let pixel = card.get_pixel_mut(px as u32, py as u32);
+            for channel in 0..3 {
+                let blended = f32::from(pixel[channel]) * (1.0 - coverage) + 255.0 * coverage;
+                pixel[channel] = blended as u8;
+            }
+        });
+    }
+}
@@ -0,0 +1,47 @@
+/// Sections of the Advanced Options panel.
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum AdvancedTab {
+    #[default]
+    Campaign,
+    Access,
+    Behavior,
+    Schedule,
+}
+
+impl AdvancedTab {
+    pub const ALL: [AdvancedTab; 4] = [Self::Campaign, Self::Access, Self::Behavior, Self::Schedule];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Campaign => "Campaign",
+            Self::Access => "Access",
+            Self::Behavior => "Behavior",
+            Self::Schedule => "Schedule",
+        }
+    }
+}
+
+/// An advanced option that differs from its default, shown as a removable
+/// chip above the Create button.
+#[derive(Clone, Copy)]
+pub enum OptionChip {
+    Utm,
+    Cloaking,
+    Password,
+    ClicksLimit,
+    Expires,
+    Redirect,
+    Schedule,
+}
+
+impl OptionChip {
+    /// The tab the option is set on.
+    pub fn tab(self) -> AdvancedTab {
+        match self {
+            Self::Utm => AdvancedTab::Campaign,
+            Self::Cloaking | Self::Password => AdvancedTab::Access,
+            Self::ClicksLimit | Self::Expires | Self::Redirect => AdvancedTab::Behavior,
+            Self::Schedule => AdvancedTab::Schedule,
+        }
+    }
+}
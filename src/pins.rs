@@ -0,0 +1,92 @@
+use crate::presets::UtmTemplate;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+
+const PINS_FILE: &str = "pins.json";
+const SIGNATURE_FILE: &str = "signature.html";
+
+/// A link kept at hand, e.g. for the email signature.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PinnedLink {
+    pub link_id: String,
+    pub short_url: String,
+    pub original_url: String,
+    /// Link text in the signature.
+    #[serde(default)]
+    pub label: String,
+    /// Added to the short URL in the signature; short.io passes them on.
+    #[serde(default)]
+    pub utm: UtmTemplate,
+}
+
+#[derive(Default)]
+pub struct Pins {
+    pub items: Vec<PinnedLink>,
+}
+
+impl Pins {
+    pub fn load() -> Self {
+        Self {
+            items: storage::load_json(PINS_FILE).unwrap_or_default(),
+        }
+    }
+
+    pub fn contains(&self, link_id: &str) -> bool {
+        self.items.iter().any(|pin| pin.link_id == link_id)
+    }
+
+    pub fn toggle(&mut self, pin: PinnedLink) {
+        if self.contains(&pin.link_id) {
+            self.items.retain(|existing| existing.link_id != pin.link_id);
+        } else {
+            self.items.push(pin);
+        }
+        self.save();
+    }
+
+    /// Saves the pins and regenerates `signature.html` next to them.
+    pub fn save(&self) {
+        if let Err(e) = storage::save_json(PINS_FILE, &self.items) {
+            elog!("Failed to save pinned links: {}", e);
+        }
+        let Some(path) = signature_path() else {
+            return;
+        };
+        if let Err(e) = std::fs::write(path, self.signature_html()) {
+            elog!("Failed to write email signature: {}", e);
+        }
+    }
+
+    /// A single line of links, styled inline since most mail clients drop
+    /// stylesheets.
+    pub fn signature_html(&self) -> String {
+        let links: Vec<String> = self
+            .items
+            .iter()
+            .map(|pin| {
+                let label = if pin.label.trim().is_empty() { &pin.short_url } else { &pin.label };
+                format!(
+                    "<a href=\"{}\" style=\"color:#1a73e8;text-decoration:none;\">{}</a>",
+                    escape(&pin.utm.apply(&pin.short_url)),
+                    escape(label.trim())
+                )
+            })
+            .collect();
+        format!(
+            "<p style=\"font-family:Arial,sans-serif;font-size:13px;margin:0;\">{}</p>\n",
+            links.join(" &middot; ")
+        )
+    }
+}
+
+/// Where the generated snippet is kept, for mail clients that can load it.
+pub fn signature_path() -> Option<std::path::PathBuf> {
+    storage::data_path(SIGNATURE_FILE)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
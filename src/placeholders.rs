@@ -0,0 +1,167 @@
+use crate::presets::UtmTemplate;
+use crate::{storage, tz, urls, vanity};
+use std::collections::HashMap;
+
+const COUNTERS_FILE: &str = "counters.json";
+
+/// Placeholders that custom paths and UTM template fields may contain.
+pub const NAMES: &[&str] = &["date", "counter", "domain", "title-slug"];
+/// Longest `{title-slug}`, so a long page title can't swamp the path.
+const TITLE_SLUG_LEN: usize = 24;
+
+/// What each placeholder expands to for one link.
+pub struct Values {
+    date: String,
+    counter: u64,
+    domain: String,
+    title_slug: String,
+}
+
+impl Values {
+    /// Values for a link to `url`. Without a page title `{title-slug}` falls
+    /// back to the site name.
+    pub fn new(url: &str, counter: u64, title: Option<&str>) -> Self {
+        let host = urls::host(url);
+        let domain = host.trim_start_matches("www.").to_string();
+        let site = domain.split('.').next().unwrap_or_default();
+        Self {
+            date: tz::today().format("%Y-%m-%d").to_string(),
+            counter,
+            title_slug: vanity::kebab(title.unwrap_or(site), TITLE_SLUG_LEN),
+            domain,
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<String> {
+        match name {
+            "date" => Some(self.date.clone()),
+            "counter" => Some(self.counter.to_string()),
+            "domain" => Some(self.domain.clone()),
+            "title-slug" => Some(self.title_slug.clone()),
+            _ => None,
+        }
+    }
+
+    /// `text` with every known placeholder replaced.
+    pub fn expand(&self, text: &str) -> String {
+        replace(text, |name| self.get(name))
+    }
+
+    /// `template` with its fields expanded, ready to apply to a destination.
+    pub fn utm(&self, template: &UtmTemplate) -> UtmTemplate {
+        UtmTemplate {
+            name: template.name.clone(),
+            source: self.expand(&template.source),
+            medium: self.expand(&template.medium),
+            campaign: self.expand(&template.campaign),
+        }
+    }
+}
+
+/// Whether `text` contains `{name}`.
+pub fn uses(text: &str, name: &str) -> bool {
+    text.contains(&format!("{{{}}}", name))
+}
+
+/// Whether any of the template's fields contain `{name}`.
+pub fn template_uses(template: &UtmTemplate, name: &str) -> bool {
+    [&template.source, &template.medium, &template.campaign].iter().any(|field| uses(field, name))
+}
+
+/// `text` with placeholders replaced by typical values, so a path can be
+/// validated before the real values are known.
+pub fn sample(text: &str) -> String {
+    let values = Values {
+        date: "2024-01-31".to_string(),
+        counter: 1,
+        domain: "example.com".to_string(),
+        title_slug: "page-title".to_string(),
+    };
+    values.expand(text)
+}
+
+/// The first `{…}` in `text` that isn't a known placeholder.
+pub fn unknown(text: &str) -> Option<String> {
+    let mut found = None;
+    replace(text, |name| {
+        if found.is_none() && !NAMES.contains(&name) && !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '-') {
+            found = Some(format!("{{{}}}", name));
+        }
+        None
+    });
+    found
+}
+
+/// The next `{counter}` value of each template, kept per profile so
+/// sequences survive restarts and profiles don't share one.
+#[derive(Default)]
+pub struct Counters {
+    /// Profile name → template key → next value.
+    next: HashMap<String, HashMap<String, u64>>,
+}
+
+impl Counters {
+    pub fn load() -> Self {
+        Self {
+            next: storage::load_json(COUNTERS_FILE).unwrap_or_default(),
+        }
+    }
+
+    /// The value the template will use next; counters start at 1.
+    pub fn peek(&self, profile: &str, key: &str) -> u64 {
+        self.next.get(profile).and_then(|keys| keys.get(key)).copied().unwrap_or(1)
+    }
+
+    /// Uses up the next value.
+    pub fn take(&mut self, profile: &str, key: &str) -> u64 {
+        let value = self.peek(profile, key);
+        self.set(profile, key, value + 1);
+        value
+    }
+
+    pub fn set(&mut self, profile: &str, key: &str, value: u64) {
+        self.next.entry(profile.to_string()).or_default().insert(key.to_string(), value.max(1));
+        self.persist();
+    }
+
+    /// Starts the template's sequence over from 1.
+    pub fn reset(&mut self, profile: &str, key: &str) {
+        if let Some(keys) = self.next.get_mut(profile) {
+            keys.remove(key);
+            if keys.is_empty() {
+                self.next.remove(profile);
+            }
+        }
+        self.persist();
+    }
+
+    fn persist(&self) {
+        if let Err(e) = storage::save_json(COUNTERS_FILE, &self.next) {
+            elog!("Failed to save counters: {}", e);
+        }
+    }
+}
+
+/// `text` with each `{name}` that `value` knows replaced; anything else is
+/// left as it is.
+fn replace(text: &str, mut value: impl FnMut(&str) -> Option<String>) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find('{') {
+        expanded.push_str(&rest[..open]);
+        rest = &rest[open..];
+        let replacement = rest.find('}').and_then(|close| value(&rest[1..close]).map(|value| (close, value)));
+        match replacement {
+            Some((close, value)) => {
+                expanded.push_str(&value);
+                rest = &rest[close + 1..];
+            }
+            None => {
+                expanded.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
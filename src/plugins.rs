@@ -0,0 +1,150 @@
+use crate::api::LinkResponse;
+use crate::storage;
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+const PLUGIN_DIR: &str = "plugins";
+
+/// A result panel button offered by a plugin.
+#[derive(Clone)]
+pub struct Action {
+    pub plugin: PathBuf,
+    pub id: String,
+    pub label: String,
+    pub hint: String,
+}
+
+#[derive(Deserialize)]
+struct Description {
+    #[serde(default)]
+    actions: Vec<ActionSpec>,
+}
+
+#[derive(Deserialize)]
+struct ActionSpec {
+    id: String,
+    label: String,
+    #[serde(default)]
+    hint: String,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Request<'a> {
+    Describe,
+    Run { action: &'a str, link: Link<'a> },
+}
+
+#[derive(Serialize)]
+struct Link<'a> {
+    id: &'a str,
+    short_url: &'a str,
+    original_url: &'a str,
+    tags: &'a [String],
+    title: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct Reply {
+    ok: bool,
+    #[serde(default)]
+    message: String,
+}
+
+pub fn dir() -> Option<PathBuf> {
+    storage::config_dir().map(|dir| dir.join(PLUGIN_DIR))
+}
+
+/// The plugins folder, created if it doesn't exist yet.
+pub fn ensure_dir() -> Result<PathBuf> {
+    let dir = dir().ok_or_else(|| anyhow!("Cannot determine config path"))?;
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Asks every program in the plugins folder which actions it offers. Each
+/// plugin is a separate process that reads one JSON request from stdin and
+/// answers with one JSON line on stdout, so it can be written in anything.
+/// Plugins that fail to answer are logged and skipped.
+pub fn discover() -> Vec<Action> {
+    let Some(entries) = dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut plugins: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| is_executable(path))
+        .collect();
+    plugins.sort();
+
+    let mut actions = Vec::new();
+    for plugin in plugins {
+        let description = exchange(&plugin, &Request::Describe)
+            .and_then(|line| serde_json::from_str::<Description>(&line).context("Bad describe reply"));
+        match description {
+            Ok(description) => actions.extend(description.actions.into_iter().map(|spec| Action {
+                plugin: plugin.clone(),
+                id: spec.id,
+                label: spec.label,
+                hint: spec.hint,
+            })),
+            Err(e) => elog!("Plugin {} skipped: {:#}", plugin.display(), e),
+        }
+    }
+    actions
+}
+
+/// Runs an action on a link and returns the plugin's message.
+pub fn run(action: &Action, link: &LinkResponse, title: Option<&str>) -> Result<String> {
+    let request = Request::Run {
+        action: &action.id,
+        link: Link {
+            id: &link.id,
+            short_url: &link.short_url,
+            original_url: &link.original_url,
+            tags: &link.tags,
+            title,
+        },
+    };
+    let line = exchange(&action.plugin, &request)?;
+    let reply: Reply = serde_json::from_str(&line).context("Bad reply")?;
+    if !reply.ok {
+        bail!("{}", if reply.message.is_empty() { "Failed" } else { &reply.message });
+    }
+    Ok(reply.message)
+}
+
+/// Starts the plugin, writes the request and reads the first line it prints.
+fn exchange(plugin: &Path, request: &Request) -> Result<String> {
+    let mut child = Command::new(plugin)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .with_context(|| format!("Failed to start {}", plugin.display()))?;
+    {
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        writeln!(stdin, "{}", serde_json::to_string(request)?)?;
+    }
+    let mut line = String::new();
+    BufReader::new(child.stdout.take().expect("stdout is piped")).read_line(&mut line)?;
+    let status = child.wait()?;
+    if line.trim().is_empty() {
+        bail!("No reply ({})", status);
+    }
+    Ok(line)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("exe") || ext.eq_ignore_ascii_case("bat") || ext.eq_ignore_ascii_case("cmd"))
+}
@@ -0,0 +1,135 @@
+use crate::urls::host;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+/// A destination the app refuses to shorten. `pattern` is a domain, which
+/// also covers its subdomains, or a URL prefix such as `example.com/promo/`.
+/// Either may use `*` as a wildcard.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(from = "RuleRepr")]
+pub struct BlockRule {
+    pub pattern: String,
+    pub reason: String,
+}
+
+/// Team presets may list bare patterns or patterns with a reason.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RuleRepr {
+    Pattern(String),
+    Rule {
+        pattern: String,
+        #[serde(default)]
+        reason: String,
+    },
+}
+
+impl From<RuleRepr> for BlockRule {
+    fn from(repr: RuleRepr) -> Self {
+        match repr {
+            RuleRepr::Pattern(pattern) => Self {
+                pattern,
+                reason: String::new(),
+            },
+            RuleRepr::Rule { pattern, reason } => Self { pattern, reason },
+        }
+    }
+}
+
+impl BlockRule {
+    pub fn matches(&self, url: &str) -> bool {
+        let pattern = self.pattern.trim().to_lowercase();
+        if pattern.is_empty() {
+            return false;
+        }
+        let url = url.trim().to_lowercase();
+        let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(&url);
+        if pattern.contains('/') {
+            let rest = rest.strip_prefix("www.").unwrap_or(rest);
+            let pattern = pattern.split_once("://").map(|(_, rest)| rest).unwrap_or(&pattern);
+            return glob(&format!("{}*", pattern.trim_start_matches("www.")), rest);
+        }
+        let host = host(&url);
+        let domain = pattern.trim_start_matches("*.");
+        glob(domain, &host) || glob(&format!("*.{}", domain), &host)
+    }
+
+    /// What the user is told when a URL is refused.
+    pub fn message(&self) -> String {
+        if self.reason.trim().is_empty() {
+            format!("Destinations matching {} are blocked by policy", self.pattern.trim())
+        } else {
+            format!("Blocked by policy: {}", self.reason.trim())
+        }
+    }
+}
+
+/// Rules as editable `pattern # reason` lines.
+#[cfg(feature = "gui")]
+pub fn rules_text(rules: &[BlockRule]) -> String {
+    rules
+        .iter()
+        .map(|rule| {
+            if rule.reason.is_empty() {
+                format!("{}\n", rule.pattern)
+            } else {
+                format!("{} # {}\n", rule.pattern, rule.reason)
+            }
+        })
+        .collect()
+}
+
+#[cfg(feature = "gui")]
+pub fn parse_rules(text: &str) -> Vec<BlockRule> {
+    text.lines()
+        .map(|line| match line.split_once('#') {
+            Some((pattern, reason)) => (pattern.trim(), reason.trim()),
+            None => (line.trim(), ""),
+        })
+        .filter(|(pattern, _)| !pattern.is_empty())
+        .map(|(pattern, reason)| BlockRule {
+            pattern: pattern.to_string(),
+            reason: reason.to_string(),
+        })
+        .collect()
+}
+
+/// The user's own rules and the ones from the team presets, checked before
+/// every link is created or repointed.
+static LOCAL: RwLock<Vec<BlockRule>> = RwLock::new(Vec::new());
+static TEAM: RwLock<Vec<BlockRule>> = RwLock::new(Vec::new());
+
+pub fn set_local(rules: Vec<BlockRule>) {
+    *LOCAL.write().unwrap() = rules;
+}
+
+pub fn set_team(rules: Vec<BlockRule>) {
+    *TEAM.write().unwrap() = rules;
+}
+
+/// The first rule that blocks `url`, if any.
+pub fn check(url: &str) -> Option<BlockRule> {
+    let local = LOCAL.read().unwrap();
+    let team = TEAM.read().unwrap();
+    local.iter().chain(team.iter()).find(|rule| rule.matches(url)).cloned()
+}
+
+/// Matches `text` against `pattern`, where `*` stands for any run of characters.
+fn glob(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
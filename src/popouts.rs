@@ -0,0 +1,55 @@
+use chrono::NaiveDate;
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PopoutKind {
+    Qr,
+    Stats,
+}
+
+/// A QR code or click chart shown in its own OS window, e.g. on a second
+/// monitor during an event.
+pub struct Popout {
+    pub kind: PopoutKind,
+    pub link_id: String,
+    pub short_url: String,
+    /// Clicks per day, once loaded. Only used by stats windows.
+    pub daily: Option<Result<Vec<(NaiveDate, u64)>, String>>,
+    /// Printed above the QR code on a poster.
+    pub poster_title: String,
+    pub poster_logo: Option<PathBuf>,
+    /// Color of the band and title on the poster.
+    pub poster_accent: Option<[u8; 3]>,
+    pub poster_status: Option<String>,
+    /// Another link overlaid on the click chart for an A/B comparison.
+    pub compare: Option<Comparison>,
+}
+
+pub struct Comparison {
+    pub link_id: String,
+    pub short_url: String,
+    pub daily: Option<Result<Vec<(NaiveDate, u64)>, String>>,
+}
+
+impl Popout {
+    pub fn new(kind: PopoutKind, link_id: &str, short_url: &str) -> Self {
+        Self {
+            kind,
+            link_id: link_id.to_string(),
+            short_url: short_url.to_string(),
+            daily: None,
+            poster_title: String::new(),
+            poster_logo: None,
+            poster_accent: None,
+            poster_status: None,
+            compare: None,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match self.kind {
+            PopoutKind::Qr => format!("QR · {}", self.short_url),
+            PopoutKind::Stats => format!("Clicks · {}", self.short_url),
+        }
+    }
+}
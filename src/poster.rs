@@ -0,0 +1,197 @@
+use crate::qr;
+use crate::slugs;
+use anyhow::Result;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// A4 in points.
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const QR_SIZE: f32 = 400.0;
+const LOGO_HEIGHT: f32 = 100.0;
+/// Height of the strip of brand color across the top.
+const ACCENT_BAND: f32 = 24.0;
+/// Helvetica averages a bit over half an em per character, which is close
+/// enough for centering a line.
+const AVERAGE_CHAR_WIDTH: f32 = 0.55;
+/// Pixels per QR module in PNG exports.
+const PNG_MODULE_PIXELS: u32 = 12;
+
+/// Writes a one-page A4 PDF with the QR code, the short URL in large type
+/// and an optional title, logo and brand color, ready to print and pin to a
+/// wall.
+pub fn write(path: &Path, short_url: &str, title: &str, logo: Option<&Path>, accent: Option<[u8; 3]>) -> Result<()> {
+    let logo = logo.map(image::open).transpose()?.map(|logo| {
+        // Print resolution for the logo's height on the page, about 300 dpi
+        let logo = logo.resize(2000, (LOGO_HEIGHT * 300.0 / 72.0) as u32, image::imageops::FilterType::Lanczos3);
+        logo.to_rgb8()
+    });
+    let page = page(short_url, title, logo.as_ref().map(|logo| logo.width() as f32 / logo.height() as f32), accent)?;
+    write_pdf(path, &[page], logo.as_ref())
+}
+
+/// Writes a PDF with one labeled QR code per page, e.g. for table tents.
+pub fn write_sheets(path: &Path, links: &[(String, String)]) -> Result<()> {
+    let pages = links
+        .iter()
+        .map(|(short_url, label)| page(short_url, label, None, None))
+        .collect::<Result<Vec<_>>>()?;
+    write_pdf(path, &pages, None)
+}
+
+/// Writes `<label>.png` into `dir` for each link, one QR code per file.
+pub fn write_pngs(dir: &Path, links: &[(String, String)]) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    let mut used = HashSet::new();
+    for (short_url, label) in links {
+        let image = qr::image(short_url, PNG_MODULE_PIXELS)?;
+        let base = file_name(label);
+        let mut name = base.clone();
+        for n in 2.. {
+            if used.insert(name.clone()) {
+                break;
+            }
+            name = format!("{}-{}", base, n);
+        }
+        image.save(dir.join(format!("{}.png", name)))?;
+    }
+    Ok(())
+}
+
+/// Content stream of one poster page. `logo_aspect` is the logo's width
+/// over height when the page has one.
+fn page(short_url: &str, title: &str, logo_aspect: Option<f32>, accent: Option<[u8; 3]>) -> Result<String> {
+    let (width, dark) = qr::matrix(short_url)?;
+
+    let mut content = String::new();
+    // Fill color operands for the accent, black without one
+    let color = accent.map(|[r, g, b]| format!("{:.3} {:.3} {:.3} rg", r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0));
+    if let Some(color) = &color {
+        let _ = writeln!(content, "{} 0 {} {} {} re f", color, PAGE_HEIGHT - ACCENT_BAND, PAGE_WIDTH, ACCENT_BAND);
+    }
+    let mut y = PAGE_HEIGHT - 60.0;
+    if let Some(aspect) = logo_aspect {
+        let height = LOGO_HEIGHT;
+        let width = height * aspect;
+        y -= height;
+        let _ = writeln!(content, "q {} 0 0 {} {} {} cm /Logo Do Q", width, height, (PAGE_WIDTH - width) / 2.0, y);
+        y -= 30.0;
+    }
+    if !title.trim().is_empty() {
+        y -= 36.0;
+        content.push_str(color.as_deref().unwrap_or("0 g"));
+        content.push('\n');
+        centered_text(&mut content, "F2", 36.0, y, title.trim());
+        y -= 30.0;
+    }
+
+    let module = QR_SIZE / (width + 2 * qr::QUIET_ZONE) as f32;
+    let left = (PAGE_WIDTH - QR_SIZE) / 2.0;
+    let top = y;
+    content.push_str("0 g\n");
+    for (i, _) in dark.iter().enumerate().filter(|(_, dark)| **dark) {
+        let x = left + (i % width + qr::QUIET_ZONE) as f32 * module;
+        let y = top - (i / width + qr::QUIET_ZONE + 1) as f32 * module;
+        let _ = writeln!(content, "{:.2} {:.2} {:.2} {:.2} re", x, y, module, module);
+    }
+    content.push_str("f\n");
+
+    centered_text(&mut content, "F1", 28.0, top - QR_SIZE - 30.0, &display_url(short_url));
+    Ok(content)
+}
+
+fn write_pdf(path: &Path, pages: &[String], logo: Option<&image::RgbImage>) -> Result<()> {
+    // Catalog, page tree, two fonts and the optional logo come first; each
+    // page then takes a page object and a content stream.
+    let first_page = 6;
+    let kids: Vec<String> = (0..pages.len()).map(|i| format!("{} 0 R", first_page + 2 * i)).collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".as_bytes().to_vec(),
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()).into_bytes(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".as_bytes().to_vec(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".as_bytes().to_vec(),
+        match logo {
+            Some(logo) => stream(
+                &format!(
+                    "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8",
+                    logo.width(),
+                    logo.height()
+                ),
+                logo.as_raw(),
+            ),
+            None => b"null".to_vec(),
+        },
+    ];
+    for (i, content) in pages.iter().enumerate() {
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >>{} >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                if logo.is_some() { " /XObject << /Logo 5 0 R >>" } else { "" },
+                first_page + 2 * i + 1
+            )
+            .into_bytes(),
+        );
+        objects.push(stream("", &latin1(content)));
+    }
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+        pdf.extend_from_slice(object);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+    let xref = pdf.len();
+    pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref).as_bytes(),
+    );
+    fs::write(path, pdf)?;
+    Ok(())
+}
+
+/// A label safe to use as a file name on every platform.
+fn file_name(label: &str) -> String {
+    let name: String = label
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect();
+    if name.trim_matches('_').is_empty() { "qr".to_string() } else { name }
+}
+
+/// The short URL as printed: no scheme, and emoji paths percent-encoded
+/// since the built-in PDF fonts can't draw them.
+fn display_url(short_url: &str) -> String {
+    let rest = short_url.split_once("://").map(|(_, rest)| rest).unwrap_or(short_url);
+    match rest.split_once('/') {
+        Some((host, path)) => format!("{}/{}", host, slugs::encode(path)),
+        None => rest.to_string(),
+    }
+}
+
+fn centered_text(content: &mut String, font: &str, size: f32, y: f32, text: &str) {
+    let width = text.chars().count() as f32 * size * AVERAGE_CHAR_WIDTH;
+    let x = ((PAGE_WIDTH - width) / 2.0).max(36.0);
+    let escaped = text.replace('\\', "\\\\").replace('(', "\\(").replace(')', "\\)");
+    let _ = writeln!(content, "BT /{} {} Tf {:.2} {:.2} Td ({}) Tj ET", font, size, x, y, escaped);
+}
+
+/// WinAnsi bytes for the built-in fonts; characters outside Latin-1 become `?`.
+fn latin1(text: &str) -> Vec<u8> {
+    text.chars().map(|c| u8::try_from(u32::from(c)).unwrap_or(b'?')).collect()
+}
+
+fn stream(dict: &str, data: &[u8]) -> Vec<u8> {
+    let mut object = format!("<< {} /Length {} >>\nstream\n", dict, data.len()).into_bytes();
+    object.extend_from_slice(data);
+    object.extend_from_slice(b"\nendstream");
+    object
+}
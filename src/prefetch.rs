@@ -0,0 +1,115 @@
+use crate::api::ApiClient;
+use chrono::NaiveDate;
+use eframe::egui;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Condvar, Mutex};
+
+const WORKERS: usize = 3;
+const MAX_QUEUED: usize = 60;
+
+#[derive(Default)]
+struct State {
+    /// Newest requests first, so rows on screen now beat ones scrolled past.
+    queue: VecDeque<(String, String)>,
+    /// Total clicks per link id; `None` while in flight or after a failure.
+    clicks: HashMap<String, Option<u64>>,
+    /// Clicks per day for the same links, from the same responses.
+    daily: HashMap<String, Vec<(NaiveDate, u64)>>,
+    started: bool,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    ready: Condvar,
+}
+
+/// Fetches click counts for links as they scroll into view, using a small
+/// fixed pool of worker threads so the API isn't flooded.
+#[derive(Clone, Default)]
+pub struct StatsPrefetcher {
+    shared: Arc<Shared>,
+}
+
+impl StatsPrefetcher {
+    pub fn clicks(&self, link_id: &str) -> Option<u64> {
+        self.shared.state.lock().unwrap().clicks.get(link_id).copied().flatten()
+    }
+
+    /// Clicks per day for one link, once fetched.
+    pub fn daily(&self, link_id: &str) -> Option<Vec<(NaiveDate, u64)>> {
+        self.shared.state.lock().unwrap().daily.get(link_id).cloned()
+    }
+
+    /// Clicks per day summed over every link fetched so far, and how many
+    /// links that covers.
+    pub fn daily_totals(&self) -> (HashMap<NaiveDate, u64>, usize) {
+        let state = self.shared.state.lock().unwrap();
+        let mut totals = HashMap::new();
+        for (day, clicks) in state.daily.values().flatten() {
+            *totals.entry(*day).or_default() += clicks;
+        }
+        (totals, state.daily.len())
+    }
+
+    /// Forgets fetched counts so links on screen are fetched again. Ones in
+    /// flight are left to finish.
+    pub fn refresh(&self) {
+        self.shared.state.lock().unwrap().clicks.retain(|_, clicks| clicks.is_none());
+    }
+
+    /// Queues a link unless it was already fetched or requested.
+    pub fn request(&self, api_key: &str, link_id: &str, ctx: &egui::Context) {
+        if api_key.is_empty() || link_id.is_empty() {
+            return;
+        }
+        let mut state = self.shared.state.lock().unwrap();
+        if state.clicks.contains_key(link_id) {
+            return;
+        }
+        state.clicks.insert(link_id.to_string(), None);
+        state.queue.push_front((api_key.to_string(), link_id.to_string()));
+        if state.queue.len() > MAX_QUEUED {
+            // Forget the oldest so it is requested again if it comes back into view
+            if let Some((_, dropped)) = state.queue.pop_back() {
+                state.clicks.remove(&dropped);
+            }
+        }
+        if !state.started {
+            state.started = true;
+            for _ in 0..WORKERS {
+                self.spawn_worker(ctx.clone());
+            }
+        }
+        self.shared.ready.notify_one();
+    }
+
+    fn spawn_worker(&self, ctx: egui::Context) {
+        let shared = self.shared.clone();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            loop {
+                let (api_key, link_id) = {
+                    let mut state = shared.state.lock().unwrap();
+                    loop {
+                        if let Some(next) = state.queue.pop_front() {
+                            break next;
+                        }
+                        state = shared.ready.wait(state).unwrap();
+                    }
+                };
+
+                let client = ApiClient::new(api_key);
+                match runtime.block_on(client.link_stats(&link_id)) {
+                    Ok(stats) => {
+                        let mut state = shared.state.lock().unwrap();
+                        state.daily.insert(link_id.clone(), stats.daily());
+                        state.clicks.insert(link_id, Some(stats.total_clicks));
+                        ctx.request_repaint();
+                    }
+                    Err(e) => elog!("Failed to fetch stats for {}: {}", link_id, e),
+                }
+            }
+        });
+    }
+}
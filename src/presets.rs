@@ -0,0 +1,197 @@
+use crate::naming::NamingRules;
+use crate::policy::BlockRule;
+#[cfg(feature = "gui")]
+use crate::policy;
+use crate::storage;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gui")]
+use std::sync::{Arc, Condvar, Mutex};
+#[cfg(feature = "gui")]
+use std::time::Duration;
+
+const PRESETS_FILE: &str = "team-presets.json";
+#[cfg(feature = "gui")]
+const REFRESH: Duration = Duration::from_secs(60 * 60);
+
+/// UTM parameters added to a destination; empty values are left out.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct UtmTemplate {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub source: String,
+    #[serde(default)]
+    pub medium: String,
+    #[serde(default)]
+    pub campaign: String,
+}
+
+impl UtmTemplate {
+    /// Adds the template's parameters to `url`, replacing any already there.
+    #[cfg(feature = "gui")]
+    pub fn apply(&self, url: &str) -> String {
+        let params = [
+            ("utm_source", &self.source),
+            ("utm_medium", &self.medium),
+            ("utm_campaign", &self.campaign),
+        ];
+        let params: Vec<(&str, &str)> = params
+            .iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(name, value)| (*name, value.as_str()))
+            .collect();
+        let Ok(mut parsed) = reqwest::Url::parse(url) else {
+            return url.to_string();
+        };
+        if params.is_empty() {
+            return url.to_string();
+        }
+        let kept: Vec<(String, String)> = parsed
+            .query_pairs()
+            .filter(|(name, _)| !params.iter().any(|(param, _)| &**name == *param))
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect();
+        parsed.query_pairs_mut().clear().extend_pairs(kept).extend_pairs(params);
+        parsed.to_string()
+    }
+}
+
+/// Organization-wide defaults published by an admin as a JSON file.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct TeamPresets {
+    /// Used when the profile has no domain of its own.
+    #[serde(default)]
+    pub domain: String,
+    /// Added to every link created in the app.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub utm_templates: Vec<UtmTemplate>,
+    /// Destinations nobody on the team may shorten, as bare patterns or
+    /// `{"pattern", "reason"}` objects.
+    #[serde(default)]
+    pub blocked_domains: Vec<BlockRule>,
+    #[serde(default)]
+    pub naming: NamingRules,
+}
+
+/// The last downloaded presets for `url`, for one-off runs such as the CLI.
+pub fn cached(url: &str) -> TeamPresets {
+    let cached: Cached = storage::load_json(PRESETS_FILE).unwrap_or_default();
+    if url.is_empty() || cached.url != url {
+        return TeamPresets::default();
+    }
+    cached.presets
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Cached {
+    url: String,
+    presets: TeamPresets,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Default)]
+struct State {
+    cached: Cached,
+    error: Option<String>,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+/// Keeps the active profile's team presets fresh, re-downloading them every
+/// hour or as soon as the URL changes. The last copy is kept on disk so the
+/// presets still apply when offline.
+#[cfg(feature = "gui")]
+#[derive(Clone, Default)]
+pub struct Presets {
+    shared: Arc<Shared>,
+}
+
+#[cfg(feature = "gui")]
+impl Presets {
+    pub fn start(url: &str) -> Self {
+        let presets = Self::default();
+        {
+            let mut state = presets.shared.state.lock().unwrap();
+            state.cached = storage::load_json(PRESETS_FILE).unwrap_or_default();
+            if state.cached.url != url {
+                state.cached = Cached {
+                    url: url.to_string(),
+                    ..Cached::default()
+                };
+            }
+            policy::set_team(state.cached.presets.blocked_domains.clone());
+        }
+
+        let shared = presets.shared.clone();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            loop {
+                let url = shared.state.lock().unwrap().cached.url.clone();
+                if !url.is_empty() {
+                    let result = runtime.block_on(fetch(&url));
+                    let mut state = shared.state.lock().unwrap();
+                    if state.cached.url == url {
+                        match result {
+                            Ok(presets) => {
+                                policy::set_team(presets.blocked_domains.clone());
+                                state.cached.presets = presets;
+                                state.cached.updated_at = Some(Utc::now());
+                                state.error = None;
+                                if let Err(e) = storage::save_json(PRESETS_FILE, &state.cached) {
+                                    elog!("Failed to save team presets: {}", e);
+                                }
+                            }
+                            Err(e) => state.error = Some(e.to_string()),
+                        }
+                    }
+                }
+                let state = shared.state.lock().unwrap();
+                let _ = shared
+                    .changed
+                    .wait_timeout_while(state, REFRESH, |state| state.cached.url == url)
+                    .unwrap();
+            }
+        });
+
+        presets
+    }
+
+    /// Switches to another URL, e.g. after a profile change, and fetches it now.
+    pub fn set_url(&self, url: &str) {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.cached.url != url {
+            state.cached = Cached {
+                url: url.to_string(),
+                ..Cached::default()
+            };
+            state.error = None;
+            policy::set_team(Vec::new());
+            self.shared.changed.notify_all();
+        }
+    }
+
+    pub fn current(&self) -> TeamPresets {
+        self.shared.state.lock().unwrap().cached.presets.clone()
+    }
+
+    /// When the presets were last downloaded, and the last error since.
+    pub fn status(&self) -> (Option<DateTime<Utc>>, Option<String>) {
+        let state = self.shared.state.lock().unwrap();
+        (state.cached.updated_at, state.error.clone())
+    }
+}
+
+#[cfg(feature = "gui")]
+async fn fetch(url: &str) -> anyhow::Result<TeamPresets> {
+    let response = reqwest::get(url).await?.error_for_status()?;
+    Ok(response.json().await?)
+}
@@ -0,0 +1,48 @@
+/// Words embarrassing anywhere in a path, even hidden inside an innocent
+/// one like `therapist`.
+const ANYWHERE: &[&str] = &[
+    "fuck", "shit", "cunt", "bitch", "whore", "slut", "rapist", "nazi", "pussy", "penis", "vagina", "porn", "bastard",
+    "wank", "twat", "nigg", "fag", "retard", "molest", "boob", "horny", "dildo", "jizz", "piss", "bollock",
+];
+
+/// Short words that only matter on their own; as substrings they'd flag
+/// `class`, `analytics`, `cocktail` and the like.
+const WHOLE_WORD: &[&str] = &[
+    "ass", "arse", "anal", "cock", "dick", "tits", "crap", "pedo", "cum", "sex", "hell", "damn", "poo", "fart", "butt",
+    "kkk", "wtf", "omfg", "stfu",
+];
+
+/// The word a path could be read as, if any, looking through leetspeak
+/// (`5h1t`) and separators (`sh-it`). Meant as a warning, not a filter:
+/// false alarms are expected and easy to ignore.
+pub fn check(path: &str) -> Option<String> {
+    let words: Vec<String> = path
+        .split(|c: char| !c.is_alphanumeric() && !"@$!|".contains(c))
+        .map(unleet)
+        .filter(|word| !word.is_empty())
+        .collect();
+    let joined: String = words.concat();
+    let found = ANYWHERE
+        .iter()
+        .find(|bad| joined.contains(*bad))
+        .or_else(|| WHOLE_WORD.iter().find(|bad| words.iter().any(|word| word == *bad)))?;
+    Some(format!("This path could be read as \"{}\"", found))
+}
+
+/// Lowercases and undoes common letter substitutions.
+fn unleet(word: &str) -> String {
+    word.to_lowercase()
+        .chars()
+        .map(|c| match c {
+            '0' => 'o',
+            '1' | '!' | '|' => 'i',
+            '3' => 'e',
+            '4' | '@' => 'a',
+            '5' | '$' => 's',
+            '7' => 't',
+            '8' => 'b',
+            '9' => 'g',
+            c => c,
+        })
+        .collect()
+}
@@ -0,0 +1,152 @@
+use crate::api::RequestOptions;
+#[cfg(feature = "gui")]
+use crate::redirects;
+#[cfg(feature = "gui")]
+use crate::reserved;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const ENV_API_KEY: &str = "SHORTYIO_API_KEY";
+pub const ENV_DOMAIN: &str = "SHORTYIO_DOMAIN";
+
+/// The value of an override variable, if set and not blank.
+pub fn env_override(name: &str) -> Option<String> {
+    std::env::var(name).ok().map(|value| value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+/// A named API key and domain pair. Switching profiles swaps the account
+/// links are created under.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Profile {
+    pub name: String,
+    #[serde(default)]
+    pub api_key: String,
+    #[serde(default)]
+    pub domain: String,
+    /// Restricted mode for shared machines: links can still be created, but
+    /// the API key, settings and edit/delete actions are locked away.
+    #[serde(default)]
+    pub kiosk: bool,
+    /// Optional PIN for unlocking settings in kiosk mode. It lives in the
+    /// config file next to the API key, so it only keeps out people who can't
+    /// read that file anyway.
+    #[serde(default)]
+    pub kiosk_pin: String,
+    /// Prepended to every custom path, e.g. `ev/` for event links.
+    #[serde(default)]
+    pub path_prefix: String,
+    /// Overrides the `shortyio/<version>` user agent when set.
+    #[serde(default)]
+    pub user_agent: String,
+    /// Sent with every API request, e.g. for a corporate gateway.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Extra CA certificate file, for corporate proxies.
+    #[serde(default)]
+    pub ca_cert: String,
+    /// SHA-256 fingerprint, or certificate file, of the only certificate
+    /// accepted for short.io.
+    #[serde(default)]
+    pub pinned_cert: String,
+    /// JSON file with the team's shared defaults, see `presets.rs`.
+    #[serde(default)]
+    pub presets_url: String,
+    /// Receives a JSON POST for every link created with this profile.
+    #[serde(default)]
+    pub webhook_url: String,
+    /// Redirect status new links start with, 301 when unset.
+    #[serde(default)]
+    pub default_redirect: Option<i32>,
+    /// Custom paths to warn about, `reserved::DEFAULTS` when unset.
+    #[serde(default)]
+    pub reserved_paths: Option<Vec<String>>,
+    /// Brand color shown in the header and on QR posters, so it's obvious
+    /// which account is active.
+    #[serde(default)]
+    pub accent: Option<[u8; 3]>,
+    /// Image file shown next to it, e.g. the brand's logo.
+    #[serde(default)]
+    pub logo: String,
+}
+
+impl Profile {
+    pub fn named(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// `SHORTYIO_API_KEY` and `SHORTYIO_DOMAIN` win over the config file, for
+    /// CI and containers. They're never written back to it.
+    pub fn with_env_overrides(mut self) -> Self {
+        if let Some(api_key) = env_override(ENV_API_KEY) {
+            self.api_key = api_key;
+        }
+        if let Some(domain) = env_override(ENV_DOMAIN) {
+            self.domain = domain;
+        }
+        self
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn default_redirect(&self) -> i32 {
+        self.default_redirect.unwrap_or(redirects::DEFAULT)
+    }
+
+    pub fn request_options(&self) -> RequestOptions {
+        RequestOptions {
+            user_agent: self.user_agent.clone(),
+            headers: self.headers.iter().map(|(name, value)| (name.clone(), value.clone())).collect(),
+            ca_cert: self.ca_cert.clone(),
+            pinned_cert: self.pinned_cert.clone(),
+        }
+    }
+
+    /// Headers as `Name: value` lines for editing.
+    #[cfg(feature = "gui")]
+    pub fn headers_text(&self) -> String {
+        self.headers.iter().map(|(name, value)| format!("{}: {}\n", name, value)).collect()
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn set_headers_text(&mut self, text: &str) {
+        self.headers = text
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .filter(|(name, _)| !name.is_empty())
+            .collect();
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn reserved_paths(&self) -> Vec<String> {
+        match &self.reserved_paths {
+            Some(paths) => paths.clone(),
+            None => reserved::DEFAULTS.iter().map(|path| path.to_string()).collect(),
+        }
+    }
+
+    /// Reserved paths one per line for editing.
+    #[cfg(feature = "gui")]
+    pub fn reserved_text(&self) -> String {
+        self.reserved_paths().iter().map(|path| format!("{}\n", path)).collect()
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn set_reserved_text(&mut self, text: &str) {
+        let paths: Vec<String> = text.lines().map(|line| line.trim().to_string()).filter(|line| !line.is_empty()).collect();
+        let defaults = reserved::DEFAULTS.iter().map(|path| path.to_string()).collect::<Vec<_>>();
+        // Kept unset while unchanged so later additions to the defaults apply
+        self.reserved_paths = (paths != defaults).then_some(paths);
+    }
+
+    /// Applies the path prefix unless the path already starts with it.
+    pub fn prefixed_path(&self, path: &str) -> String {
+        if path.starts_with(&self.path_prefix) {
+            path.to_string()
+        } else {
+            format!("{}{}", self.path_prefix, path)
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use crate::slugs;
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream};
+use std::path::Path;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Kind {
+    #[default]
+    Folder,
+    Ftp,
+    S3,
+}
+
+impl Kind {
+    pub const ALL: [Kind; 3] = [Kind::Folder, Kind::Ftp, Kind::S3];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Kind::Folder => "Local folder",
+            Kind::Ftp => "FTP",
+            Kind::S3 => "S3",
+        }
+    }
+}
+
+/// Where a generated file is uploaded. Only the fields of the chosen kind
+/// are used, so switching back and forth keeps what was typed.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct Target {
+    pub kind: Kind,
+    /// Folder, e.g. one synced by a static host, to write the file into.
+    pub folder: String,
+    /// `host` or `host:port`.
+    pub ftp_host: String,
+    pub ftp_user: String,
+    pub ftp_password: String,
+    /// Remote file path, e.g. `public_html/links.html`.
+    pub ftp_path: String,
+    /// e.g. `https://s3.eu-west-1.amazonaws.com`, or an S3-compatible service.
+    pub s3_endpoint: String,
+    pub s3_region: String,
+    pub s3_bucket: String,
+    /// Object key, e.g. `links/index.html`.
+    pub s3_key: String,
+    pub s3_access_key: String,
+    pub s3_secret_key: String,
+}
+
+impl Target {
+    /// Uploads an HTML page, blocking until it's done.
+    pub fn publish(&self, html: &str) -> Result<()> {
+        match self.kind {
+            Kind::Folder => {
+                if self.folder.trim().is_empty() {
+                    bail!("Choose a folder to publish to");
+                }
+                let path = Path::new(self.folder.trim()).join("index.html");
+                std::fs::write(&path, html).with_context(|| format!("Failed to write {}", path.display()))
+            }
+            Kind::Ftp => ftp_upload(self, html.as_bytes()),
+            Kind::S3 => {
+                let runtime = tokio::runtime::Runtime::new()?;
+                runtime.block_on(s3_upload(self, html.as_bytes()))
+            }
+        }
+    }
+}
+
+/// Plain FTP in passive mode, which is what cheap shared hosting offers.
+fn ftp_upload(target: &Target, body: &[u8]) -> Result<()> {
+    let host = target.ftp_host.trim();
+    if host.is_empty() || target.ftp_path.trim().is_empty() {
+        bail!("Set the FTP host and file path");
+    }
+    let address = if host.contains(':') { host.to_string() } else { format!("{}:21", host) };
+    let control = TcpStream::connect(&address).with_context(|| format!("Failed to connect to {}", address))?;
+    control.set_read_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(control.try_clone()?);
+    let mut writer = control.try_clone()?;
+    let mut send = |command: &str, expected: &[u16]| -> Result<(u16, String)> {
+        if !command.is_empty() {
+            write!(writer, "{}\r\n", command)?;
+        }
+        let (code, text) = ftp_reply(&mut reader)?;
+        if !expected.contains(&code) {
+            // Don't echo the password back in the error
+            let shown = if command.starts_with("PASS") { "PASS" } else { command };
+            bail!("FTP server answered {} {} to {}", code, text.trim(), shown);
+        }
+        Ok((code, text))
+    };
+
+    send("", &[220])?;
+    let (code, _) = send(&format!("USER {}", target.ftp_user), &[230, 331])?;
+    if code == 331 {
+        send(&format!("PASS {}", target.ftp_password), &[230, 202])?;
+    }
+    send("TYPE I", &[200])?;
+    let (_, passive) = send("PASV", &[227])?;
+    let port = pasv_port(&passive).ok_or_else(|| anyhow!("Couldn't read the FTP data port from {}", passive.trim()))?;
+    // The address in the reply is often a private one behind NAT, so reuse
+    // the host already connected to
+    let ip = control.peer_addr().map(|peer| peer.ip()).unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+    let mut data = TcpStream::connect_timeout(&SocketAddr::new(ip, port), TIMEOUT)?;
+    send(&format!("STOR {}", target.ftp_path.trim()), &[125, 150])?;
+    data.write_all(body)?;
+    drop(data);
+    send("", &[226, 250])?;
+    let _ = send("QUIT", &[221]);
+    Ok(())
+}
+
+/// Reads one reply, following multi-line `123-` continuations.
+fn ftp_reply(reader: &mut impl BufRead) -> Result<(u16, String)> {
+    let mut text = String::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            bail!("FTP server closed the connection");
+        }
+        text.push_str(&line);
+        let code = line.get(..3).and_then(|code| code.parse::<u16>().ok());
+        if let Some(code) = code {
+            if line.as_bytes().get(3) != Some(&b'-') {
+                return Ok((code, text));
+            }
+        }
+    }
+}
+
+/// The port from a `227 Entering Passive Mode (h1,h2,h3,h4,p1,p2)` reply.
+fn pasv_port(reply: &str) -> Option<u16> {
+    let open = reply.find('(')? + 1;
+    let inside = &reply[open..open + reply[open..].find(')')?];
+    let numbers: Vec<u8> = inside.split(',').map(|n| n.trim().parse().ok()).collect::<Option<_>>()?;
+    match numbers[..] {
+        [_, _, _, _, high, low] => Some(u16::from(high) * 256 + u16::from(low)),
+        _ => None,
+    }
+}
+
+/// A path-style PUT signed with AWS Signature Version 4.
+async fn s3_upload(target: &Target, body: &[u8]) -> Result<()> {
+    let endpoint = reqwest::Url::parse(target.s3_endpoint.trim()).context("Invalid S3 endpoint")?;
+    let host = match (endpoint.host_str(), endpoint.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        (None, _) => bail!("Invalid S3 endpoint"),
+    };
+    let region = if target.s3_region.trim().is_empty() { "us-east-1" } else { target.s3_region.trim() };
+    let path = format!("/{}/{}", target.s3_bucket.trim(), target.s3_key.trim().trim_start_matches('/'));
+    let uri = slugs::encode(&path);
+
+    let now = Utc::now();
+    let timestamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let content_type = "text/html; charset=utf-8";
+    let payload_hash = hex(&Sha256::digest(body));
+    let signed_headers = "content-type;host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "PUT\n{}\n\ncontent-type:{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        uri, content_type, host, payload_hash, timestamp, signed_headers, payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        timestamp,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+    let mut key = hmac(format!("AWS4{}", target.s3_secret_key.trim()).as_bytes(), date.as_bytes());
+    for part in [region, "s3", "aws4_request"] {
+        key = hmac(&key, part.as_bytes());
+    }
+    let signature = hex(&hmac(&key, string_to_sign.as_bytes()));
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        target.s3_access_key.trim(),
+        scope,
+        signed_headers,
+        signature
+    );
+
+    let url = format!("{}://{}{}", endpoint.scheme(), host, uri);
+    reqwest::Client::new()
+        .put(url)
+        .timeout(TIMEOUT)
+        .header("content-type", content_type)
+        .header("x-amz-content-sha256", payload_hash)
+        .header("x-amz-date", timestamp)
+        .header("authorization", authorization)
+        .body(body.to_vec())
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+fn hmac(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,46 @@
+use eframe::egui;
+use qrcode::{Color, QrCode};
+
+/// Modules of blank margin scanners need around the code.
+pub const QUIET_ZONE: usize = 4;
+
+/// Width of the code in modules and whether each module is dark, row by row.
+pub fn matrix(text: &str) -> anyhow::Result<(usize, Vec<bool>)> {
+    let code = QrCode::new(text.as_bytes())?;
+    let dark = code.to_colors().into_iter().map(|color| color == Color::Dark).collect();
+    Ok((code.width(), dark))
+}
+
+/// `text` as a black and white QR code image, `scale` pixels per module.
+pub fn image(text: &str, scale: u32) -> anyhow::Result<image::GrayImage> {
+    let (width, dark) = matrix(text)?;
+    let size = (width + 2 * QUIET_ZONE) as u32 * scale;
+    Ok(image::GrayImage::from_fn(size, size, |x, y| {
+        let (column, row) = ((x / scale) as usize, (y / scale) as usize);
+        let inside = (QUIET_ZONE..QUIET_ZONE + width).contains(&column) && (QUIET_ZONE..QUIET_ZONE + width).contains(&row);
+        let is_dark = inside && dark[(row - QUIET_ZONE) * width + column - QUIET_ZONE];
+        image::Luma([if is_dark { 0 } else { 255 }])
+    }))
+}
+
+/// Draws `text` as a QR code filling a `size` square.
+pub fn show(ui: &mut egui::Ui, text: &str, size: f32) {
+    let (width, dark) = match matrix(text) {
+        Ok(matrix) => matrix,
+        Err(e) => {
+            ui.colored_label(egui::Color32::from_rgb(220, 60, 60), format!("Can't make a QR code: {}", e));
+            return;
+        }
+    };
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, egui::Color32::WHITE);
+
+    let module = size / (width + 2 * QUIET_ZONE) as f32;
+    for (i, _) in dark.iter().enumerate().filter(|(_, dark)| **dark) {
+        let (x, y) = ((i % width + QUIET_ZONE) as f32, (i / width + QUIET_ZONE) as f32);
+        let min = rect.min + egui::vec2(x * module, y * module);
+        // Slight overlap avoids hairline gaps between modules
+        painter.rect_filled(egui::Rect::from_min_size(min, egui::vec2(module + 0.5, module + 0.5)), 0.0, egui::Color32::BLACK);
+    }
+}
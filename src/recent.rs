@@ -0,0 +1,84 @@
+use crate::storage;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+const RECENT_FILE: &str = "recent_results.json";
+/// How many result cards survive a restart.
+const KEEP: usize = 5;
+
+/// A created link as shown on the result card.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RecentResult {
+    pub short_url: String,
+    pub original_url: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub copied: bool,
+}
+
+/// The last few result cards, kept on disk so quitting with Escape doesn't
+/// lose a link that was never copied.
+#[derive(Default)]
+pub struct RecentResults {
+    items: Vec<RecentResult>,
+}
+
+impl RecentResults {
+    pub fn load() -> Self {
+        Self {
+            items: storage::load_json(RECENT_FILE).unwrap_or_default(),
+        }
+    }
+
+    /// Newest first.
+    pub fn items(&self) -> &[RecentResult] {
+        &self.items
+    }
+
+    pub fn add(&mut self, short_url: &str, original_url: &str) {
+        self.items.retain(|item| item.short_url != short_url);
+        self.items.insert(
+            0,
+            RecentResult {
+                short_url: short_url.to_string(),
+                original_url: original_url.to_string(),
+                created_at: Utc::now(),
+                copied: false,
+            },
+        );
+        self.items.truncate(KEEP);
+        self.persist();
+    }
+
+    pub fn mark_copied(&mut self, short_url: &str) {
+        if let Some(item) = self.items.iter_mut().find(|item| item.short_url == short_url && !item.copied) {
+            item.copied = true;
+            self.persist();
+        }
+    }
+
+    /// Puts a dismissed card back in date order.
+    pub fn restore(&mut self, item: RecentResult) {
+        let index = self.items.iter().position(|other| other.created_at < item.created_at).unwrap_or(self.items.len());
+        self.items.insert(index, item);
+        self.items.truncate(KEEP);
+        self.persist();
+    }
+
+    pub fn remove(&mut self, short_url: &str) {
+        self.items.retain(|item| item.short_url != short_url);
+        self.persist();
+    }
+
+    /// Forgets every card and overwrites the file before deleting it.
+    pub fn wipe(&mut self) -> anyhow::Result<()> {
+        self.items.clear();
+        storage::secure_delete(RECENT_FILE)
+    }
+
+    fn persist(&self) {
+        if let Err(e) = storage::save_json(RECENT_FILE, &self.items) {
+            elog!("Failed to save recent results: {}", e);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use eframe::egui;
+
+/// Redirect status codes short.io supports, with what each means for
+/// visitors and search engines.
+pub const TYPES: [(i32, &str, &str); 4] = [
+    (
+        301,
+        "301 Permanent",
+        "Browsers and search engines remember the destination. Best for links that will never change; \
+         editing the destination later may not reach people who already clicked.",
+    ),
+    (
+        302,
+        "302 Temporary",
+        "Every click goes through short.io, so destination changes apply at once. \
+         Search engines keep ranking the short link rather than the destination.",
+    ),
+    (
+        307,
+        "307 Temporary",
+        "Like 302, but browsers must repeat the request unchanged, e.g. keep a form POST a POST. \
+         Rarely needed for links shared with people.",
+    ),
+    (
+        308,
+        "308 Permanent",
+        "Like 301, but browsers must repeat the request unchanged. Rarely needed for links shared with people.",
+    ),
+];
+
+pub const DEFAULT: i32 = 301;
+
+pub fn label(code: i32) -> String {
+    TYPES
+        .iter()
+        .find(|(type_code, _, _)| *type_code == code)
+        .map(|(_, label, _)| label.to_string())
+        .unwrap_or_else(|| code.to_string())
+}
+
+/// A combo box of redirect types, each explained on hover. Returns whether
+/// the selection changed.
+pub fn picker(ui: &mut egui::Ui, id_salt: &str, code: &mut i32) -> bool {
+    let before = *code;
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(label(*code))
+        .show_ui(ui, |ui| {
+            for (type_code, label, explanation) in TYPES {
+                ui.selectable_value(code, type_code, label).on_hover_text(explanation);
+            }
+        })
+        .response
+        .on_hover_text(
+            TYPES
+                .iter()
+                .find(|(type_code, _, _)| *type_code == *code)
+                .map(|(_, _, explanation)| *explanation)
+                .unwrap_or_default(),
+        );
+    *code != before
+}
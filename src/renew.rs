@@ -0,0 +1,161 @@
+#[cfg(feature = "gui")]
+use crate::api::{ApiClient, CreateLinkRequest, UpdateLinkRequest};
+#[cfg(feature = "gui")]
+use crate::history::{History, HistoryEntry};
+#[cfg(feature = "gui")]
+use crate::urls::host;
+#[cfg(feature = "gui")]
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gui")]
+use std::sync::{Arc, Condvar, Mutex};
+#[cfg(feature = "gui")]
+use std::time::Duration;
+
+#[cfg(feature = "gui")]
+const INTERVAL: Duration = Duration::from_secs(15 * 60);
+/// Share of the clicks limit at which a link is renewed.
+#[cfg(feature = "gui")]
+pub const THRESHOLD: f64 = 0.9;
+
+/// What happens to a link that is close to its clicks limit.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum RenewMode {
+    #[default]
+    Off,
+    /// Doubles the limit on the same link.
+    Raise,
+    /// Creates a new link to the same destination with the same limit.
+    Duplicate,
+}
+
+#[cfg(feature = "gui")]
+impl RenewMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            RenewMode::Off => "Do nothing",
+            RenewMode::Raise => "Double the limit",
+            RenewMode::Duplicate => "Create a fresh copy",
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+#[derive(Default)]
+struct State {
+    api_key: String,
+    mode: RenewMode,
+    started: bool,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+/// Checks links created with a clicks limit every 15 minutes and renews the
+/// ones past `THRESHOLD` according to the chosen mode.
+#[cfg(feature = "gui")]
+#[derive(Clone, Default)]
+pub struct Renewer {
+    shared: Arc<Shared>,
+}
+
+#[cfg(feature = "gui")]
+impl Renewer {
+    pub fn configure(&self, api_key: &str, mode: RenewMode, history: &History) {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.api_key == api_key && state.mode == mode {
+            return;
+        }
+        state.api_key = api_key.to_string();
+        state.mode = mode;
+        if !state.started && mode != RenewMode::Off {
+            state.started = true;
+            self.spawn_worker(history.clone());
+        }
+        self.shared.changed.notify_all();
+    }
+
+    fn spawn_worker(&self, history: History) {
+        let shared = self.shared.clone();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            loop {
+                let (api_key, mode) = {
+                    let state = shared.state.lock().unwrap();
+                    (state.api_key.clone(), state.mode)
+                };
+                if mode != RenewMode::Off && !api_key.is_empty() {
+                    runtime.block_on(run(&ApiClient::new(api_key.clone()), mode, &history));
+                }
+                let state = shared.state.lock().unwrap();
+                let _ = shared
+                    .changed
+                    .wait_timeout_while(state, INTERVAL, |state| state.api_key == api_key && state.mode == mode)
+                    .unwrap();
+            }
+        });
+    }
+}
+
+#[cfg(feature = "gui")]
+async fn run(client: &ApiClient, mode: RenewMode, history: &History) {
+    let limited: Vec<(HistoryEntry, i32)> = history.read(|entries| {
+        entries
+            .iter()
+            .filter(|entry| !entry.renewed && !entry.deleted_remotely)
+            .filter_map(|entry| Some((entry.clone(), entry.clicks_limit?)))
+            .collect()
+    });
+    for (entry, limit) in limited {
+        let clicks = match client.link_stats(&entry.link_id).await {
+            Ok(stats) => stats.total_clicks,
+            // Most likely a link from another profile
+            Err(_) => continue,
+        };
+        if (clicks as f64) < f64::from(limit) * THRESHOLD {
+            continue;
+        }
+        match mode {
+            RenewMode::Off => return,
+            RenewMode::Raise => {
+                let raised = limit.saturating_mul(2);
+                let request = UpdateLinkRequest {
+                    clicks_limit: Some(raised),
+                    ..Default::default()
+                };
+                match client.update_link(&entry.link_id, &request).await {
+                    Ok(_) => history.update(&entry.link_id, |entry| entry.clicks_limit = Some(raised)),
+                    Err(e) => elog!("Failed to raise the clicks limit of {}: {}", entry.short_url, e),
+                }
+            }
+            RenewMode::Duplicate => {
+                let mut request = CreateLinkRequest::simple(entry.original_url.clone(), Some(host(&entry.short_url)));
+                request.allow_duplicates = true;
+                request.clicks_limit = Some(limit);
+                request.tags = Some(entry.tags.clone()).filter(|tags| !tags.is_empty()).or(request.tags);
+                request.title = entry.title.clone();
+                match client.create_link(&request).await {
+                    Ok(link) => {
+                        history.update(&entry.link_id, |entry| entry.renewed = true);
+                        history.add(HistoryEntry {
+                            link_id: link.id,
+                            short_url: link.short_url,
+                            original_url: link.original_url,
+                            title: entry.title,
+                            tags: request.tags.unwrap_or_default(),
+                            created_at: Utc::now(),
+                            clicks_limit: Some(limit),
+                            renewed: false,
+                            deleted_remotely: false,
+                        });
+                    }
+                    Err(e) => elog!("Failed to renew {}: {}", entry.short_url, e),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use crate::rollups::{self, Rollup, RollupStats};
+use crate::storage::csv_field;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum ReportFormat {
+    #[default]
+    Csv,
+    Html,
+}
+
+impl ReportFormat {
+    pub const ALL: [ReportFormat; 2] = [ReportFormat::Csv, ReportFormat::Html];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ReportFormat::Csv => "CSV",
+            ReportFormat::Html => "HTML",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Csv => "csv",
+            ReportFormat::Html => "html",
+        }
+    }
+}
+
+/// Writes a dated stats summary into `dir` and returns its path.
+pub fn write(dir: &Path, format: ReportFormat, stats: &RollupStats) -> anyhow::Result<PathBuf> {
+    let name = format!("shortyio-report-{}.{}", Local::now().format("%Y-%m-%d"), format.extension());
+    let path = dir.join(name);
+    let content = match format {
+        ReportFormat::Csv => csv(stats),
+        ReportFormat::Html => html(stats),
+    };
+    fs::write(&path, content)?;
+    Ok(path)
+}
+
+fn csv(stats: &RollupStats) -> String {
+    let mut rows = vec!["section,name,links,clicks".to_string()];
+    for (section, rollups) in [("tag", &stats.by_tag), ("folder", &stats.by_folder)] {
+        for rollup in rollups {
+            rows.push(format!("{},{},{},{}", section, csv_field(&rollup.name), rollup.links, rollup.total_clicks));
+        }
+    }
+    for (short_url, clicks) in &stats.top_links {
+        rows.push(format!("top_link,{},1,{}", csv_field(short_url), clicks));
+    }
+    rows.push(String::new());
+    rows.join("\n")
+}
+
+fn html(stats: &RollupStats) -> String {
+    let table = |title: &str, rollups: &[Rollup]| {
+        let rows: String = rollups
+            .iter()
+            .map(|r| format!("<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n", escape(&r.name), r.links, r.total_clicks))
+            .collect();
+        format!(
+            "<h2>{}</h2>\n<table>\n<tr><th>Name</th><th>Links</th><th>Clicks</th></tr>\n{}</table>\n",
+            title, rows
+        )
+    };
+    let top: String = stats
+        .top_links
+        .iter()
+        .map(|(short_url, clicks)| {
+            format!("<tr><td><a href=\"{0}\">{0}</a></td><td>{1}</td></tr>\n", escape(short_url), clicks)
+        })
+        .collect();
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>Link report for {domain}</title>\n\
+         <style>body{{font-family:sans-serif;max-width:720px;margin:2em auto}}\
+         table{{border-collapse:collapse;width:100%}}td,th{{border-bottom:1px solid #ddd;padding:4px 8px;text-align:left}}</style>\n\
+         </head><body>\n<h1>Link report for {domain}</h1>\n<p>{period}, generated {date}</p>\n\
+         {tags}{folders}<h2>Top links</h2>\n<table>\n<tr><th>Link</th><th>Clicks</th></tr>\n{top}</table>\n</body></html>\n",
+        domain = escape(&stats.domain),
+        period = rollups::period_label(&stats.period),
+        date = Local::now().format("%Y-%m-%d %H:%M"),
+        tags = table("By tag", &stats.by_tag),
+        folders = table("By folder", &stats.by_folder),
+        top = top,
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
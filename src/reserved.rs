@@ -0,0 +1,51 @@
+use std::collections::BTreeSet;
+
+/// Paths reserved until a profile sets its own list: words that read like
+/// part of a site or app and would make a short link look suspicious or
+/// clash with something the domain serves.
+pub const DEFAULTS: &[&str] = &[
+    "about", "account", "admin", "api", "app", "assets", "auth", "billing", "dashboard", "favicon.ico", "help",
+    "login", "logout", "oauth", "password", "privacy", "register", "robots.txt", "security", "settings", "signin",
+    "signup", "sitemap.xml", "static", "status", "support", "terms", "verify", "www",
+];
+
+/// The reserved word `path` uses, if any. A word matches the whole path or
+/// any of its words, so `admin` also catches `Admin-Panel`.
+pub fn check(path: &str, reserved: &[String]) -> Option<String> {
+    let path = path.trim().trim_matches('/').to_lowercase();
+    if path.is_empty() {
+        return None;
+    }
+    let words: Vec<&str> = path.split(['/', '-', '_', '.', '~']).collect();
+    reserved
+        .iter()
+        .map(|word| word.trim().to_lowercase())
+        .filter(|word| !word.is_empty())
+        .find(|word| path == *word || words.contains(&word.as_str()))
+        .map(|word| format!("\"{}\" is a reserved path for this profile", word))
+}
+
+/// Whether `path` is a folder other links live under, like `ev` next to
+/// `ev/summit`, which makes it easy to confuse with them.
+pub fn check_folder(path: &str, folders: &BTreeSet<String>) -> Option<String> {
+    let path = path.trim().trim_matches('/').to_lowercase();
+    folders.contains(&path).then(|| format!("Other links already live under {}/", path))
+}
+
+/// The folder part of each path, e.g. `ev` and `ev/2025` for
+/// `ev/2025/summit`.
+pub fn folders<'a>(paths: impl Iterator<Item = &'a str>) -> BTreeSet<String> {
+    let mut folders = BTreeSet::new();
+    for path in paths {
+        let path = path.to_lowercase();
+        let mut end = 0;
+        while let Some(slash) = path[end..].find('/') {
+            end += slash;
+            if end > 0 {
+                folders.insert(path[..end].to_string());
+            }
+            end += 1;
+        }
+    }
+    folders
+}
@@ -0,0 +1,63 @@
+use crate::api::{ApiClient, LinkResponse, UpdateLinkRequest};
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+
+/// Which links a bulk re-tag touches. Empty fields match everything.
+#[derive(Clone, Default)]
+pub struct Query {
+    pub url_contains: String,
+    pub created_before: Option<NaiveDate>,
+}
+
+impl Query {
+    pub fn matches(&self, link: &LinkResponse) -> bool {
+        let needle = self.url_contains.trim().to_lowercase();
+        (needle.is_empty() || link.original_url.to_lowercase().contains(&needle))
+            && self
+                .created_before
+                .is_none_or(|before| link.created_at.is_some_and(|at| at.date_naive() < before))
+    }
+}
+
+/// Every link on the domain matching `query`, for previewing.
+pub async fn find(client: &ApiClient, hostname: &str, query: &Query) -> Result<Vec<LinkResponse>> {
+    let domain = client
+        .find_domain(hostname)
+        .await?
+        .ok_or_else(|| anyhow!("Domain {} not found on this account", hostname))?;
+    Ok(client
+        .list_links(domain.id)
+        .await?
+        .into_iter()
+        .filter(|link| query.matches(link))
+        .collect())
+}
+
+/// `tags` without `remove` (case-insensitively) and with `add`.
+pub fn retagged(tags: &[String], add: &[String], remove: &[String]) -> Vec<String> {
+    let mut result: Vec<String> = tags
+        .iter()
+        .filter(|tag| !remove.iter().any(|r| r.eq_ignore_ascii_case(tag)))
+        .cloned()
+        .collect();
+    for tag in add {
+        if !result.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            result.push(tag.clone());
+        }
+    }
+    result
+}
+
+/// Updates one link's tags. Returns whether anything had to change.
+pub async fn apply(client: &ApiClient, link: &LinkResponse, add: &[String], remove: &[String]) -> Result<bool> {
+    let tags = retagged(&link.tags, add, remove);
+    if tags == link.tags {
+        return Ok(false);
+    }
+    let request = UpdateLinkRequest {
+        tags: Some(tags),
+        ..Default::default()
+    };
+    client.update_link(&link.id, &request).await?;
+    Ok(true)
+}
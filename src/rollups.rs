@@ -0,0 +1,92 @@
+use crate::api::ApiClient;
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+
+/// Periods short.io can total clicks over, with their labels.
+pub const PERIODS: [(&str, &str); 4] = [
+    ("last7", "Last 7 days"),
+    ("last30", "Last 30 days"),
+    ("lastmonth", "Last month"),
+    ("total", "All time"),
+];
+
+pub fn period_label(period: &str) -> &str {
+    PERIODS.iter().find(|(p, _)| *p == period).map_or(period, |(_, label)| label)
+}
+
+/// Clicks summed over every link in one tag or folder.
+#[derive(Clone)]
+pub struct Rollup {
+    pub name: String,
+    pub links: usize,
+    pub total_clicks: u64,
+}
+
+/// Click totals per tag and per folder across a domain, most clicked first.
+#[derive(Clone)]
+pub struct RollupStats {
+    pub domain: String,
+    pub period: String,
+    pub by_tag: Vec<Rollup>,
+    pub by_folder: Vec<Rollup>,
+    /// The ten most clicked links with their clicks.
+    pub top_links: Vec<(String, u64)>,
+}
+
+pub async fn fetch(client: &ApiClient, hostname: &str, period: &str) -> Result<RollupStats> {
+    let domain = client
+        .find_domain(hostname)
+        .await?
+        .ok_or_else(|| anyhow!("Domain {} not found on this account", hostname))?;
+    let folders: HashMap<String, String> = client
+        .list_folders(domain.id)
+        .await?
+        .into_iter()
+        .map(|folder| (folder.id, folder.name))
+        .collect();
+
+    let mut by_tag: HashMap<String, Rollup> = HashMap::new();
+    let mut by_folder: HashMap<String, Rollup> = HashMap::new();
+    let mut top_links = Vec::new();
+    for link in client.list_links(domain.id).await? {
+        let clicks = client.link_stats_for(&link.id, period).await?.total_clicks;
+        top_links.push((link.short_url.clone(), clicks));
+        // Every link the app creates has this tag, so it says nothing
+        for tag in link.tags.iter().filter(|tag| *tag != "shortyio") {
+            add(&mut by_tag, tag, clicks);
+        }
+        let folder = link
+            .folder_id
+            .as_ref()
+            .and_then(|id| folders.get(id))
+            .map_or("No folder", String::as_str);
+        add(&mut by_folder, folder, clicks);
+    }
+
+    top_links.sort_by(|a, b| b.1.cmp(&a.1));
+    top_links.truncate(10);
+
+    Ok(RollupStats {
+        domain: hostname.to_string(),
+        period: period.to_string(),
+        by_tag: ranked(by_tag),
+        by_folder: ranked(by_folder),
+        top_links,
+    })
+}
+
+fn add(rollups: &mut HashMap<String, Rollup>, name: &str, clicks: u64) {
+    let rollup = rollups.entry(name.to_string()).or_insert_with(|| Rollup {
+        name: name.to_string(),
+        links: 0,
+        total_clicks: 0,
+    });
+    rollup.links += 1;
+    rollup.total_clicks += clicks;
+}
+
+fn ranked(rollups: HashMap<String, Rollup>) -> Vec<Rollup> {
+    let mut rollups: Vec<Rollup> = rollups.into_values().collect();
+    rollups.sort_by(|a, b| b.total_clicks.cmp(&a.total_clicks).then_with(|| a.name.cmp(&b.name)));
+    rollups
+}
@@ -0,0 +1,296 @@
+use crate::api::{ApiClient, UpdateLinkRequest};
+use crate::reports::{self, ReportFormat};
+use crate::{rollups, storage};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const SCHEDULE_FILE: &str = "schedule.json";
+const TICK: Duration = Duration::from_secs(30);
+
+/// A link created against a holding page that should switch to its real
+/// destination once `go_live_at` has passed.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ScheduledActivation {
+    pub link_id: String,
+    pub short_url: String,
+    pub destination: String,
+    pub go_live_at: DateTime<Utc>,
+    /// Profile whose API key the link was created with.
+    #[serde(default)]
+    pub profile: String,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+/// An existing link whose destination is replaced with the next URL from
+/// `queue` every `interval_days`, starting at `next_run`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Rotation {
+    pub link_id: String,
+    pub short_url: String,
+    pub interval_days: u32,
+    pub next_run: DateTime<Utc>,
+    pub queue: Vec<String>,
+    #[serde(default)]
+    pub profile: String,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+impl Rotation {
+    fn advance(&mut self, now: DateTime<Utc>) {
+        let interval = ChronoDuration::days(i64::from(self.interval_days.max(1)));
+        while self.next_run <= now {
+            self.next_run += interval;
+        }
+    }
+}
+
+/// A stats summary of the last week written to `folder` every week.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReportSchedule {
+    pub folder: PathBuf,
+    pub format: ReportFormat,
+    pub next_run: DateTime<Utc>,
+    #[serde(default)]
+    pub profile: String,
+    #[serde(default)]
+    pub last_file: Option<PathBuf>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+struct ScheduleState {
+    #[serde(default)]
+    activations: Vec<ScheduledActivation>,
+    #[serde(default)]
+    rotations: Vec<Rotation>,
+    #[serde(default)]
+    report: Option<ReportSchedule>,
+}
+
+/// Runs pending activations and rotations on a background thread while the
+/// app is open. Anything that comes due while the app is closed runs on the
+/// next launch.
+#[derive(Clone)]
+pub struct Scheduler {
+    state: Arc<Mutex<ScheduleState>>,
+}
+
+impl Scheduler {
+    pub fn start() -> Self {
+        let state: ScheduleState = storage::load_json(SCHEDULE_FILE).unwrap_or_default();
+        let scheduler = Self {
+            state: Arc::new(Mutex::new(state)),
+        };
+
+        let worker = scheduler.clone();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            loop {
+                runtime.block_on(worker.run_due());
+                std::thread::sleep(TICK);
+            }
+        });
+
+        scheduler
+    }
+
+    pub fn add(&self, activation: ScheduledActivation) {
+        let mut state = self.state.lock().unwrap();
+        state.activations.push(activation);
+        state.activations.sort_by_key(|a| a.go_live_at);
+        Self::persist(&state);
+    }
+
+    pub fn cancel(&self, link_id: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.activations.retain(|a| a.link_id != link_id);
+        Self::persist(&state);
+    }
+
+    pub fn pending(&self) -> Vec<ScheduledActivation> {
+        self.state.lock().unwrap().activations.clone()
+    }
+
+    pub fn add_rotation(&self, rotation: Rotation) {
+        let mut state = self.state.lock().unwrap();
+        state.rotations.retain(|r| r.link_id != rotation.link_id);
+        state.rotations.push(rotation);
+        Self::persist(&state);
+    }
+
+    pub fn remove_rotation(&self, link_id: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.rotations.retain(|r| r.link_id != link_id);
+        Self::persist(&state);
+    }
+
+    pub fn set_queue(&self, link_id: &str, queue: Vec<String>) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(rotation) = state.rotations.iter_mut().find(|r| r.link_id == link_id) {
+            rotation.queue = queue;
+        }
+        Self::persist(&state);
+    }
+
+    pub fn rotations(&self) -> Vec<Rotation> {
+        self.state.lock().unwrap().rotations.clone()
+    }
+
+    pub fn report(&self) -> Option<ReportSchedule> {
+        self.state.lock().unwrap().report.clone()
+    }
+
+    /// Starts weekly reports on `profile`'s domain with the first one right
+    /// away, or stops them.
+    pub fn set_report(&self, folder: Option<PathBuf>, format: ReportFormat, profile: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.report = folder.map(|folder| ReportSchedule {
+            folder,
+            format,
+            next_run: Utc::now(),
+            profile: profile.to_string(),
+            last_file: None,
+            last_error: None,
+        });
+        Self::persist(&state);
+    }
+
+    async fn run_due(&self) {
+        let now = Utc::now();
+        let due_report = self.state.lock().unwrap().report.clone().filter(|report| report.next_run <= now);
+        let (due_activations, due_rotations) = {
+            let state = self.state.lock().unwrap();
+            let activations: Vec<ScheduledActivation> = state
+                .activations
+                .iter()
+                .filter(|a| a.go_live_at <= now)
+                .cloned()
+                .collect();
+            let rotations: Vec<Rotation> = state
+                .rotations
+                .iter()
+                .filter(|r| r.next_run <= now)
+                .cloned()
+                .collect();
+            (activations, rotations)
+        };
+        if due_activations.is_empty() && due_rotations.is_empty() && due_report.is_none() {
+            return;
+        }
+
+        let Some(config) = crate::Config::load() else {
+            return;
+        };
+        // Each item runs with the key of the profile it was set up under,
+        // whichever profile is active now
+        let profile = |name: &str| config.profile_named(name).ok_or_else(|| format!("Profile \"{}\" no longer exists", name));
+
+        if let Some(report) = due_report {
+            let result = match profile(&report.profile) {
+                Ok(profile) => match rollups::fetch(&ApiClient::new(profile.api_key), &profile.domain, "last7").await {
+                    Ok(stats) => reports::write(&report.folder, report.format, &stats).map_err(|e| e.to_string()),
+                    Err(e) => Err(e.to_string()),
+                },
+                Err(e) => Err(e),
+            };
+            let mut state = self.state.lock().unwrap();
+            if let Some(entry) = &mut state.report {
+                match result {
+                    Ok(path) => {
+                        entry.last_file = Some(path);
+                        entry.last_error = None;
+                    }
+                    Err(e) => {
+                        elog!("Failed to write stats report: {}", e);
+                        entry.last_error = Some(e);
+                    }
+                }
+                // Failures wait a week too rather than retrying every tick
+                while entry.next_run <= now {
+                    entry.next_run += ChronoDuration::weeks(1);
+                }
+            }
+            Self::persist(&state);
+        }
+
+        for activation in due_activations {
+            let request = UpdateLinkRequest {
+                original_url: Some(activation.destination.clone()),
+                ..Default::default()
+            };
+            let result = match profile(&activation.profile) {
+                Ok(profile) => ApiClient::new(profile.api_key)
+                    .update_link(&activation.link_id, &request)
+                    .await
+                    .map_err(|e| e.to_string()),
+                Err(e) => Err(e),
+            };
+
+            let mut state = self.state.lock().unwrap();
+            match result {
+                Ok(_) => state.activations.retain(|a| a.link_id != activation.link_id),
+                Err(e) => {
+                    elog!("Failed to activate {}: {}", activation.short_url, e);
+                    if let Some(entry) = state.activations.iter_mut().find(|a| a.link_id == activation.link_id) {
+                        entry.last_error = Some(e);
+                    }
+                }
+            }
+            Self::persist(&state);
+        }
+
+        for rotation in due_rotations {
+            let result = match (rotation.queue.first(), profile(&rotation.profile)) {
+                (Some(next), Ok(profile)) => {
+                    let request = UpdateLinkRequest {
+                        original_url: Some(next.clone()),
+                        ..Default::default()
+                    };
+                    ApiClient::new(profile.api_key)
+                        .update_link(&rotation.link_id, &request)
+                        .await
+                        .map_err(|e| e.to_string())
+                }
+                (None, _) => Err("Queue was empty".to_string()),
+                (_, Err(e)) => Err(e),
+            };
+
+            let mut state = self.state.lock().unwrap();
+            if let Some(entry) = state.rotations.iter_mut().find(|r| r.link_id == rotation.link_id) {
+                match result {
+                    Ok(_) => {
+                        // The queue may have been edited while the request
+                        // was out, so drop the URL that was sent, not the first
+                        if let Some(index) = rotation.queue.first().and_then(|sent| entry.queue.iter().position(|url| url == sent)) {
+                            entry.queue.remove(index);
+                        }
+                        entry.last_error = None;
+                        entry.advance(now);
+                    }
+                    Err(e) => {
+                        elog!("Failed to rotate {}: {}", rotation.short_url, e);
+                        entry.last_error = Some(e);
+                        // An empty queue waits for the next slot instead of
+                        // firing late as soon as URLs are added.
+                        if entry.queue.is_empty() {
+                            entry.advance(now);
+                        }
+                    }
+                }
+            }
+            Self::persist(&state);
+        }
+    }
+
+    fn persist(state: &ScheduleState) {
+        if let Err(e) = storage::save_json(SCHEDULE_FILE, state) {
+            elog!("Failed to save schedule: {}", e);
+        }
+    }
+}
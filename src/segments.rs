@@ -0,0 +1,47 @@
+use crate::api::ApiClient;
+use anyhow::{Result, anyhow};
+
+/// Clicks on one link under a path prefix.
+#[derive(Clone)]
+pub struct SegmentLink {
+    pub short_url: String,
+    pub total_clicks: u64,
+    pub human_clicks: u64,
+}
+
+/// Click totals across every link whose path starts with a profile's prefix.
+#[derive(Clone)]
+pub struct SegmentStats {
+    pub prefix: String,
+    pub links: Vec<SegmentLink>,
+    pub total_clicks: u64,
+    pub human_clicks: u64,
+}
+
+pub async fn fetch(client: &ApiClient, hostname: &str, prefix: &str) -> Result<SegmentStats> {
+    let domain = client
+        .find_domain(hostname)
+        .await?
+        .ok_or_else(|| anyhow!("Domain {} not found on this account", hostname))?;
+
+    let mut links = Vec::new();
+    for link in client.list_links(domain.id).await? {
+        if !link.path.starts_with(prefix) {
+            continue;
+        }
+        let stats = client.link_stats(&link.id).await?;
+        links.push(SegmentLink {
+            short_url: link.short_url,
+            total_clicks: stats.total_clicks,
+            human_clicks: stats.human_clicks,
+        });
+    }
+    links.sort_by(|a, b| b.total_clicks.cmp(&a.total_clicks));
+
+    Ok(SegmentStats {
+        prefix: prefix.to_string(),
+        total_clicks: links.iter().map(|l| l.total_clicks).sum(),
+        human_clicks: links.iter().map(|l| l.human_clicks).sum(),
+        links,
+    })
+}
@@ -0,0 +1,45 @@
+/// One link collected into the share sheet; the title is editable in the
+/// side panel before copying.
+#[derive(Clone)]
+pub struct ShareItem {
+    pub title: String,
+    pub short_url: String,
+}
+
+/// Every link created while share sheet mode is on, rendered as a Markdown
+/// list for link round-ups.
+#[derive(Default)]
+pub struct ShareSheet {
+    pub enabled: bool,
+    pub items: Vec<ShareItem>,
+}
+
+impl ShareSheet {
+    pub fn add(&mut self, title: Option<&str>, original_url: &str, short_url: &str) {
+        if self.items.iter().any(|item| item.short_url == short_url) {
+            return;
+        }
+        let title = title.map(str::to_string).unwrap_or_else(|| default_title(original_url));
+        self.items.push(ShareItem {
+            title,
+            short_url: short_url.to_string(),
+        });
+    }
+
+    pub fn to_markdown(&self) -> String {
+        self.items
+            .iter()
+            .map(|item| format!("- [{}]({})\n", escape(&item.title), item.short_url))
+            .collect()
+    }
+}
+
+/// Host and path of the destination, which reads better than the raw URL.
+fn default_title(original_url: &str) -> String {
+    let rest = original_url.split_once("://").map(|(_, rest)| rest).unwrap_or(original_url);
+    rest.trim_start_matches("www.").trim_end_matches('/').to_string()
+}
+
+fn escape(title: &str) -> String {
+    title.replace('[', "\\[").replace(']', "\\]")
+}
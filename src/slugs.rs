@@ -0,0 +1,35 @@
+/// A few emoji for the picker next to the custom path field. short.io
+/// accepts any of them in a path.
+#[cfg(feature = "gui")]
+pub const EMOJI: &[&str] = &[
+    "😀", "😎", "🤩", "🥳", "😍", "🤔", "👍", "👋", "🙌", "👏", "🔥", "✨", "🎉", "🎁", "🚀", "⭐", "💡", "💰", "📈", "📣",
+    "📅", "📚", "🎵", "🎮", "🍕", "☕", "🌮", "🌍", "🌈", "☀", "❤", "💙", "💚", "💜", "✅", "👉",
+];
+
+/// Why `path` can't be registered as a short link path, if anything.
+/// Letters and emoji from any script are fine; ASCII punctuation other than
+/// `-_.~` would change the meaning of the URL.
+pub fn check(path: &str) -> Option<String> {
+    let bad = path
+        .chars()
+        .find(|&c| c.is_whitespace() || c.is_control() || (c.is_ascii() && !c.is_ascii_alphanumeric() && !"-_.~".contains(c)));
+    bad.map(|c| match c {
+        c if c.is_whitespace() => "Custom path can't contain spaces".to_string(),
+        c if c.is_control() => "Custom path can't contain control characters".to_string(),
+        c => format!("Custom path can't contain '{}'", c),
+    })
+}
+
+/// The percent-encoded form of `path` that appears in the short URL.
+#[cfg(feature = "gui")]
+pub fn encode(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~/".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
@@ -0,0 +1,74 @@
+use crate::slugs;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Both networks count any link as this many characters, however long.
+const LINK_LENGTH: usize = 23;
+
+static LINK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"https?://\S+").unwrap());
+
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum Network {
+    #[default]
+    Mastodon,
+    X,
+}
+
+impl Network {
+    pub const ALL: [Network; 2] = [Network::Mastodon, Network::X];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Network::Mastodon => "Mastodon",
+            Network::X => "X (Twitter)",
+        }
+    }
+
+    /// Characters allowed in one post.
+    pub fn limit(self) -> usize {
+        match self {
+            Network::Mastodon => 500,
+            Network::X => 280,
+        }
+    }
+
+    /// Length of `text` as the network counts it. X weighs most non-Latin
+    /// characters double.
+    pub fn count(self, text: &str) -> usize {
+        let links = LINK.find_iter(text).count();
+        let rest = LINK.replace_all(text, "");
+        let weight = |c: char| match self {
+            Network::X if c as u32 > 0x10FF && !matches!(c as u32, 0x2000..=0x200D | 0x2010..=0x201F | 0x2032..=0x2037) => 2,
+            _ => 1,
+        };
+        links * LINK_LENGTH + rest.chars().map(weight).sum::<usize>()
+    }
+
+    /// The network's compose page with `text` filled in. Mastodon needs the
+    /// user's server, e.g. `mastodon.social`.
+    pub fn compose_url(self, text: &str, server: &str) -> String {
+        match self {
+            Network::Mastodon => {
+                let server = server.trim().trim_start_matches("https://").trim_end_matches('/');
+                format!("https://{}/share?text={}", server, slugs::encode(text))
+            }
+            Network::X => format!("https://x.com/intent/post?text={}", slugs::encode(text)),
+        }
+    }
+}
+
+/// The post text suggested for a link: its page title, then the short URL.
+pub fn suggested_text(title: Option<&str>, short_url: &str) -> String {
+    match title {
+        Some(title) => format!("{} {}", title, short_url),
+        None => short_url.to_string(),
+    }
+}
+
+/// A post being written about a link.
+pub struct Compose {
+    pub short_url: String,
+    pub original_url: String,
+    pub text: String,
+    pub network: Network,
+}
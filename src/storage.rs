@@ -0,0 +1,156 @@
+use anyhow::Result;
+use directories::ProjectDirs;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fs;
+#[cfg(feature = "gui")]
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Next to the executable, naming a directory picked by hand.
+const LOCATION_FILE: &str = "shortyio-location.txt";
+/// Next to the executable; settings and data live here in portable mode.
+const PORTABLE_DIR: &str = "shortyio-data";
+
+pub const ENV_CONFIG_DIR: &str = "SHORTYIO_CONFIG_DIR";
+
+static CHOSEN: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Config,
+    Data,
+}
+
+impl Kind {
+    fn subdir(self) -> &'static str {
+        match self {
+            Kind::Config => "config",
+            Kind::Data => "data",
+        }
+    }
+}
+
+/// Where settings are saved, or `None` when nowhere is usable and the user
+/// has to pick a directory.
+pub fn config_dir() -> Option<PathBuf> {
+    dir(Kind::Config)
+}
+
+/// Location of a JSON file in the app's data directory.
+pub fn data_path(file: &str) -> Option<PathBuf> {
+    dir(Kind::Data).map(|dir| dir.join(file))
+}
+
+/// Tries, in order: `SHORTYIO_CONFIG_DIR` (config in it, data in its `data`
+/// folder), a directory picked by hand, an existing portable directory, the platform's usual place, the XDG variables, the home
+/// directory, and finally a new portable directory.
+fn dir(kind: Kind) -> Option<PathBuf> {
+    if let Some(base) = env_dir(ENV_CONFIG_DIR) {
+        return Some(match kind {
+            Kind::Config => base,
+            Kind::Data => base.join("data"),
+        });
+    }
+    if let Some(base) = chosen_dir() {
+        return Some(base.join(kind.subdir()));
+    }
+    let portable = exe_dir().map(|dir| dir.join(PORTABLE_DIR));
+    if let Some(base) = portable.as_ref().filter(|dir| dir.is_dir()) {
+        return Some(base.join(kind.subdir()));
+    }
+    if let Some(dirs) = ProjectDirs::from("systems", "weedmark", "shortyio") {
+        return Some(match kind {
+            Kind::Config => dirs.config_dir().to_path_buf(),
+            Kind::Data => dirs.data_dir().to_path_buf(),
+        });
+    }
+    let xdg = match kind {
+        Kind::Config => "XDG_CONFIG_HOME",
+        Kind::Data => "XDG_DATA_HOME",
+    };
+    if let Some(base) = env_dir(xdg) {
+        return Some(base.join("shortyio"));
+    }
+    if let Some(home) = env_dir("HOME").or_else(|| env_dir("USERPROFILE")) {
+        return Some(home.join(".shortyio").join(kind.subdir()));
+    }
+    let portable = portable?;
+    fs::create_dir_all(&portable).ok()?;
+    Some(portable.join(kind.subdir()))
+}
+
+/// Uses `dir` for settings and data from now on, remembering it next to the
+/// executable when that's writable.
+#[cfg(feature = "gui")]
+pub fn choose_dir(dir: PathBuf) -> Result<()> {
+    fs::create_dir_all(&dir)?;
+    if let Some(exe_dir) = exe_dir() {
+        if let Err(e) = fs::write(exe_dir.join(LOCATION_FILE), dir.to_string_lossy().as_bytes()) {
+            elog!("Can't remember the chosen directory, it applies to this session only: {}", e);
+        }
+    }
+    *CHOSEN.lock().unwrap() = Some(dir);
+    Ok(())
+}
+
+fn chosen_dir() -> Option<PathBuf> {
+    let mut chosen = CHOSEN.lock().unwrap();
+    if chosen.is_none() {
+        let saved = fs::read_to_string(exe_dir()?.join(LOCATION_FILE)).ok()?;
+        *chosen = Some(PathBuf::from(saved.trim())).filter(|dir| dir.is_absolute());
+    }
+    chosen.clone()
+}
+
+fn exe_dir() -> Option<PathBuf> {
+    std::env::current_exe().ok()?.parent().map(PathBuf::from)
+}
+
+fn env_dir(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name).map(PathBuf::from).filter(|dir| dir.is_absolute())
+}
+
+pub fn load_json<T: DeserializeOwned>(file: &str) -> Option<T> {
+    let path = data_path(file)?;
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Quotes a CSV field when it contains a separator, quote or newline.
+#[cfg(feature = "gui")]
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn save_json<T: Serialize>(file: &str, value: &T) -> Result<()> {
+    let path = data_path(file).ok_or_else(|| anyhow::anyhow!("Cannot determine data path"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+/// Overwrites a data file with zeros before removing it. This defeats casual
+/// recovery on shared machines, though SSD wear levelling and filesystem
+/// snapshots may still keep older copies.
+#[cfg(feature = "gui")]
+pub fn secure_delete(file: &str) -> Result<()> {
+    let path = data_path(file).ok_or_else(|| anyhow::anyhow!("Cannot determine data path"))?;
+    if !path.exists() {
+        return Ok(());
+    }
+    let len = fs::metadata(&path)?.len() as usize;
+    let mut handle = fs::OpenOptions::new().write(true).open(&path)?;
+    handle.write_all(&vec![0u8; len])?;
+    handle.sync_all()?;
+    drop(handle);
+    fs::remove_file(path)?;
+    Ok(())
+}
@@ -0,0 +1,264 @@
+use crate::api::{ApiClient, ApiError, LinkResponse};
+use crate::history::{History, HistoryEntry};
+use crate::trash;
+use crate::urls;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use eframe::egui;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Delay before the first background sync, so it doesn't compete with
+/// everything else the app fetches at launch.
+const FIRST_SYNC: Duration = Duration::from_secs(60);
+/// Most times the interval is doubled after short.io rate-limits a sync.
+const MAX_BACKOFF: u32 = 4;
+
+/// A field changed on the short.io dashboard since the link was created.
+#[derive(Clone)]
+pub struct Difference {
+    pub short_url: String,
+    pub field: &'static str,
+    pub local: String,
+    pub remote: String,
+}
+
+/// What a sync found, for the summary shown afterwards.
+#[derive(Clone)]
+pub struct Report {
+    pub at: DateTime<Utc>,
+    pub checked: usize,
+    /// Short URLs of links deleted outside the app.
+    pub deleted: Vec<String>,
+    pub edited: Vec<Difference>,
+    /// Domains in history that this account doesn't have, left alone.
+    pub skipped_domains: Vec<String>,
+}
+
+impl Report {
+    pub fn is_clean(&self) -> bool {
+        self.deleted.is_empty() && self.edited.is_empty()
+    }
+}
+
+/// Brings history in line with short.io. Links deleted on the dashboard are
+/// marked as deleted remotely rather than dropped, and destinations and tags
+/// changed there are copied into history.
+pub async fn reconcile(client: &ApiClient, history: &History) -> Result<Report> {
+    let trashed: HashSet<String> = trash::items(client).into_iter().map(|item| item.link_id).collect();
+    let mut by_domain: BTreeMap<String, Vec<HistoryEntry>> = BTreeMap::new();
+    history.read(|entries| {
+        for entry in entries.iter().filter(|entry| !entry.deleted_remotely && !trashed.contains(&entry.link_id)) {
+            by_domain.entry(urls::host(&entry.short_url)).or_default().push(entry.clone());
+        }
+    });
+
+    let mut report = Report {
+        at: Utc::now(),
+        checked: 0,
+        deleted: Vec::new(),
+        edited: Vec::new(),
+        skipped_domains: Vec::new(),
+    };
+    client.forget_cached_links();
+    for (hostname, entries) in by_domain {
+        // History is shared between profiles, so another account's links
+        // aren't this one's to judge
+        let Some(domain) = client.find_domain(&hostname).await? else {
+            report.skipped_domains.push(hostname);
+            continue;
+        };
+        let listed: HashMap<String, LinkResponse> =
+            client.list_links(domain.id).await?.into_iter().map(|link| (link.id.clone(), link)).collect();
+        for entry in entries {
+            report.checked += 1;
+            let link = match listed.get(&entry.link_id) {
+                Some(link) => link.clone(),
+                // Missing from the list; ask for it directly before calling it gone
+                None => match client.get_link(&entry.link_id).await {
+                    Ok(link) => link,
+                    Err(ApiError::Status { status, .. }) if status == reqwest::StatusCode::NOT_FOUND => {
+                        history.update(&entry.link_id, |entry| entry.deleted_remotely = true);
+                        report.deleted.push(entry.short_url);
+                        continue;
+                    }
+                    Err(e) => return Err(e.into()),
+                },
+            };
+            let mut differences = Vec::new();
+            if link.original_url != entry.original_url {
+                differences.push(("Destination", entry.original_url.clone(), link.original_url.clone()));
+            }
+            if link.tags != entry.tags {
+                differences.push(("Tags", entry.tags.join(", "), link.tags.join(", ")));
+            }
+            if differences.is_empty() {
+                continue;
+            }
+            history.update(&entry.link_id, |entry| {
+                entry.original_url = link.original_url.clone();
+                entry.tags = link.tags.clone();
+            });
+            report.edited.extend(differences.into_iter().map(|(field, local, remote)| Difference {
+                short_url: entry.short_url.clone(),
+                field,
+                local,
+                remote,
+            }));
+        }
+    }
+    Ok(report)
+}
+
+#[derive(Default)]
+struct State {
+    api_key: String,
+    /// Zero syncs only when asked.
+    interval: Duration,
+    started: bool,
+    /// Set by `sync_now`, which wakes the worker early.
+    requested: bool,
+    running: bool,
+    last_synced: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+    /// A finished sync the UI hasn't picked up, and whether it was asked for.
+    report: Option<(Result<Report, String>, bool)>,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+/// Reconciles history with short.io on a timer, and on demand. Backs off
+/// while short.io is rate-limiting the account.
+#[derive(Clone, Default)]
+pub struct SyncDaemon {
+    shared: Arc<Shared>,
+}
+
+impl SyncDaemon {
+    pub fn configure(&self, api_key: &str, interval_minutes: u32, history: &History, ctx: &egui::Context) {
+        let interval = Duration::from_secs(u64::from(interval_minutes) * 60);
+        let mut state = self.shared.state.lock().unwrap();
+        if state.api_key == api_key && state.interval == interval && state.started {
+            return;
+        }
+        state.api_key = api_key.to_string();
+        state.interval = interval;
+        if !state.started {
+            state.started = true;
+            self.spawn_worker(history.clone(), ctx.clone());
+        }
+        self.shared.changed.notify_all();
+    }
+
+    pub fn sync_now(&self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.requested = true;
+        self.shared.changed.notify_all();
+    }
+
+    pub fn is_running(&self) -> bool {
+        let state = self.shared.state.lock().unwrap();
+        state.running || state.requested
+    }
+
+    pub fn last_synced(&self) -> Option<DateTime<Utc>> {
+        self.shared.state.lock().unwrap().last_synced
+    }
+
+    /// Why the latest sync failed, until one succeeds.
+    pub fn last_error(&self) -> Option<String> {
+        self.shared.state.lock().unwrap().last_error.clone()
+    }
+
+    /// The sync that finished since the last call, if any, and whether the
+    /// user asked for it.
+    pub fn take_report(&self) -> Option<(Result<Report, String>, bool)> {
+        self.shared.state.lock().unwrap().report.take()
+    }
+
+    fn spawn_worker(&self, history: History, ctx: egui::Context) {
+        let shared = self.shared.clone();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let mut wait = FIRST_SYNC;
+            let mut backoff = 0;
+            loop {
+                let (api_key, manual) = {
+                    let state = shared.state.lock().unwrap();
+                    let (api_key, interval) = (state.api_key.clone(), state.interval);
+                    let unchanged =
+                        |state: &mut State| !state.requested && state.api_key == api_key && state.interval == interval;
+                    let (mut state, timed_out) = if interval.is_zero() {
+                        (shared.changed.wait_while(state, unchanged).unwrap(), false)
+                    } else {
+                        let (state, timeout) = shared.changed.wait_timeout_while(state, wait, unchanged).unwrap();
+                        (state, timeout.timed_out())
+                    };
+                    let manual = std::mem::take(&mut state.requested);
+                    if state.api_key.is_empty() || !(manual || timed_out) {
+                        // Settings changed; wait again with the new ones
+                        wait = jittered(state.interval);
+                        continue;
+                    }
+                    state.running = true;
+                    (state.api_key.clone(), manual)
+                };
+                ctx.request_repaint();
+
+                let client = ApiClient::new(api_key);
+                let result = runtime.block_on(reconcile(&client, &history));
+                // Click counts are refetched as rows come into view
+                client.forget_cached_stats();
+                let rate_limited = result
+                    .as_ref()
+                    .err()
+                    .and_then(|e| e.downcast_ref::<ApiError>())
+                    .is_some_and(ApiError::is_rate_limited);
+                backoff = if rate_limited { (backoff + 1).min(MAX_BACKOFF) } else { 0 };
+
+                let mut state = shared.state.lock().unwrap();
+                state.running = false;
+                match &result {
+                    Ok(report) => {
+                        state.last_synced = Some(report.at);
+                        state.last_error = None;
+                    }
+                    Err(e) => {
+                        elog!("Background sync failed: {}", e);
+                        state.last_error = Some(e.to_string());
+                    }
+                }
+                state.report = Some((result.map_err(|e| e.to_string()), manual));
+                wait = jittered(state.interval * 2u32.pow(backoff));
+                drop(state);
+                ctx.request_repaint();
+            }
+        });
+    }
+}
+
+/// Up to a tenth of `interval` either way, so copies of the app sharing an
+/// account drift apart instead of syncing on the same minute.
+fn jittered(interval: Duration) -> Duration {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().subsec_nanos();
+    interval.mul_f64(0.9 + f64::from(nanos) / 1e9 * 0.2)
+}
+
+/// Roughly how long ago `at` was, e.g. "5 min ago".
+pub fn ago(at: DateTime<Utc>) -> String {
+    let minutes = (Utc::now() - at).num_minutes();
+    if minutes < 1 {
+        "just now".to_string()
+    } else if minutes < 60 {
+        format!("{} min ago", minutes)
+    } else if minutes < 24 * 60 {
+        format!("{} h ago", minutes / 60)
+    } else {
+        format!("{} days ago", minutes / (24 * 60))
+    }
+}
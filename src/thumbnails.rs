@@ -0,0 +1,115 @@
+use crate::storage;
+use eframe::egui;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+pub const WIDTH: u32 = 240;
+pub const HEIGHT: u32 = 126;
+
+enum Entry {
+    Loading,
+    Missing,
+    Loaded(egui::ColorImage),
+    Texture(egui::TextureHandle),
+}
+
+/// Small previews of destination pages for recognizing links at a glance.
+/// Taken from the page's share image, or from a screenshot service when one
+/// is configured, and kept in the data directory.
+#[derive(Clone, Default)]
+pub struct Thumbnails {
+    entries: Arc<Mutex<HashMap<String, Entry>>>,
+    /// Screenshot service URL with `{url}` where the destination goes.
+    service: Arc<Mutex<String>>,
+}
+
+impl Thumbnails {
+    pub fn set_service(&self, service: &str) {
+        *self.service.lock().unwrap() = service.trim().to_string();
+    }
+
+    /// The thumbnail of `url`, starting a fetch the first time it's asked for.
+    pub fn get(&self, url: &str, ctx: &egui::Context) -> Option<egui::TextureHandle> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.entry(url.to_string()).or_insert_with(|| {
+            match cache_path(url).and_then(|path| image::open(path).ok()) {
+                Some(image) => Entry::Loaded(color_image(image)),
+                None => {
+                    self.spawn_fetch(url.to_string(), ctx.clone());
+                    Entry::Loading
+                }
+            }
+        });
+        if let Entry::Loaded(image) = entry {
+            let texture = ctx.load_texture(format!("thumbnail-{}", url), image.clone(), Default::default());
+            *entry = Entry::Texture(texture);
+        }
+        match entry {
+            Entry::Texture(texture) => Some(texture.clone()),
+            _ => None,
+        }
+    }
+
+    fn spawn_fetch(&self, url: String, ctx: egui::Context) {
+        let entries = self.entries.clone();
+        let service = self.service.lock().unwrap().clone();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let entry = match runtime.block_on(fetch(&url, &service)) {
+                Ok(image) => {
+                    let image = image.resize_to_fill(WIDTH, HEIGHT, image::imageops::FilterType::Triangle);
+                    if let Some(path) = cache_path(&url) {
+                        if let Some(parent) = path.parent() {
+                            let _ = std::fs::create_dir_all(parent);
+                        }
+                        if let Err(e) = image.save_with_format(&path, image::ImageFormat::Png) {
+                            elog!("Failed to cache thumbnail of {}: {}", url, e);
+                        }
+                    }
+                    Entry::Loaded(color_image(image))
+                }
+                Err(e) => {
+                    elog!("No thumbnail for {}: {}", url, e);
+                    Entry::Missing
+                }
+            };
+            entries.lock().unwrap().insert(url, entry);
+            ctx.request_repaint();
+        });
+    }
+}
+
+async fn fetch(url: &str, service: &str) -> anyhow::Result<image::DynamicImage> {
+    let image_url = if service.is_empty() {
+        crate::og::image_url(url)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Page has no share image"))?
+    } else {
+        service.replace("{url}", &crate::slugs::encode(url))
+    };
+    let bytes = reqwest::Client::new()
+        .get(image_url)
+        .timeout(TIMEOUT)
+        .header("user-agent", concat!("shortyio/", env!("CARGO_PKG_VERSION")))
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    Ok(image::load_from_memory(&bytes)?)
+}
+
+fn color_image(image: image::DynamicImage) -> egui::ColorImage {
+    let rgba = image.to_rgba8();
+    let size = [rgba.width() as usize, rgba.height() as usize];
+    egui::ColorImage::from_rgba_unmultiplied(size, rgba.as_raw())
+}
+
+fn cache_path(url: &str) -> Option<std::path::PathBuf> {
+    let hash = Sha256::digest(url.as_bytes());
+    let name: String = hash[..12].iter().map(|b| format!("{:02x}", b)).collect();
+    storage::data_path(&format!("thumbnails/{}.png", name))
+}
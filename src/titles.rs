@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(8);
+/// Titles live in the `<head>`, so there is no need to read whole pages.
+const MAX_BYTES: usize = 256 * 1024;
+
+/// The `<title>` of the page at `url`, if it has one.
+pub async fn fetch(url: &str) -> anyhow::Result<Option<String>> {
+    let mut response = reqwest::Client::new()
+        .get(url)
+        .timeout(TIMEOUT)
+        .header("user-agent", concat!("shortyio/", env!("CARGO_PKG_VERSION")))
+        .send()
+        .await?
+        .error_for_status()?;
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        body.extend_from_slice(&chunk);
+        if body.len() >= MAX_BYTES || find_title(&String::from_utf8_lossy(&body)).is_some() {
+            break;
+        }
+    }
+    Ok(find_title(&String::from_utf8_lossy(&body)))
+}
+
+fn find_title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    let title = decode_entities(&html[start..end]);
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#039;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// Slack's `<url|title>` link markup, or a bare `<url>` without a title.
+pub fn slack_link(url: &str, title: Option<&str>) -> String {
+    let escape = |text: &str| text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    match title {
+        Some(title) => format!("<{}|{}>", escape(url), escape(title).replace('|', "-")),
+        None => format!("<{}>", escape(url)),
+    }
+}
@@ -0,0 +1,78 @@
+use crate::pins::PinnedLink;
+use crate::recent::RecentResult;
+use crate::share_sheet::ShareItem;
+use crate::trash::Trashed;
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+const SHORT: Duration = Duration::from_millis(2500);
+/// Long enough to notice a mistake and reach for Undo.
+const WITH_UNDO: Duration = Duration::from_secs(6);
+
+/// Puts back something removed from a list, at its old position.
+pub enum Undo {
+    Recent(RecentResult),
+    ShareItem(usize, ShareItem),
+    Pin(usize, PinnedLink),
+    /// Restored on the server, so this one runs in the background.
+    Trashed(Trashed),
+}
+
+struct Toast {
+    text: String,
+    undo: Option<Undo>,
+    expires_at: Instant,
+}
+
+/// Short-lived notes in the bottom-right corner, so confirmations don't
+/// push the rest of the layout around.
+#[derive(Default)]
+pub struct Toasts {
+    items: Vec<Toast>,
+}
+
+impl Toasts {
+    pub fn info(&mut self, text: impl Into<String>) {
+        self.push(text.into(), None, SHORT);
+    }
+
+    pub fn with_undo(&mut self, text: impl Into<String>, undo: Undo) {
+        self.push(text.into(), Some(undo), WITH_UNDO);
+    }
+
+    fn push(&mut self, text: String, undo: Option<Undo>, duration: Duration) {
+        // Repeating the same confirmation just extends it
+        self.items.retain(|toast| toast.text != text || toast.undo.is_some());
+        self.items.push(Toast {
+            text,
+            undo,
+            expires_at: Instant::now() + duration,
+        });
+    }
+
+    /// Draws the toasts and returns the undo the user asked for, if any.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<Undo> {
+        let now = Instant::now();
+        self.items.retain(|toast| toast.expires_at > now);
+        let next_expiry = self.items.iter().map(|toast| toast.expires_at).min()?;
+        ctx.request_repaint_after(next_expiry - now);
+
+        let mut undone = None;
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -12.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                for (i, toast) in self.items.iter().enumerate() {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(&toast.text);
+                            if toast.undo.is_some() && ui.small_button("Undo").clicked() {
+                                undone = Some(i);
+                            }
+                        });
+                    });
+                }
+            });
+        undone.and_then(|i| self.items.remove(i).undo)
+    }
+}
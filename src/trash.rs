@@ -0,0 +1,129 @@
+#[cfg(feature = "gui")]
+use crate::api::LinkResponse;
+use crate::api::{ApiClient, ApiError};
+use crate::storage;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+const TRASH_FILE: &str = "trash.json";
+
+pub fn default_days() -> u32 {
+    30
+}
+
+/// A deleted link, archived on short.io until it's restored or its time in
+/// the trash runs out.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Trashed {
+    pub link_id: String,
+    pub short_url: String,
+    pub original_url: String,
+    /// `audit::key_id` of the account the link belongs to.
+    pub key: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Held while reading and rewriting the file.
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn load() -> Vec<Trashed> {
+    storage::load_json(TRASH_FILE).unwrap_or_default()
+}
+
+fn save(items: &[Trashed]) {
+    if let Err(e) = storage::save_json(TRASH_FILE, &items) {
+        elog!("Failed to save trash: {}", e);
+    }
+}
+
+fn remove(link_id: &str) {
+    let _guard = LOCK.lock().unwrap();
+    let mut items = load();
+    items.retain(|item| item.link_id != link_id);
+    save(&items);
+}
+
+/// The account's trashed links, most recently deleted first.
+pub fn items(client: &ApiClient) -> Vec<Trashed> {
+    let key = client.key_id();
+    let _guard = LOCK.lock().unwrap();
+    let mut items: Vec<Trashed> = load().into_iter().filter(|item| item.key == key).collect();
+    items.sort_by_key(|item| std::cmp::Reverse(item.deleted_at));
+    items
+}
+
+/// Archives a link and keeps it in the trash instead of deleting it.
+pub async fn trash(client: &ApiClient, link_id: &str, short_url: &str, original_url: &str) -> Result<(), ApiError> {
+    client.archive_link(link_id, short_url).await?;
+    let _guard = LOCK.lock().unwrap();
+    let mut items = load();
+    items.retain(|item| item.link_id != link_id);
+    items.push(Trashed {
+        link_id: link_id.to_string(),
+        short_url: short_url.to_string(),
+        original_url: original_url.to_string(),
+        key: client.key_id(),
+        deleted_at: Utc::now(),
+    });
+    save(&items);
+    Ok(())
+}
+
+#[cfg(feature = "gui")]
+pub async fn restore(client: &ApiClient, item: &Trashed) -> Result<(), ApiError> {
+    client.unarchive_link(&item.link_id, &item.short_url).await?;
+    remove(&item.link_id);
+    Ok(())
+}
+
+pub async fn delete_now(client: &ApiClient, item: &Trashed) -> Result<(), ApiError> {
+    match client.delete_link(&item.link_id).await {
+        Ok(()) => {}
+        // Already deleted elsewhere, e.g. on the short.io dashboard
+        Err(ApiError::Status { status, .. }) if status == reqwest::StatusCode::NOT_FOUND => {}
+        Err(e) => return Err(e),
+    }
+    remove(&item.link_id);
+    Ok(())
+}
+
+/// Forgets every trashed link, overwriting the file before deleting it. The
+/// links stay archived on short.io.
+#[cfg(feature = "gui")]
+pub fn wipe() -> anyhow::Result<()> {
+    let _guard = LOCK.lock().unwrap();
+    storage::secure_delete(TRASH_FILE)
+}
+
+/// Something the trash window or My Links asked for.
+#[cfg(feature = "gui")]
+#[derive(Clone)]
+pub enum Action {
+    Trash(LinkResponse),
+    Restore(Trashed),
+    DeleteNow(Trashed),
+}
+
+#[cfg(feature = "gui")]
+impl Action {
+    pub async fn run(&self, client: &ApiClient) -> Result<(), ApiError> {
+        match self {
+            Action::Trash(link) => trash(client, &link.id, &link.short_url, &link.original_url).await,
+            Action::Restore(item) => restore(client, item).await,
+            Action::DeleteNow(item) => delete_now(client, item).await,
+        }
+    }
+}
+
+/// Deletes the account's links that have been in the trash longer than
+/// `days`, returning how many went.
+pub async fn purge(client: &ApiClient, days: u32) -> Result<usize, ApiError> {
+    let cutoff = Utc::now() - Duration::days(i64::from(days));
+    let mut purged = 0;
+    for item in items(client).iter().filter(|item| item.deleted_at < cutoff) {
+        delete_now(client, item).await?;
+        purged += 1;
+    }
+    Ok(purged)
+}
@@ -0,0 +1,58 @@
+#[cfg(feature = "gui")]
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::sync::RwLock;
+
+/// The resolved display zone; `None` until first used or set.
+static ZONE: RwLock<Option<Tz>> = RwLock::new(None);
+
+/// Picks the zone schedules are entered in and dates and stats are shown in,
+/// by IANA name such as `America/New_York`. Empty follows the system.
+pub fn set(name: &str) {
+    *ZONE.write().unwrap() = Some(resolve(name));
+}
+
+pub fn zone() -> Tz {
+    if let Some(zone) = *ZONE.read().unwrap() {
+        return zone;
+    }
+    let zone = resolve("");
+    *ZONE.write().unwrap() = Some(zone);
+    zone
+}
+
+/// The system's zone, or UTC if it can't be told.
+pub fn system() -> Tz {
+    iana_time_zone::get_timezone()
+        .ok()
+        .and_then(|name| name.parse().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+fn resolve(name: &str) -> Tz {
+    match name.trim() {
+        "" => system(),
+        name => name.parse().unwrap_or_else(|_| {
+            elog!("Unknown time zone {}, using the system's", name);
+            system()
+        }),
+    }
+}
+
+/// An API timestamp in the display zone.
+#[cfg(feature = "gui")]
+pub fn local(at: DateTime<Utc>) -> DateTime<Tz> {
+    at.with_timezone(&zone())
+}
+
+#[cfg(feature = "gui")]
+pub fn today() -> NaiveDate {
+    local(Utc::now()).date_naive()
+}
+
+/// A wall-clock time typed in the display zone, as UTC for the API. Times
+/// skipped or repeated by a DST change have no single answer and give `None`.
+#[cfg(feature = "gui")]
+pub fn to_utc(naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+    zone().from_local_datetime(&naive).single().map(|at| at.with_timezone(&Utc))
+}
@@ -0,0 +1,110 @@
+#[cfg(feature = "gui")]
+use eframe::egui;
+#[cfg(feature = "gui")]
+use serde::Deserialize;
+
+#[cfg(feature = "gui")]
+const RELEASES_URL: &str = "https://api.github.com/repos/KishCom/shortyio/releases/latest";
+
+#[cfg(feature = "gui")]
+#[derive(Deserialize, Clone)]
+pub struct Release {
+    #[serde(rename = "tag_name")]
+    pub version: String,
+    #[serde(rename = "html_url")]
+    pub url: String,
+}
+
+/// Packagers can build with `SHORTYIO_NO_UPDATE_CHECK=1` so the check
+/// defaults to off; users can still toggle it in settings.
+pub fn enabled_by_default() -> bool {
+    option_env!("SHORTYIO_NO_UPDATE_CHECK").is_none()
+}
+
+/// Looks up the latest GitHub release in the background and stores it under
+/// `update_available` when it is newer than this build.
+#[cfg(feature = "gui")]
+pub fn check(ctx: egui::Context) {
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let client = reqwest::Client::new();
+            let response = client
+                .get(RELEASES_URL)
+                .header("user-agent", concat!("shortyio/", env!("CARGO_PKG_VERSION")))
+                .header("accept", "application/vnd.github+json")
+                .send()
+                .await;
+
+            let release = match response {
+                Ok(resp) if resp.status().is_success() => resp.json::<Release>().await.ok(),
+                Ok(resp) => {
+                    elog!("Update check failed: {}", resp.status());
+                    None
+                }
+                Err(e) => {
+                    elog!("Update check failed: {}", e);
+                    None
+                }
+            };
+
+            if let Some(release) = release.filter(|r| is_newer(&r.version, env!("CARGO_PKG_VERSION"))) {
+                ctx.data_mut(|data| {
+                    data.insert_temp(egui::Id::new("update_available"), Some(release));
+                });
+                ctx.request_repaint();
+            }
+        });
+    });
+}
+
+#[cfg(feature = "gui")]
+fn is_newer(latest: &str, current: &str) -> bool {
+    fn parts(version: &str) -> Vec<u64> {
+        version
+            .trim_start_matches('v')
+            .split(['.', '-'])
+            .map_while(|part| part.parse().ok())
+            .collect()
+    }
+    parts(latest) > parts(current)
+}
+
+/// Whether this build can replace itself. macOS app bundles and builds
+/// without the `self-update` feature only link to the release page.
+#[cfg(feature = "gui")]
+pub fn can_self_update() -> bool {
+    cfg!(all(feature = "self-update", not(target_os = "macos")))
+}
+
+/// Downloads the latest release asset and replaces the running binary,
+/// reporting the outcome under `update_status`.
+#[cfg(all(feature = "gui", feature = "self-update", not(target_os = "macos")))]
+pub fn install(ctx: egui::Context) {
+    std::thread::spawn(move || {
+        let os = if cfg!(target_os = "windows") { "Windows" } else { "Linux" };
+        let result = self_update::backends::github::Update::configure()
+            .repo_owner("KishCom")
+            .repo_name("shortyio")
+            .bin_name("shortyio")
+            .target(&format!("{}-{}", os, std::env::consts::ARCH))
+            .bin_path_in_archive("{{ bin }}-{{ target }}/{{ bin }}")
+            .show_download_progress(false)
+            .no_confirm(true)
+            .current_version(env!("CARGO_PKG_VERSION"))
+            .build()
+            .and_then(|updater| updater.update());
+
+        let status = match result {
+            Ok(status) => format!("Updated to {}. Restart Shortyio to finish.", status.version()),
+            Err(e) => format!("Update failed: {}", e),
+        };
+        ctx.data_mut(|data| {
+            data.insert_temp(egui::Id::new("update_status"), Some(status));
+        });
+        ctx.request_repaint();
+    });
+}
+
+#[cfg(all(feature = "gui", not(all(feature = "self-update", not(target_os = "macos")))))]
+pub fn install(_ctx: egui::Context) {}
@@ -0,0 +1,30 @@
+/// Host part of an http(s) URL, lowercased.
+pub fn host(url: &str) -> String {
+    let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    host.split(':').next().unwrap_or_default().to_lowercase()
+}
+
+/// The first http(s) URL anywhere in `text`, e.g. inside a copied sentence
+/// or an HTML `href` attribute.
+#[cfg(feature = "gui")]
+pub fn find_url(text: &str) -> Option<&str> {
+    let start = match (text.find("http://"), text.find("https://")) {
+        (Some(a), Some(b)) => a.min(b),
+        (a, b) => a.or(b)?,
+    };
+    let rest = &text[start..];
+    let end = rest
+        .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '<' | '>' | '`'))
+        .unwrap_or(rest.len());
+    let url = rest[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']']);
+    (url.len() > "https://".len()).then_some(url)
+}
+
+/// Path of a URL without the leading slash, query or fragment.
+pub fn path(url: &str) -> &str {
+    let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let path = rest.split_once('/').map(|(_, path)| path).unwrap_or_default();
+    path.split(['?', '#']).next().unwrap_or_default()
+}
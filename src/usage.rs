@@ -0,0 +1,87 @@
+use crate::storage;
+#[cfg(feature = "gui")]
+use crate::storage::csv_field;
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+#[cfg(feature = "gui")]
+use std::path::Path;
+use std::sync::Mutex;
+
+const USAGE_FILE: &str = "usage.json";
+
+/// Purely local counters of links created, kept separately from history so
+/// they survive history pruning. Nothing here ever leaves the machine.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Usage {
+    #[serde(default)]
+    pub days: BTreeMap<NaiveDate, u32>,
+    /// Keyed by profile name, so profiles sharing a domain count apart.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub domains: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub tags: BTreeMap<String, u32>,
+}
+
+static LOCK: Mutex<()> = Mutex::new(());
+
+impl Usage {
+    pub fn load() -> Self {
+        storage::load_json(USAGE_FILE).unwrap_or_default()
+    }
+
+    /// Counts one created link against today, its profile, its short domain
+    /// and its tags.
+    pub fn record(profile: &str, domain: &str, tags: &[String]) {
+        let _guard = LOCK.lock().unwrap();
+        let mut usage = Self::load();
+        *usage.days.entry(Local::now().date_naive()).or_default() += 1;
+        *usage.profiles.entry(profile.to_string()).or_default() += 1;
+        *usage.domains.entry(domain.to_string()).or_default() += 1;
+        for tag in tags {
+            *usage.tags.entry(tag.clone()).or_default() += 1;
+        }
+        if let Err(e) = storage::save_json(USAGE_FILE, &usage) {
+            elog!("Failed to save usage stats: {}", e);
+        }
+    }
+
+    /// Links created on each of the last `days` days, oldest first.
+    #[cfg(feature = "gui")]
+    pub fn last_days(&self, days: i64) -> Vec<(NaiveDate, u32)> {
+        let today = Local::now().date_naive();
+        (0..days)
+            .rev()
+            .map(|offset| {
+                let date = today - chrono::Duration::days(offset);
+                (date, self.days.get(&date).copied().unwrap_or(0))
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn total(&self) -> u32 {
+        self.days.values().sum()
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn export_csv(&self, path: &Path) -> std::io::Result<()> {
+        let mut rows = vec!["kind,key,links".to_string()];
+        for (date, count) in &self.days {
+            rows.push(format!("day,{},{}", date, count));
+        }
+        for (profile, count) in &self.profiles {
+            rows.push(format!("profile,{},{}", csv_field(profile), count));
+        }
+        for (domain, count) in &self.domains {
+            rows.push(format!("domain,{},{}", csv_field(domain), count));
+        }
+        for (tag, count) in &self.tags {
+            rows.push(format!("tag,{},{}", csv_field(tag), count));
+        }
+        rows.push(String::new());
+        std::fs::write(path, rows.join("\n"))
+    }
+}
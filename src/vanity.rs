@@ -0,0 +1,87 @@
+use crate::api::{ApiClient, ApiError};
+use crate::titles;
+use crate::urls;
+
+/// Longest suggested path, before the profile's prefix.
+const MAX_LEN: usize = 24;
+const MAX_SUGGESTIONS: usize = 4;
+/// Words that make a path longer without making it more memorable.
+const STOP_WORDS: &[&str] = &["a", "an", "and", "at", "for", "in", "of", "on", "or", "the", "to", "with"];
+
+/// Custom paths derived from the destination's page title and URL that are
+/// still free on `hostname`. Without a hostname availability isn't checked.
+pub async fn suggest(client: &ApiClient, hostname: &str, prefix: &str, url: &str) -> anyhow::Result<Vec<String>> {
+    let title = titles::fetch(url).await.unwrap_or_else(|e| {
+        elog!("Failed to fetch title of {}: {}", url, e);
+        None
+    });
+
+    let mut available = Vec::new();
+    for path in candidates(title.as_deref(), url) {
+        if available.len() == MAX_SUGGESTIONS {
+            break;
+        }
+        if hostname.is_empty() {
+            available.push(path);
+            continue;
+        }
+        match client.expand_link(hostname, &format!("{}{}", prefix, path)).await {
+            Err(ApiError::Status { status, .. }) if status.as_u16() == 404 => available.push(path),
+            Err(e) => return Err(e.into()),
+            Ok(_) => {}
+        }
+    }
+    Ok(available)
+}
+
+fn candidates(title: Option<&str>, url: &str) -> Vec<String> {
+    let mut candidates = Vec::new();
+    if let Some(title) = title {
+        // Site names usually follow a separator: "Spring Sale | Example Shop".
+        let title = title.split(['|', '—', '–']).next().unwrap_or(title);
+        let title = title.split(" - ").next().unwrap_or(title);
+        candidates.push(kebab(title, MAX_LEN));
+        candidates.push(kebab(&title.split_whitespace().take(2).collect::<Vec<_>>().join(" "), MAX_LEN));
+    }
+    let segment = urls::path(url).trim_end_matches('/').rsplit('/').next().unwrap_or_default();
+    let segment = segment.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(segment);
+    candidates.push(kebab(segment, MAX_LEN));
+    let host = urls::host(url);
+    let host = host.trim_start_matches("www.");
+    let site = host.split('.').next().unwrap_or_default();
+    if !segment.is_empty() {
+        candidates.push(kebab(&format!("{} {}", site, segment), MAX_LEN));
+    }
+
+    let mut unique = Vec::new();
+    for candidate in candidates {
+        if candidate.len() >= 3 && !unique.contains(&candidate) {
+            unique.push(candidate);
+        }
+    }
+    unique
+}
+
+/// Lowercase words joined with `-`, cut at a word boundary to `max_len`.
+pub fn kebab(text: &str, max_len: usize) -> String {
+    let words = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()));
+    let mut path = String::new();
+    for word in words {
+        let len = path.chars().count() + word.chars().count() + usize::from(!path.is_empty());
+        if len > max_len {
+            if path.is_empty() {
+                path = word.chars().take(max_len).collect();
+            }
+            break;
+        }
+        if !path.is_empty() {
+            path.push('-');
+        }
+        path.push_str(&word);
+    }
+    path
+}
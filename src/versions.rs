@@ -0,0 +1,59 @@
+use crate::storage;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+const VERSIONS_FILE: &str = "versions.json";
+
+/// A destination change made through the app.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Version {
+    pub at: DateTime<Utc>,
+    pub from: String,
+    pub to: String,
+}
+
+/// Held while reading and rewriting the file, since edits can finish on
+/// several worker threads at once.
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn load() -> HashMap<String, Vec<Version>> {
+    storage::load_json(VERSIONS_FILE).unwrap_or_default()
+}
+
+pub fn record(link_id: &str, from: &str, to: &str) {
+    if from == to {
+        return;
+    }
+    let _guard = LOCK.lock().unwrap();
+    let mut all = load();
+    all.entry(link_id.to_string()).or_default().push(Version {
+        at: Utc::now(),
+        from: from.to_string(),
+        to: to.to_string(),
+    });
+    if let Err(e) = storage::save_json(VERSIONS_FILE, &all) {
+        elog!("Failed to save link versions: {}", e);
+    }
+}
+
+/// Forgets every recorded change, overwriting the file before deleting it.
+#[cfg(feature = "gui")]
+pub fn wipe() -> anyhow::Result<()> {
+    let _guard = LOCK.lock().unwrap();
+    storage::secure_delete(VERSIONS_FILE)
+}
+
+/// Changes to a link's destination, newest first.
+pub fn of(link_id: &str) -> Vec<Version> {
+    let _guard = LOCK.lock().unwrap();
+    let mut versions = load().remove(link_id).unwrap_or_default();
+    versions.reverse();
+    versions
+}
+
+/// The destination the app last set for a link, if it ever changed one.
+pub fn latest(link_id: &str) -> Option<String> {
+    of(link_id).into_iter().next().map(|version| version.to)
+}
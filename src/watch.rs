@@ -0,0 +1,138 @@
+use crate::api::{ApiClient, CreateLinkRequest};
+use crate::storage::csv_field;
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const POLL: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Default)]
+pub struct WatchStatus {
+    pub shortened: usize,
+    pub last_error: Option<String>,
+}
+
+/// Polls a text/CSV file and shortens every URL appended to it, writing
+/// `original,short` rows to a companion `*.shortened.csv` file.
+pub struct FileWatcher {
+    pub path: PathBuf,
+    pub output: PathBuf,
+    status: Arc<Mutex<WatchStatus>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl FileWatcher {
+    pub fn start(path: PathBuf, api_key: String, domain: Option<String>, ctx: eframe::egui::Context) -> Self {
+        let output = companion_path(&path);
+        let status = Arc::new(Mutex::new(WatchStatus::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let watcher = Self {
+            path: path.clone(),
+            output: output.clone(),
+            status: status.clone(),
+            stop: stop.clone(),
+        };
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let client = ApiClient::new(api_key);
+            // Only lines appended after the watch starts are shortened
+            let mut offset = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            let mut partial = String::new();
+
+            while !stop.load(Ordering::Relaxed) {
+                std::thread::sleep(POLL);
+
+                let len = match fs::metadata(&path) {
+                    Ok(meta) => meta.len(),
+                    Err(e) => {
+                        status.lock().unwrap().last_error = Some(format!("Cannot read {}: {}", path.display(), e));
+                        continue;
+                    }
+                };
+                if len < offset {
+                    // The file was truncated or rewritten; start over
+                    offset = 0;
+                    partial.clear();
+                }
+                if len == offset {
+                    continue;
+                }
+
+                let appended = match read_from(&path, offset) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        status.lock().unwrap().last_error = Some(format!("Cannot read {}: {}", path.display(), e));
+                        continue;
+                    }
+                };
+                offset += appended.len() as u64;
+                partial.push_str(&appended);
+
+                // Keep an unterminated last line until the writer finishes it
+                let complete = match partial.rfind('\n') {
+                    Some(end) => partial.drain(..=end).collect::<String>(),
+                    None => continue,
+                };
+
+                for url in complete.lines().filter_map(url_in_line) {
+                    let request = CreateLinkRequest::simple(url.to_string(), domain.clone());
+                    let result = runtime.block_on(client.create_link(&request));
+                    let mut status = status.lock().unwrap();
+                    match result {
+                        Ok(link) => match append_row(&output, &link.original_url, &link.short_url) {
+                            Ok(()) => {
+                                status.shortened += 1;
+                                status.last_error = None;
+                            }
+                            Err(e) => status.last_error = Some(format!("Cannot write {}: {}", output.display(), e)),
+                        },
+                        Err(e) => status.last_error = Some(format!("{}: {}", url, e)),
+                    }
+                }
+                ctx.request_repaint();
+            }
+        });
+
+        watcher
+    }
+
+    pub fn status(&self) -> WatchStatus {
+        self.status.lock().unwrap().clone()
+    }
+}
+
+impl Drop for FileWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn companion_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("links");
+    path.with_file_name(format!("{}.shortened.csv", stem))
+}
+
+fn read_from(path: &Path, offset: u64) -> std::io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut text = String::new();
+    file.read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// Returns the first CSV field of a line that looks like a URL.
+fn url_in_line(line: &str) -> Option<&str> {
+    line.split(',')
+        .map(|field| field.trim().trim_matches('"'))
+        .find(|field| field.starts_with("http://") || field.starts_with("https://"))
+}
+
+fn append_row(output: &Path, original: &str, short: &str) -> std::io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(output)?;
+    writeln!(file, "{},{}", csv_field(original), csv_field(short))
+}
@@ -0,0 +1,54 @@
+use crate::api::LinkResponse;
+use chrono::Utc;
+use serde::Serialize;
+use std::sync::RwLock;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Serialize)]
+struct Payload<'a> {
+    event: &'static str,
+    link_id: &'a str,
+    short_url: &'a str,
+    original_url: &'a str,
+    tags: &'a [String],
+    profile: &'a str,
+    created_at: String,
+}
+
+/// Webhook URL and profile name of the active profile.
+static TARGET: RwLock<(String, String)> = RwLock::new((String::new(), String::new()));
+
+pub fn configure(url: &str, profile: &str) {
+    *TARGET.write().unwrap() = (url.trim().to_string(), profile.to_string());
+}
+
+/// POSTs the new link to the configured webhook. Failures are only logged,
+/// and a slow endpoint is cut off so it never holds up link creation for long.
+pub async fn notify(link: &LinkResponse, tags: &[String]) {
+    let (url, profile) = TARGET.read().unwrap().clone();
+    if url.is_empty() {
+        return;
+    }
+    let payload = Payload {
+        event: "link.created",
+        link_id: &link.id,
+        short_url: &link.short_url,
+        original_url: &link.original_url,
+        tags,
+        profile: &profile,
+        created_at: Utc::now().to_rfc3339(),
+    };
+    let result = reqwest::Client::new()
+        .post(&url)
+        .timeout(TIMEOUT)
+        .header("user-agent", concat!("shortyio/", env!("CARGO_PKG_VERSION")))
+        .json(&payload)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
+    if let Err(e) = result {
+        elog!("Webhook to {} failed: {}", url, e);
+    }
+}
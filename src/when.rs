@@ -0,0 +1,148 @@
+use crate::tz;
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
+
+/// Reads a time typed the way people say it, in the display time zone:
+/// `in 2 weeks`, `tomorrow`, `friday 5pm`, `next monday 9:30`, `end of month`
+/// or an exact `2025-06-30 18:00`. A day without a time means its end.
+pub fn parse(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim().to_lowercase();
+    let now = tz::local(Utc::now()).naive_local();
+    let words: Vec<&str> = text.split_whitespace().collect();
+
+    if let Some(at) = exact(&text) {
+        return tz::to_utc(at);
+    }
+    let at = match words[..] {
+        ["in", amount, unit] | [amount, unit, "from", "now"] => {
+            let amount: u32 = match amount {
+                "a" | "an" | "one" => 1,
+                amount => amount.parse().ok()?,
+            };
+            after(now, amount, unit)?
+        }
+        ["end", "of", "day"] | ["tonight"] => end_of(now.date()),
+        ["end", "of", "week"] => end_of(weekday_after(now.date(), Weekday::Sun, true)),
+        ["end", "of", "month"] => end_of(last_of_month(now.date())),
+        ["end", "of", "year"] => end_of(NaiveDate::from_ymd_opt(now.year(), 12, 31)?),
+        ["end", "of", "next", "month"] => end_of(last_of_month(now.date().checked_add_months(Months::new(1))?)),
+        _ => {
+            let (day, time) = day_and_time(&words, now)?;
+            match time {
+                Some(time) => day.and_time(time),
+                None => end_of(day),
+            }
+        }
+    };
+    tz::to_utc(at)
+}
+
+/// `YYYY-MM-DD HH:MM` or just `YYYY-MM-DD`.
+fn exact(text: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M")
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok().map(end_of))
+}
+
+fn after(now: NaiveDateTime, amount: u32, unit: &str) -> Option<NaiveDateTime> {
+    let unit = unit.trim_end_matches('s');
+    let amount64 = i64::from(amount);
+    match unit {
+        "minute" | "min" => now.checked_add_signed(Duration::minutes(amount64)),
+        "hour" | "hr" => now.checked_add_signed(Duration::hours(amount64)),
+        "day" => now.checked_add_signed(Duration::days(amount64)),
+        "week" => now.checked_add_signed(Duration::weeks(amount64)),
+        "month" => now.checked_add_months(Months::new(amount)),
+        "year" => now.checked_add_months(Months::new(amount.checked_mul(12)?)),
+        _ => None,
+    }
+}
+
+/// A day word (`today`, `tomorrow`, `friday`, `next friday`) followed by an
+/// optional time, or a time alone meaning its next occurrence.
+fn day_and_time(words: &[&str], now: NaiveDateTime) -> Option<(NaiveDate, Option<NaiveTime>)> {
+    let today = now.date();
+    let (day, rest) = match words {
+        ["today", rest @ ..] => (Some(today), rest),
+        ["tomorrow", rest @ ..] => (Some(today.succ_opt()?), rest),
+        ["next", "week", rest @ ..] => (Some(today + Duration::weeks(1)), rest),
+        ["next", name, rest @ ..] | [name, rest @ ..] if weekday(name).is_some() => {
+            (Some(weekday_after(today, weekday(name)?, false)), rest)
+        }
+        rest => (None, rest),
+    };
+    let rest = match rest {
+        ["at", rest @ ..] => rest,
+        rest => rest,
+    };
+    let time = match rest {
+        [] => None,
+        _ => Some(time_of_day(&rest.concat())?),
+    };
+    match (day, time) {
+        (Some(day), time) => Some((day, time)),
+        // A time that's already passed today means tomorrow's
+        (None, Some(time)) if time > now.time() => Some((today, Some(time))),
+        (None, Some(time)) => Some((today.succ_opt()?, Some(time))),
+        (None, None) => None,
+    }
+}
+
+/// `5pm`, `5:30pm`, `17:00` or `noon`.
+fn time_of_day(text: &str) -> Option<NaiveTime> {
+    if text == "noon" {
+        return NaiveTime::from_hms_opt(12, 0, 0);
+    }
+    let (clock, offset) = if let Some(clock) = text.strip_suffix("am") {
+        (clock, Some(0))
+    } else if let Some(clock) = text.strip_suffix("pm") {
+        (clock, Some(12))
+    } else {
+        (text, None)
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        None => (clock.parse::<u32>().ok()?, 0),
+    };
+    let hour = match offset {
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        Some(_) => return None,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// A day by name, or its first three or more letters (`fri`, `thurs`).
+fn weekday(name: &str) -> Option<Weekday> {
+    const NAMES: [(&str, Weekday); 7] = [
+        ("monday", Weekday::Mon),
+        ("tuesday", Weekday::Tue),
+        ("wednesday", Weekday::Wed),
+        ("thursday", Weekday::Thu),
+        ("friday", Weekday::Fri),
+        ("saturday", Weekday::Sat),
+        ("sunday", Weekday::Sun),
+    ];
+    if name.len() < 3 {
+        return None;
+    }
+    NAMES.iter().find(|(full, _)| full.starts_with(name)).map(|(_, day)| *day)
+}
+
+/// The next `day` after `from`, or `from` itself if it is that day and
+/// `inclusive` is set.
+fn weekday_after(from: NaiveDate, day: Weekday, inclusive: bool) -> NaiveDate {
+    let ahead = (day.num_days_from_monday() + 7 - from.weekday().num_days_from_monday()) % 7;
+    let ahead = if ahead == 0 && !inclusive { 7 } else { ahead };
+    from + Duration::days(i64::from(ahead))
+}
+
+fn last_of_month(day: NaiveDate) -> NaiveDate {
+    day.with_day(1)
+        .and_then(|first| first.checked_add_months(Months::new(1)))
+        .and_then(|next| next.pred_opt())
+        .unwrap_or(day)
+}
+
+fn end_of(day: NaiveDate) -> NaiveDateTime {
+    day.and_hms_opt(23, 59, 0).unwrap_or_default()
+}